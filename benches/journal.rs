@@ -0,0 +1,40 @@
+use assert_fs::TempDir;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use time::macros::date;
+
+use journal::{carry_over, seed, Journal};
+
+/// Roughly a month, a year, and a decade of daily entries
+const SIZES: &[u32] = &[30, 365, 3650];
+
+fn seeded(days: u32) -> (TempDir, Journal) {
+    let dir = TempDir::new().unwrap();
+    let journal = Journal::new_at(dir.path());
+    seed(&journal, date!(2022 - 03 - 11), days).unwrap();
+
+    (dir, journal)
+}
+
+fn latest_entry(c: &mut Criterion) {
+    let mut group = c.benchmark_group("latest_entry");
+
+    for days in SIZES {
+        let (_dir, journal) = seeded(*days);
+
+        group.bench_with_input(BenchmarkId::from_parameter(days), &journal, |b, journal| {
+            b.iter(|| journal.latest_entry().unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn carrying_over_todos(c: &mut Criterion) {
+    let (_dir, journal) = seeded(30);
+    let latest = journal.latest_entry().unwrap().unwrap();
+
+    c.bench_function("carry_over", |b| b.iter(|| carry_over(&latest.markdown)));
+}
+
+criterion_group!(benches, latest_entry, carrying_over_todos);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+use tabled::object::Segment;
+use tabled::{Alignment, Modify, Style, Table};
+
+/// Typographic characters we use in entries and their plain ASCII counterparts
+const REPLACEMENTS: &[(char, &str)] = &[
+    ('…', "..."),
+    ('–', "-"),
+    ('—', "-"),
+    ('‘', "'"),
+    ('’', "'"),
+    ('“', "\""),
+    ('”', "\""),
+];
+
+/// Styles tables for the terminal, using only ASCII characters if asked to
+pub fn styled(table: Table, ascii: bool) -> Table {
+    let table = if ascii {
+        table.with(Style::ascii())
+    } else {
+        table.with(Style::modern())
+    };
+
+    table.with(Modify::new(Segment::all()).with(Alignment::left()))
+}
+
+/// Replaces the typographic characters that sections produce with ASCII ones
+pub fn plain(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    for c in text.chars() {
+        match REPLACEMENTS.iter().find(|(from, _)| *from == c) {
+            Some((_, to)) => plain.push_str(to),
+            None => plain.push(c),
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tabled::Tabled;
+
+    #[test]
+    fn replaces_typographic_characters() {
+        assert_eq!(
+            plain("* [ ] Review “the PR”\n…and 3 more"),
+            "* [ ] Review \"the PR\"\n...and 3 more"
+        );
+    }
+
+    #[test]
+    fn ascii_tables_have_no_box_drawing_characters() {
+        #[derive(Tabled)]
+        struct Row {
+            nr: usize,
+        }
+
+        let table = styled(Table::new(&[Row { nr: 1 }]), true).to_string();
+
+        assert!(table.is_ascii());
+    }
+}
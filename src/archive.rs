@@ -0,0 +1,88 @@
+use anyhow::{Context, Result};
+use clap::StructOpt;
+use time::Date;
+
+use crate::edit::DryRun;
+use crate::signing::signature_of;
+use crate::storage::{date_from_filename, parse_date, Journal};
+
+#[derive(Debug, StructOpt)]
+pub struct ArchiveCmd {
+    /// Archive the entries from before this day, e.g. 2023-01-01
+    #[clap(long, parse(try_from_str = parse_date))]
+    before: Date,
+
+    #[clap(flatten)]
+    dry_run: DryRun,
+}
+
+impl ArchiveCmd {
+    /// Moves old entries, along with their signatures, into `archive/<year>/`
+    pub(crate) fn execute(&self, journal: &Journal) -> Result<()> {
+        let mut archived = 0;
+        for path in journal.entries()? {
+            let date = match date_from_filename(&path) {
+                Some(date) if date < self.before && !journal.is_archived(&path) => date,
+                _ => continue,
+            };
+
+            let archived_path = journal.archived(&path, date.year());
+            if let Some(dir) = archived_path
+                .parent()
+                .filter(|_| !self.dry_run.is_dry_run())
+            {
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("Could not create {:?}", dir))?;
+            }
+
+            self.dry_run.rename(&path, &archived_path)?;
+            let signature = signature_of(&path);
+            if signature.exists() {
+                self.dry_run
+                    .rename(&signature, &signature_of(&archived_path))?;
+            }
+            archived += 1;
+        }
+
+        if !self.dry_run.is_dry_run() {
+            println!("Archived {} entries", archived);
+        }
+        self.dry_run.finish(archived)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+    use predicates::{path::exists, prelude::*};
+    use time::macros::date;
+
+    #[test]
+    fn moves_old_entries_into_a_folder_per_year() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("2021-12-31-old.md").write_str("# Old")?;
+        dir.child("2021-12-31-old.md.sig").write_str("signed")?;
+        dir.child("2022-03-11-recent.md").write_str("# Recent")?;
+        let journal = Journal::new_at(dir.path());
+
+        ArchiveCmd {
+            before: date!(2022 - 01 - 01),
+            dry_run: DryRun::default(),
+        }
+        .execute(&journal)?;
+
+        dir.child("archive/2021/2021-12-31-old.md").assert(exists());
+        dir.child("archive/2021/2021-12-31-old.md.sig")
+            .assert(exists());
+        dir.child("2021-12-31-old.md").assert(exists().not());
+        assert_eq!(
+            journal.entries()?,
+            vec![dir.path().join("2022-03-11-recent.md")]
+        );
+
+        let everything = Journal::new_at(dir.path()).including_archive(true);
+        assert_eq!(everything.entries()?.len(), 2);
+        Ok(())
+    }
+}
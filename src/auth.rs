@@ -0,0 +1,345 @@
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use clap::StructOpt;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize, Serializer};
+use tabled::{Table, Tabled};
+
+use crate::accessibility;
+use crate::Config;
+
+#[derive(Debug, StructOpt)]
+pub enum AuthCmd {
+    /// Check that the credentials of every enabled integration can be found
+    Status,
+}
+
+impl AuthCmd {
+    pub fn execute(&self, config: &Config) -> Result<()> {
+        match self {
+            AuthCmd::Status => {
+                #[derive(Tabled)]
+                struct Row {
+                    integration: &'static str,
+                    source: String,
+                    expires: String,
+                    status: String,
+                }
+
+                let mut broken = 0;
+                let mut rows = Vec::new();
+                for (integration, token) in config.tokens() {
+                    let status = match token.resolve() {
+                        Ok(_) => "ok".to_string(),
+                        Err(e) => {
+                            broken += 1;
+                            format!("{:#}", e)
+                        }
+                    };
+                    rows.push(Row {
+                        integration,
+                        source: format!("{:?}", token),
+                        expires: token.expiry(),
+                        status,
+                    });
+                }
+
+                println!("{}", accessibility::styled(Table::new(&rows), config.ascii));
+
+                if broken > 0 {
+                    bail!("{} integrations are missing their credentials", broken);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A token or password for a remote integration.
+/// It can be written directly into the config, read from an environment variable,
+/// be the output of a command like a password manager, or be kept in the keychain of the operating system.
+#[derive(Clone, Deserialize)]
+#[serde(from = "Source")]
+pub struct Token {
+    source: Source,
+    cache: Arc<Mutex<Option<Cached>>>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+#[serde(untagged)]
+enum Source {
+    #[serde(serialize_with = "only_asterisk")]
+    Inline(Secret<String>),
+    Env {
+        env: String,
+    },
+    Command {
+        command: String,
+        /// Run the command again once the token it returned is this old
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_after_minutes: Option<u64>,
+    },
+    /// An entry like `journal/github`, its service and account, in the macOS keychain
+    /// or the Secret Service on Linux through `secret-tool`
+    Keychain {
+        keychain: String,
+    },
+}
+
+struct Cached {
+    token: Secret<String>,
+    fetched: Instant,
+}
+
+fn only_asterisk<S>(_: &Secret<String>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str("***")
+}
+
+impl From<Source> for Token {
+    fn from(source: Source) -> Self {
+        Token {
+            source,
+            cache: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl Serialize for Token {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.source.serialize(serializer)
+    }
+}
+
+/// Shows where the token comes from, never the token itself
+impl std::fmt::Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.source {
+            Source::Inline(_) => f.write_str("***"),
+            Source::Env { ref env } => write!(f, "${}", env),
+            Source::Command { ref command, .. } => write!(f, "`{}`", command),
+            Source::Keychain { ref keychain } => write!(f, "keychain {}", keychain),
+        }
+    }
+}
+
+impl Token {
+    /// The current value of the token, running the command again if the last one has expired
+    pub fn resolve(&self) -> Result<Secret<String>> {
+        match self.source {
+            Source::Inline(ref token) => Ok(token.clone()),
+            Source::Env { ref env } => std::env::var(env)
+                .map(Secret::new)
+                .with_context(|| format!("${} is not set", env)),
+            Source::Command {
+                ref command,
+                expires_after_minutes,
+            } => {
+                let mut cache = self.cache.lock().unwrap();
+                if let Some(ref cached) = *cache {
+                    let expired = expires_after_minutes
+                        .map(|minutes| {
+                            cached.fetched.elapsed() >= Duration::from_secs(minutes * 60)
+                        })
+                        .unwrap_or(false);
+                    if !expired {
+                        return Ok(cached.token.clone());
+                    }
+                    tracing::info!("Token from `{}` expired, refreshing it", command);
+                }
+
+                let token = run(command)?;
+                *cache = Some(Cached {
+                    token: token.clone(),
+                    fetched: Instant::now(),
+                });
+                Ok(token)
+            }
+            Source::Keychain { ref keychain } => {
+                // The keychain may ask for permission each time, so only look once
+                let mut cache = self.cache.lock().unwrap();
+                if let Some(ref cached) = *cache {
+                    return Ok(cached.token.clone());
+                }
+
+                let token = read_token(keychain_lookup(keychain)?, keychain)?;
+                *cache = Some(Cached {
+                    token: token.clone(),
+                    fetched: Instant::now(),
+                });
+                Ok(token)
+            }
+        }
+    }
+
+    /// `resolve`s the token on a thread of its own, so that a slow command or keychain
+    /// doesn't hold up everything else, like the timeout of a section
+    pub async fn resolve_in_background(&self) -> Result<Secret<String>> {
+        let token = self.clone();
+        tokio::task::spawn_blocking(move || token.resolve())
+            .await
+            .context("Could not resolve the token")?
+    }
+
+    /// A human readable description of when the token is fetched again
+    fn expiry(&self) -> String {
+        match self.source {
+            Source::Command {
+                expires_after_minutes: Some(minutes),
+                ..
+            } => format!("refreshed after {} minutes", minutes),
+            _ => "never".to_string(),
+        }
+    }
+}
+
+fn run(command: &str) -> Result<Secret<String>> {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]);
+    read_token(shell, command)
+}
+
+/// The token `command` prints, with `description` standing in for it in errors
+fn read_token(mut command: Command, description: &str) -> Result<Secret<String>> {
+    let output = command
+        .output()
+        .with_context(|| format!("Could not run `{}`", description))?;
+
+    if !output.status.success() {
+        bail!("`{}` failed with {}", description, output.status);
+    }
+
+    let token = String::from_utf8(output.stdout)
+        .with_context(|| format!("`{}` did not print valid UTF-8", description))?;
+    let token = token.trim();
+    if token.is_empty() {
+        bail!("`{}` did not print a token", description);
+    }
+
+    Ok(Secret::new(token.to_string()))
+}
+
+/// The command that prints the password of a keychain `entry` written as `service/account`
+#[cfg(target_os = "macos")]
+fn keychain_lookup(entry: &str) -> Result<Command> {
+    let (service, account) = keychain_entry(entry)?;
+    let mut security = Command::new("security");
+    security.args(["find-generic-password", "-s", service, "-a", account, "-w"]);
+    Ok(security)
+}
+
+/// The command that prints the password of a keychain `entry` written as `service/account`
+#[cfg(all(unix, not(target_os = "macos")))]
+fn keychain_lookup(entry: &str) -> Result<Command> {
+    let (service, account) = keychain_entry(entry)?;
+    let mut secret_tool = Command::new("secret-tool");
+    secret_tool.args(["lookup", "service", service, "account", account]);
+    Ok(secret_tool)
+}
+
+#[cfg(not(unix))]
+fn keychain_lookup(entry: &str) -> Result<Command> {
+    bail!(
+        "Reading '{}' from the keychain is only supported on macOS and Linux, use a `command` instead",
+        entry
+    )
+}
+
+fn keychain_entry(entry: &str) -> Result<(&str, &str)> {
+    match entry.split_once('/') {
+        Some((service, account)) if !service.is_empty() && !account.is_empty() => {
+            Ok((service, account))
+        }
+        _ => bail!(
+            "The keychain entry '{}' needs a service and an account, like `journal/github`",
+            entry
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use secrecy::ExposeSecret;
+
+    #[test]
+    fn never_shows_inline_tokens() -> Result<()> {
+        let token: Token = serde_yaml::from_str("abc")?;
+
+        assert_eq!(token.resolve()?.expose_secret(), "abc");
+        assert_eq!(format!("{:?}", token), "***");
+        assert_eq!(serde_yaml::to_string(&token)?.trim(), "---\n\"***\"");
+
+        Ok(())
+    }
+
+    #[test]
+    fn shows_where_other_tokens_come_from() -> Result<()> {
+        let token: Token = serde_yaml::from_str("env: JOURNAL_TEST_MISSING_TOKEN")?;
+
+        assert_eq!(format!("{:?}", token), "$JOURNAL_TEST_MISSING_TOKEN");
+        assert_eq!(
+            token.resolve().unwrap_err().to_string(),
+            "$JOURNAL_TEST_MISSING_TOKEN is not set"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn runs_the_command_again_once_the_token_expired() -> Result<()> {
+        let dir = TempDir::new()?;
+        let counter = dir.path().join("counter");
+        let command = format!("echo x >> {0}; wc -l < {0}", counter.to_string_lossy());
+
+        let cached: Token = serde_yaml::from_str(&format!("command: \"{}\"", command))?;
+        assert_eq!(cached.resolve()?.expose_secret(), "1");
+        assert_eq!(cached.resolve()?.expose_secret(), "1");
+
+        let expiring: Token = serde_yaml::from_str(&format!(
+            indoc! {r#"
+            command: "{}"
+            expires_after_minutes: 0
+            "#},
+            command
+        ))?;
+        assert_eq!(expiring.resolve()?.expose_secret(), "2");
+        assert_eq!(expiring.resolve()?.expose_secret(), "3");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn a_slow_command_does_not_hold_up_timeouts() -> Result<()> {
+        let slow: Token = serde_yaml::from_str("command: sleep 1; echo abc")?;
+
+        let waited =
+            tokio::time::timeout(Duration::from_millis(50), slow.resolve_in_background()).await;
+        assert!(waited.is_err());
+        assert_eq!(slow.resolve_in_background().await?.expose_secret(), "abc");
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_tokens_from_the_keychain() -> Result<()> {
+        let token: Token = serde_yaml::from_str("keychain: journal-test/missing")?;
+        assert_eq!(format!("{:?}", token), "keychain journal-test/missing");
+        assert!(token.resolve().is_err());
+
+        assert_eq!(keychain_entry("journal/github")?, ("journal", "github"));
+        assert!(keychain_entry("github").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use std::io::Stdout;
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Span, Spans, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+
+use crate::storage::{Entry, Journal};
+
+const HELP: &str = "j/k: move  J/K: scroll  /: search  esc: clear search  q: quit";
+
+/// Shows the entries newest first, with a preview of the selected one, until the user quits
+pub(crate) fn browse(journal: &Journal, ascii: bool) -> Result<()> {
+    let mut entries = Vec::new();
+    for path in journal.entries()?.into_iter().rev() {
+        entries.push(journal.read_entry(path)?);
+    }
+
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = run(&mut terminal, App::new(entries), ascii);
+
+    // Always give the terminal back, even if something went wrong
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>, mut app: App, ascii: bool) -> Result<()> {
+    loop {
+        terminal.draw(|f| draw(f, &mut app, ascii))?;
+
+        if let Event::Key(key) = event::read()? {
+            // Windows also reports releasing a key
+            if key.kind == KeyEventKind::Press && app.handle(key) == Flow::Quit {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Flow {
+    Continue,
+    Quit,
+}
+
+struct App {
+    entries: Vec<Entry>,
+    /// The indices of the entries matching the search, in the order they are shown
+    visible: Vec<usize>,
+    list: ListState,
+    search: String,
+    searching: bool,
+    scroll: u16,
+}
+
+impl App {
+    fn new(entries: Vec<Entry>) -> Self {
+        let mut app = App {
+            entries,
+            visible: Vec::new(),
+            list: ListState::default(),
+            search: String::new(),
+            searching: false,
+            scroll: 0,
+        };
+        app.filter();
+        app
+    }
+
+    /// Only shows the entries containing the search, ignoring case, keeping the cursor in range
+    fn filter(&mut self) {
+        let search = self.search.to_lowercase();
+        self.visible = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.markdown.to_lowercase().contains(&search))
+            .map(|(index, _)| index)
+            .collect();
+
+        let selected = match self.visible.len() {
+            0 => None,
+            len => Some(self.list.selected().unwrap_or(0).min(len - 1)),
+        };
+        self.list.select(selected);
+        self.scroll = 0;
+    }
+
+    fn selected(&self) -> Option<&Entry> {
+        self.list
+            .selected()
+            .and_then(|i| self.visible.get(i))
+            .map(|index| &self.entries[*index])
+    }
+
+    fn handle(&mut self, key: KeyEvent) -> Flow {
+        if self.searching {
+            match key.code {
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.search.clear();
+                }
+                KeyCode::Backspace => {
+                    self.search.pop();
+                }
+                KeyCode::Char(c) => self.search.push(c),
+                _ => return Flow::Continue,
+            }
+            self.filter();
+            return Flow::Continue;
+        }
+
+        let selected = self.list.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Char('q') => return Flow::Quit,
+            KeyCode::Esc if self.search.is_empty() => return Flow::Quit,
+            KeyCode::Esc => {
+                self.search.clear();
+                self.filter();
+            }
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Char('j') | KeyCode::Down if selected + 1 < self.visible.len() => {
+                self.list.select(Some(selected + 1));
+                self.scroll = 0;
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.list.select(Some(selected.saturating_sub(1)));
+                self.scroll = 0;
+            }
+            KeyCode::Char('J') | KeyCode::PageDown => self.scroll = self.scroll.saturating_add(5),
+            KeyCode::Char('K') | KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(5),
+            _ => {}
+        }
+        Flow::Continue
+    }
+
+    fn prompt(&self) -> String {
+        match (self.searching, self.search.is_empty()) {
+            (true, _) => format!("Search: {}", self.search),
+            (false, false) => format!(
+                "{} of {} entries contain '{}'  {}",
+                self.visible.len(),
+                self.entries.len(),
+                self.search,
+                HELP
+            ),
+            (false, true) => HELP.to_string(),
+        }
+    }
+}
+
+/// Styles the markdown of an entry for the terminal: headings in bold, quotes in italics, and code dimmed
+fn preview(markdown: &str) -> Text<'static> {
+    let mut in_code = false;
+    let lines = markdown.lines().map(|line| {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            return Spans::from(Span::styled(
+                line.to_string(),
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+
+        let (text, style) = if in_code {
+            (line, Style::default().add_modifier(Modifier::DIM))
+        } else if let Some(title) = line.strip_prefix("# ") {
+            (
+                title,
+                Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            )
+        } else if line.starts_with("##") {
+            (
+                line.trim_start_matches('#').trim_start(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )
+        } else if line.starts_with('>') {
+            (line, Style::default().add_modifier(Modifier::ITALIC))
+        } else {
+            (line, Style::default())
+        };
+        Spans::from(Span::styled(text.to_string(), style))
+    });
+
+    Text::from(lines.collect::<Vec<_>>())
+}
+
+fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App, ascii: bool) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(f.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(rows[0]);
+    let borders = if ascii { Borders::NONE } else { Borders::ALL };
+
+    let items: Vec<_> = app
+        .visible
+        .iter()
+        .map(|index| {
+            let entry = &app.entries[*index];
+            ListItem::new(format!("{}  {}", entry.describe(), entry.title()))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(borders).title("Entries"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(if ascii { "> " } else { "▶ " });
+    f.render_stateful_widget(list, columns[0], &mut app.list);
+
+    let text = app
+        .selected()
+        .map(|entry| preview(&entry.markdown))
+        .unwrap_or_default();
+    let preview = Paragraph::new(text)
+        .block(Block::default().borders(borders))
+        .wrap(Wrap { trim: false })
+        .scroll((app.scroll, 0));
+    f.render_widget(preview, columns[1]);
+
+    f.render_widget(Paragraph::new(app.prompt()), rows[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use std::path::PathBuf;
+
+    fn type_in(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            app.handle(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> Flow {
+        app.handle(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn titles(app: &App) -> Vec<String> {
+        app.visible
+            .iter()
+            .map(|index| app.entries[*index].title())
+            .collect()
+    }
+
+    #[test]
+    fn searches_entries_as_you_type() {
+        let entry = |name: &str, markdown: &str| Entry {
+            path: PathBuf::from(name),
+            markdown: markdown.to_string(),
+        };
+        let mut app = App::new(vec![
+            entry(
+                "2022-03-11-retro.md",
+                "# Retro on 2022-03-11\n\nThe rollout went fine",
+            ),
+            entry(
+                "2022-03-10-planning.md",
+                "# Planning on 2022-03-10\n\nPlan the Rollout",
+            ),
+            entry(
+                "2022-03-09-standup.md",
+                "# Standup on 2022-03-09\n\nNothing new",
+            ),
+        ]);
+
+        type_in(&mut app, "jj");
+        assert_eq!(
+            app.selected().map(Entry::title),
+            Some("Standup".to_string())
+        );
+
+        type_in(&mut app, "/rollout");
+        assert_eq!(titles(&app), vec!["Retro", "Planning"]);
+        assert_eq!(
+            app.selected().map(Entry::title),
+            Some("Planning".to_string())
+        );
+
+        press(&mut app, KeyCode::Enter);
+        type_in(&mut app, "k");
+        assert_eq!(app.selected().map(Entry::title), Some("Retro".to_string()));
+
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(titles(&app).len(), 3);
+        assert_eq!(press(&mut app, KeyCode::Esc), Flow::Quit);
+    }
+}
@@ -0,0 +1,242 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use time::{Date, Duration, OffsetDateTime};
+
+use crate::storage::YEAR_MONTH_DAY;
+
+/// Keeps what remote sections like pull requests and Jira fetched around for a while,
+/// so running `journal new --stdout` again and again while working on a template is instant.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheConfig {
+    /// How long fetched data is used before asking the remote again
+    #[serde(default = "default_ttl_minutes")]
+    ttl_minutes: i64,
+    /// Where the cached data is kept, the user's cache directory if left out
+    dir: Option<PathBuf>,
+}
+
+fn default_ttl_minutes() -> i64 {
+    10
+}
+
+impl CacheConfig {
+    /// How long fetched data is used for
+    pub(crate) fn ttl(&self) -> Duration {
+        Duration::minutes(self.ttl_minutes)
+    }
+
+    /// The same cache, but fetching again each time to replace what was kept before
+    pub(crate) fn refreshing(&self) -> CacheConfig {
+        CacheConfig {
+            ttl_minutes: 0,
+            dir: self.dir.clone(),
+        }
+    }
+
+    /// The cache for the journal in `journal_dir`, which doesn't share what it fetched with other journals
+    pub fn cache(&self, journal_dir: &Path) -> Cache {
+        let dir = self.dir.clone().unwrap_or_else(|| {
+            dirs::cache_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("journal")
+        });
+
+        Cache {
+            dir,
+            ttl: self.ttl(),
+            journal: journal_dir.to_path_buf(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    ttl: Duration,
+    journal: PathBuf,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Cached<T> {
+    fetched_at: OffsetDateTime,
+    value: T,
+}
+
+impl Cache {
+    /// What `fetch` got for `section` on `date` the last time, unless that was too long ago.
+    /// Changing the `settings` the section fetches with, like its query, fetches again.
+    pub(crate) async fn get_or_fetch<T, F>(
+        &self,
+        section: &str,
+        settings: &impl Serialize,
+        date: Date,
+        fetch: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Future<Output = Result<T>>,
+    {
+        let path = self.dir.join(format!(
+            "{}-{}-{}.json",
+            section,
+            date.format(YEAR_MONTH_DAY)?,
+            self.key(settings)?
+        ));
+
+        if let Some(value) = self.read(&path, OffsetDateTime::now_utc()).await {
+            tracing::info!("Using cached {} from {}", section, path.to_string_lossy());
+            return Ok(value);
+        }
+
+        let value = fetch.await?;
+        // Not being able to cache shouldn't keep anyone from writing their entry
+        if let Err(e) = self.write(path, &value).await {
+            tracing::warn!("Could not cache {}: {:#}", section, e);
+        }
+        Ok(value)
+    }
+
+    /// Tells apart the journals and the settings of a section, without putting them in the name of the file
+    fn key(&self, settings: &impl Serialize) -> Result<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.journal.to_string_lossy().as_bytes());
+        hasher.update(serde_json::to_vec(settings)?);
+
+        Ok(hasher.finalize()[..8]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+
+    /// Reads (and `write` writes) on a thread of its own,
+    /// so that a slow disk doesn't keep the timeout of a section from firing
+    async fn read<T: DeserializeOwned>(&self, path: &Path, now: OffsetDateTime) -> Option<T> {
+        let path = path.to_path_buf();
+        let content = tokio::task::spawn_blocking(move || std::fs::read(path))
+            .await
+            .ok()?
+            .ok()?;
+        let cached: Cached<T> = serde_json::from_slice(&content).ok()?;
+        (now - cached.fetched_at < self.ttl).then_some(cached.value)
+    }
+
+    async fn write<T: Serialize>(&self, path: PathBuf, value: &T) -> Result<()> {
+        let cached = Cached {
+            fetched_at: OffsetDateTime::now_utc(),
+            value,
+        };
+        let content = serde_json::to_vec(&cached)?;
+
+        let dir = self.dir.clone();
+        tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(&dir).with_context(|| format!("Could not create {:?}", dir))?;
+            std::fs::write(path, content)?;
+            Ok(())
+        })
+        .await?
+    }
+}
+
+/// Goes through the `cache` if there is one, otherwise always fetches
+pub(crate) async fn fetch<T, F>(
+    cache: Option<&Cache>,
+    section: &str,
+    settings: &impl Serialize,
+    date: Date,
+    fetch: F,
+) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<T>>,
+{
+    match cache {
+        Some(cache) => cache.get_or_fetch(section, settings, date, fetch).await,
+        None => fetch.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use time::macros::date;
+
+    #[tokio::test]
+    async fn reuses_fetched_data_until_it_expires() -> Result<()> {
+        let dir = TempDir::new()?;
+        let cache = Cache {
+            dir: dir.path().to_path_buf(),
+            ttl: Duration::minutes(10),
+            journal: PathBuf::from("/home/me/journal"),
+        };
+        let today = date!(2022 - 03 - 11);
+        let query = "is:open";
+
+        let first: Vec<String> = cache
+            .get_or_fetch("prs", &query, today, async {
+                Ok(vec!["Fix the build".to_string()])
+            })
+            .await?;
+        let second: Vec<String> = cache
+            .get_or_fetch("prs", &query, today, async {
+                Ok(vec!["Something new".to_string()])
+            })
+            .await?;
+        assert_eq!(first, second);
+
+        let tomorrow: Vec<String> = cache
+            .get_or_fetch("prs", &query, date!(2022 - 03 - 12), async {
+                Ok(vec!["Something new".to_string()])
+            })
+            .await?;
+        assert_eq!(tomorrow, vec!["Something new"]);
+
+        let path = dir
+            .path()
+            .join(format!("prs-2022-03-11-{}.json", cache.key(&query)?));
+        let later = OffsetDateTime::now_utc() + Duration::minutes(11);
+        assert_eq!(cache.read::<Vec<String>>(&path, later).await, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn other_settings_and_journals_fetch_again() -> Result<()> {
+        let dir = TempDir::new()?;
+        let cache = Cache {
+            dir: dir.path().to_path_buf(),
+            ttl: Duration::minutes(10),
+            journal: PathBuf::from("/home/me/journal"),
+        };
+        let work = Cache {
+            journal: PathBuf::from("/home/me/work"),
+            ..cache.clone()
+        };
+        let today = date!(2022 - 03 - 11);
+
+        let _: Vec<String> = cache
+            .get_or_fetch("prs", &"is:open", today, async {
+                Ok(vec!["Fix the build".to_string()])
+            })
+            .await?;
+
+        let other_query: Vec<String> = cache
+            .get_or_fetch("prs", &"is:closed", today, async {
+                Ok(vec!["Closed".to_string()])
+            })
+            .await?;
+        assert_eq!(other_query, vec!["Closed"]);
+
+        let other_journal: Vec<String> = work
+            .get_or_fetch("prs", &"is:open", today, async {
+                Ok(vec!["At work".to_string()])
+            })
+            .await?;
+        assert_eq!(other_journal, vec!["At work"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::{bail, Result};
+use clap::StructOpt;
+
+use crate::edit::{self, DryRun};
+use crate::post_process;
+use crate::storage::Journal;
+use crate::template::Heading;
+use crate::todo::{append_todos, check_off, todo_text, FindTodos};
+
+const WRAP_UP: &str = "## Wrap-up";
+
+#[derive(Debug, StructOpt)]
+pub struct CloseCmd {
+    /// The numbers of open TODOs that got done, counting from 1
+    #[clap(long = "done")]
+    done: Vec<usize>,
+    /// Something that got done today, can be given several times
+    #[clap(long = "accomplished")]
+    accomplished: Vec<String>,
+    /// How the day went, e.g. `tired but happy`
+    #[clap(long)]
+    mood: Option<String>,
+    /// Something to pick up tomorrow, added as an open TODO so it is carried over
+    #[clap(long = "tomorrow")]
+    tomorrow: Vec<String>,
+    /// Only use the flags instead of asking for anything that wasn't given
+    #[clap(long)]
+    no_prompt: bool,
+    #[clap(flatten)]
+    dry_run: DryRun,
+}
+
+/// Everything that goes into closing the day
+#[derive(Debug, Default)]
+struct WrapUp {
+    done: Vec<usize>,
+    accomplished: Vec<String>,
+    mood: Option<String>,
+    tomorrow: Vec<String>,
+}
+
+impl CloseCmd {
+    pub(crate) fn execute(self, journal: &Journal, heading: &Heading) -> Result<()> {
+        let entry = match journal.latest_entry()? {
+            Some(entry) => entry,
+            None => bail!("There is no entry to close the day in yet"),
+        };
+
+        let nothing_given = self.done.is_empty()
+            && self.accomplished.is_empty()
+            && self.mood.is_none()
+            && self.tomorrow.is_empty();
+
+        let wrap_up = if nothing_given && !self.no_prompt {
+            ask(&entry.markdown, heading)?
+        } else {
+            WrapUp {
+                done: self.done,
+                accomplished: self.accomplished,
+                mood: self.mood,
+                tomorrow: self.tomorrow,
+            }
+        };
+
+        let updated = close(&entry.markdown, &wrap_up, heading)?;
+        let changed = self.dry_run.apply(&entry.path, &entry.markdown, &updated)?;
+        if changed && !self.dry_run.is_dry_run() {
+            println!("Closed the day in {}", entry.describe());
+        }
+
+        self.dry_run.finish(changed as usize)
+    }
+}
+
+fn ask(markdown: &str, heading: &Heading) -> Result<WrapUp> {
+    let mut done = Vec::new();
+    for (index, range) in FindTodos::new()
+        .under(heading)
+        .open_todo_ranges(markdown)
+        .into_iter()
+        .enumerate()
+    {
+        if edit::confirm(&format!("Done with '{}'?", todo_text(&markdown[range])))? {
+            done.push(index + 1);
+        }
+    }
+
+    let accomplished = edit::ask_many("What did you get done today?")?;
+    let mood = Some(edit::ask("How did the day go?")?).filter(|mood| !mood.is_empty());
+    let tomorrow = edit::ask_many("Anything to pick up tomorrow?")?;
+
+    Ok(WrapUp {
+        done,
+        accomplished,
+        mood,
+        tomorrow,
+    })
+}
+
+/// Checks off the TODOs that got done, queues new ones for tomorrow, and adds a wrap-up section
+fn close(markdown: &str, wrap_up: &WrapUp, heading: &Heading) -> Result<String> {
+    if markdown.lines().any(|line| line.trim_end() == WRAP_UP) {
+        bail!(
+            "The day was already wrapped up, change the '{}' section by hand",
+            WRAP_UP
+        );
+    }
+
+    let updated = check_off(markdown, &wrap_up.done, heading)?;
+    let mut updated = append_todos(&updated, &wrap_up.tomorrow, heading);
+
+    if !wrap_up.accomplished.is_empty() || wrap_up.mood.is_some() {
+        let mut section = format!("\n{}\n\n", WRAP_UP);
+        for accomplishment in &wrap_up.accomplished {
+            section.push_str(&format!("* {}\n", accomplishment));
+        }
+        if let Some(ref mood) = wrap_up.mood {
+            if !wrap_up.accomplished.is_empty() {
+                section.push('\n');
+            }
+            section.push_str(&format!("Mood: {}\n", mood));
+        }
+
+        if !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push_str(&section);
+    }
+
+    Ok(post_process::update_toc(&updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    const ENTRY: &str = indoc! {r#"
+        # Planning on 2022-03-11
+
+        ## TODOs
+
+        * [ ] Send the figures
+        * [ ] Call the bank
+        * [x] Book the flights
+
+        ## Notes
+
+        Busy day.
+        "#};
+
+    #[test]
+    fn checks_off_todos_and_wraps_up_the_day() -> Result<()> {
+        let wrap_up = WrapUp {
+            done: vec![2],
+            accomplished: vec!["Shipped the release".to_string()],
+            mood: Some("tired but happy".to_string()),
+            tomorrow: vec!["Follow up with Apollo".to_string()],
+        };
+
+        assert_eq!(
+            close(ENTRY, &wrap_up, &Heading::todos())?,
+            indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## TODOs
+
+            * [ ] Send the figures
+            * [x] Call the bank
+            * [x] Book the flights
+            * [ ] Follow up with Apollo
+
+            ## Notes
+
+            Busy day.
+
+            ## Wrap-up
+
+            * Shipped the release
+
+            Mood: tired but happy
+            "#}
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn only_wraps_up_once() -> Result<()> {
+        let wrap_up = WrapUp {
+            mood: Some("fine".to_string()),
+            ..Default::default()
+        };
+        let closed = close(ENTRY, &wrap_up, &Heading::todos())?;
+
+        assert!(close(&closed, &wrap_up, &Heading::todos()).is_err());
+        assert!(close(
+            ENTRY,
+            &WrapUp {
+                done: vec![3],
+                ..Default::default()
+            },
+            &Heading::todos()
+        )
+        .is_err());
+        Ok(())
+    }
+}
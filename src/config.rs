@@ -1,18 +1,60 @@
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use clap::StructOpt;
-use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, io::Read, path::PathBuf};
+use handlebars::Handlebars;
+use regex::Regex;
+use serde::de::{
+    self, value::MapAccessDeserializer, DeserializeSeed, IntoDeserializer, MapAccess, Visitor,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    io::Read,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+use time::Date;
 
+use crate::auth::Token;
+use crate::cache::{Cache, CacheConfig};
+use crate::countdowns::CountdownConfig;
+use crate::datafile::DatafileConfig;
+use crate::edit;
+use crate::git::GitConfig;
+use crate::helpers;
+use crate::index::IndexConfig;
+use crate::init::expand_home;
+use crate::locale::Locale;
 use crate::notes::NotesConfig;
+use crate::post_process::PostProcessor;
+use crate::scratchpad::ScratchpadConfig;
+use crate::secret_scan::SecretScanConfig;
+use crate::signing::SigningConfig;
+use crate::status::Statuses;
+use crate::storage::{write_atomically, DateStamp};
+use crate::template::{DateFormat, EntryTemplate, FrontMatterConfig, Heading};
 use crate::{
-    github::PullRequestConfig, jira::JiraConfig, reminders::ReminderConfig, storage::Journal,
-    todo::TodoConfig, Clock,
+    github::PullRequestConfig,
+    jira::{JiraConfig, JiraWorklogConfig},
+    reminders::ReminderConfig,
+    storage::Journal,
+    todo::TodoConfig,
+    Clock,
 };
 
 #[derive(Debug, StructOpt)]
 pub enum ConfigCmd {
     /// Show the current configuration that is loaded
     Show,
+    /// Show a single setting, e.g. `journal config get todos.enabled`
+    Get { key: String },
+    /// Change a single setting in the configuration file, e.g. `journal config set todos.enabled false`
+    Set { key: String, value: String },
+    /// Change the configuration file in your `$EDITOR`, it is only saved if it is still valid
+    Edit,
+    /// Check the configuration file for settings that can't be read and keys that aren't used, like typos
+    Check,
 }
 
 impl ConfigCmd {
@@ -21,12 +63,486 @@ impl ConfigCmd {
             ConfigCmd::Show => {
                 serde_yaml::to_writer(std::io::stdout(), config).map_err(|e| anyhow::anyhow!(e))
             }
+            ConfigCmd::Get { key } => {
+                let loaded = serde_yaml::to_value(config)?;
+                let value = key
+                    .split('.')
+                    .try_fold(&loaded, |value, part| value.get(part))
+                    .ok_or_else(|| anyhow!("There is no '{}' in the configuration", key))?;
+                match value {
+                    Value::String(value) => println!("{}", value),
+                    value => println!("{}", to_yaml(value)?),
+                }
+                Ok(())
+            }
+            ConfigCmd::Set { key, value } => {
+                let path = Config::config_path()?;
+                let yaml = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Could not read {:?}", path))?;
+                let updated = set(&yaml, key, value)?;
+                write_config(&path, &updated)?;
+                println!("Set {} to {} in {}", key, value, path.to_string_lossy());
+                Ok(())
+            }
+            ConfigCmd::Edit => {
+                let path = Config::config_path()?;
+                let yaml = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Could not read {:?}", path))?;
+                let edited = edit::in_editor("config.yaml", &yaml)?;
+                Config::from_reader(edited.as_bytes())
+                    .context("The configuration was not saved as it is no longer valid")?;
+                write_config(&path, &edited)?;
+                Ok(())
+            }
+            ConfigCmd::Check => check(&Config::config_path()?),
+        }
+    }
+}
+
+/// Prints each problem in the configuration at `path` along with where it is, failing if there are any
+pub fn check(path: &Path) -> Result<()> {
+    let problems = problems_at(path)?;
+    for problem in &problems {
+        println!("{}:{}", path.to_string_lossy(), problem);
+    }
+    match problems.len() {
+        0 => {
+            println!("{} is valid", path.to_string_lossy());
+            Ok(())
+        }
+        1 => bail!("There is a problem with {}", path.to_string_lossy()),
+        n => bail!("There are {} problems with {}", n, path.to_string_lossy()),
+    }
+}
+
+/// The problems in the configuration at `path`, the files it includes, and each of its profiles
+pub(crate) fn problems_at(path: &Path) -> Result<Vec<Problem>> {
+    let yaml =
+        std::fs::read_to_string(path).with_context(|| format!("Could not read {:?}", path))?;
+    problems(&yaml, &with_includes(path, &mut Vec::new())?)
+}
+
+/// Something wrong with the configuration, on a line and column counting from 1 if it's known
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Problem {
+    location: Option<(usize, usize)>,
+    message: String,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(f, "{}:{}: {}", line, column, self.message),
+            None => write!(f, " {}", self.message),
+        }
+    }
+}
+
+/// A setting that none of the configuration knows about, like `reminders.fiel`, with the ones that were expected instead
+#[derive(Debug)]
+struct UnknownSetting {
+    key: String,
+    expected: Vec<String>,
+}
+
+impl UnknownSetting {
+    /// The setting in an error about an `unknown field` at `path`, which is either the setting itself
+    /// or the section it was found in
+    fn from_error(path: &serde_path_to_error::Path, message: &str) -> Option<Self> {
+        let (_, rest) = message.split_once("unknown field `")?;
+        let (name, rest) = rest.split_once('`')?;
+        let expected = match rest.split_once("expected") {
+            Some((_, expected)) => Regex::new("`([^`]*)`")
+                .unwrap()
+                .captures_iter(expected)
+                .map(|captures| captures[1].to_string())
+                .collect(),
+            None => Vec::new(),
+        };
+        let key = match path.iter().next_back() {
+            Some(serde_path_to_error::Segment::Map { key }) if key == name => path.to_string(),
+            Some(_) => format!("{}.{}", path, name),
+            None => name.to_string(),
+        };
+        Some(UnknownSetting { key, expected })
+    }
+
+    /// Where the setting is, like `reminders` for `reminders.fiel`
+    fn parent(&self) -> Option<&str> {
+        self.key.rsplit_once('.').map(|(parent, _)| parent)
+    }
+
+    /// Says that `key` is unknown, suggesting the one of the `candidates` it is most likely a typo of
+    fn message(&self, key: &str, candidates: &[String]) -> String {
+        let name = self.key.rsplit('.').next().unwrap_or(&self.key);
+        let mut message = format!("'{}' is not a setting journal knows about", key);
+        if let Some(suggestion) = closest(name, candidates) {
+            message.push_str(&format!(", did you mean '{}'?", suggestion));
+        }
+        message
+    }
+}
+
+impl fmt::Display for UnknownSetting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message(&self.key, &self.expected))
+    }
+}
+
+impl std::error::Error for UnknownSetting {}
+
+/// Reads the configuration, refusing settings that none of its sections know about
+fn read<'de, D>(deserializer: D) -> Result<Config>
+where
+    D: Deserializer<'de>,
+    D::Error: std::error::Error + Send + Sync + 'static,
+{
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        match UnknownSetting::from_error(e.path(), &e.inner().to_string()) {
+            Some(unknown) => anyhow::Error::new(unknown),
+            None => anyhow::Error::new(e.into_inner()),
+        }
+    })
+}
+
+/// The settings in the configuration `yaml`, with what it includes in `raw`, and each of its profiles
+/// that can't be read or aren't used
+fn problems(yaml: &str, raw: &Value) -> Result<Vec<Problem>> {
+    let mut raw = raw.clone();
+    let read = match raw.get("include") {
+        // Only a file on its own can tell where a setting that can't be read is
+        None => Config::from_reader(yaml.as_bytes()),
+        Some(_) => Config::with_profile(raw.clone(), None),
+    };
+    let (read, unknown) = without_unknown(&mut raw, None, read);
+    // Only used to suggest what else could have been meant
+    let known = match read {
+        Ok(ref config) => serde_yaml::to_value(config)?,
+        Err(_) => Value::Null,
+    };
+    let mut problems: Vec<Problem> = unknown
+        .iter()
+        .map(|setting| unused(yaml, setting, "", &known))
+        .collect();
+
+    let config = match read {
+        Ok(config) => config,
+        Err(e) => {
+            let location = e
+                .downcast_ref::<serde_yaml::Error>()
+                .and_then(serde_yaml::Error::location)
+                .map(|location| (location.line(), location.column()));
+            problems.push(Problem {
+                location,
+                message: e.to_string(),
+            });
+            return Ok(problems);
+        }
+    };
+
+    for name in config.profiles.keys() {
+        let read = Config::with_profile(raw.clone(), Some(name));
+        let (read, unknown) = without_unknown(&mut raw, Some(name), read);
+        let known = match read {
+            Ok(ref profile) => serde_yaml::to_value(profile)?,
+            Err(_) => Value::Null,
+        };
+        let prefix = format!("profiles.{}", name);
+        problems.extend(
+            unknown
+                .iter()
+                .map(|setting| unused(yaml, setting, &prefix, &known)),
+        );
+        if let Err(e) = read {
+            problems.push(Problem {
+                location: locate(yaml, &prefix),
+                message: format!("The '{}' journal can't be read: {}", name, e),
+            });
+        }
+    }
+    Ok(problems)
+}
+
+/// Takes each setting that reading the configuration for `profile` refused out of `raw`, or out of
+/// the profile's overrides, and reads it again, until it can be read or fails for another reason
+fn without_unknown(
+    raw: &mut Value,
+    profile: Option<&str>,
+    mut read: Result<Config>,
+) -> (Result<Config>, Vec<UnknownSetting>) {
+    let mut unknown = Vec::new();
+    loop {
+        let error = match read {
+            Ok(config) => return (Ok(config), unknown),
+            Err(error) => error,
+        };
+        let setting = match error.downcast::<UnknownSetting>() {
+            Ok(setting) => setting,
+            Err(error) => return (Err(error), unknown),
+        };
+        let settings = match profile {
+            Some(profile) => &mut raw["profiles"][profile],
+            None => &mut *raw,
+        };
+        if !remove(settings, &setting.key) {
+            return (Err(setting.into()), unknown);
+        }
+        unknown.push(setting);
+        read = Config::with_profile(raw.clone(), profile);
+    }
+}
+
+/// Takes `key`, like `reminders.fiel` or `post_processors[1].args`, out of `raw`, if it is there
+fn remove(raw: &mut Value, key: &str) -> bool {
+    let (parent, name) = match key.rsplit_once('.') {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, key),
+    };
+    let mut value = raw;
+    for part in parent.into_iter().flat_map(|parent| parent.split('.')) {
+        let (name, index) = match part.split_once('[') {
+            Some((name, index)) => (name, index.trim_end_matches(']').parse::<usize>().ok()),
+            None => (part, None),
+        };
+        value = match value.get_mut(name) {
+            Some(value) => value,
+            None => return false,
+        };
+        if let Some(index) = index {
+            value = match value.get_mut(index) {
+                Some(value) => value,
+                None => return false,
+            };
+        }
+    }
+    match value {
+        Value::Mapping(mapping) => mapping.remove(&Value::from(name)).is_some(),
+        _ => false,
+    }
+}
+
+/// Points at the `setting` nothing knew about, below `prefix` in `yaml`, with the closest one that was expected
+/// or, if the section didn't say, that is next to it in the `known` configuration
+fn unused(yaml: &str, setting: &UnknownSetting, prefix: &str, known: &Value) -> Problem {
+    let key = match prefix {
+        "" => setting.key.clone(),
+        prefix => format!("{}.{}", prefix, setting.key),
+    };
+    let candidates = match setting.expected.is_empty() {
+        true => siblings(known, setting.parent()),
+        false => setting.expected.clone(),
+    };
+    Problem {
+        location: locate(yaml, &key),
+        message: setting.message(&key, &candidates),
+    }
+}
+
+/// The keys next to where `parent`, like `reminders` or `post_processors[1]`, leads in `known`
+fn siblings(known: &Value, parent: Option<&str>) -> Vec<String> {
+    let mut value = known;
+    for part in parent.into_iter().flat_map(|parent| parent.split('.')) {
+        let (name, index) = match part.split_once('[') {
+            Some((name, index)) => (name, index.trim_end_matches(']').parse::<usize>().ok()),
+            None => (part, None),
+        };
+        value = &value[name];
+        if let Some(index) = index {
+            value = &value[index];
+        }
+    }
+    match value {
+        Value::Mapping(mapping) => mapping
+            .iter()
+            .filter_map(|(key, _)| key.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The one of the `candidates` that `name` is most likely a typo of, if any is close enough
+fn closest<'a>(name: &str, candidates: &'a [String]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (strsim::levenshtein(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= (name.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Where `key`, like `reminders.fiel` or `post_processors[1].args`, is written in `yaml`.
+/// Items of a list aren't told apart, so this is the first one with the key.
+fn locate(yaml: &str, key: &str) -> Option<(usize, usize)> {
+    let path: Vec<&str> = key
+        .split('.')
+        .map(|part| part.split('[').next().unwrap_or(part))
+        .collect();
+    let setting = Regex::new(r"^(\s*(?:-\s+)?)([\w-]+):").unwrap();
+
+    let mut parents: Vec<(usize, &str)> = Vec::new();
+    for (index, line) in yaml.lines().enumerate() {
+        let captures = match setting.captures(line) {
+            Some(captures) => captures,
+            None => continue,
+        };
+        let column = captures[1].len();
+        parents.retain(|(parent, _)| *parent < column);
+        parents.push((column, captures.get(2).unwrap().as_str()));
+
+        if parents
+            .iter()
+            .map(|(_, name)| *name)
+            .eq(path.iter().copied())
+        {
+            return Some((index + 1, column + 1));
         }
     }
+    None
+}
+
+/// Writes the configuration at once, so it is never left half written.
+/// A symlinked configuration, e.g. from a dotfiles repository, stays a symlink.
+fn write_config(path: &Path, yaml: &str) -> Result<()> {
+    let target = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    write_atomically(&target, yaml.as_bytes())
+        .with_context(|| format!("Could not write {:?}", path))
+}
+
+/// Changes `key` to `value` in the configuration `yaml`. Only the line with the setting is changed if it
+/// is already there, so comments are kept. Anything else means writing the whole configuration again.
+fn set(yaml: &str, key: &str, value: &str) -> Result<String> {
+    let path: Vec<&str> = key.split('.').collect();
+    let value: Value =
+        serde_yaml::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+
+    let updated = match replace_line(yaml, &path, &value) {
+        Some(updated) => updated,
+        None => {
+            let mut raw: Value = serde_yaml::from_str(yaml)?;
+            insert(&mut raw, &path, value)?;
+            if yaml.lines().any(|line| line.trim_start().starts_with('#')) {
+                eprintln!("Warning: the comments in the configuration could not be kept");
+            }
+            to_yaml(&raw)? + "\n"
+        }
+    };
+
+    Config::from_reader(updated.as_bytes())
+        .with_context(|| format!("'{}' can't be set to that", key))?;
+    Ok(updated)
+}
+
+/// Replaces the value on the line of an existing `key: value`, leaving any comment behind it alone
+fn replace_line(yaml: &str, path: &[&str], value: &Value) -> Option<String> {
+    if matches!(value, Value::Mapping(_) | Value::Sequence(_)) {
+        return None;
+    }
+    let value = to_yaml(value).ok()?;
+    let setting = Regex::new(r"^(\s*)([\w-]+):(.*)$").unwrap();
+
+    let mut lines: Vec<String> = yaml.lines().map(str::to_string).collect();
+    let mut parents: Vec<(usize, &str)> = Vec::new();
+    for (index, line) in yaml.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        parents.retain(|(parent, _)| *parent < indent);
+
+        // Settings within lists can't be told apart by their name alone
+        if trimmed.starts_with('-') {
+            parents.push((indent, "-"));
+            continue;
+        }
+        let captures = match setting.captures(line) {
+            Some(captures) => captures,
+            None => continue,
+        };
+        let name = captures.get(2).unwrap().as_str();
+        parents.push((indent, name));
+
+        if parents
+            .iter()
+            .map(|(_, name)| *name)
+            .eq(path.iter().copied())
+        {
+            let (current, comment) = split_comment(captures.get(3).unwrap().as_str());
+            if current.trim().is_empty() {
+                return None;
+            }
+            let gap = &current[current.trim_end().len()..];
+            lines[index] = format!("{}{}: {}{}{}", &captures[1], name, value, gap, comment);
+
+            let mut updated = lines.join("\n");
+            if yaml.ends_with('\n') {
+                updated.push('\n');
+            }
+            return Some(updated);
+        }
+    }
+    None
+}
+
+/// The value and the `# comment` after it, if there is one
+fn split_comment(rest: &str) -> (&str, &str) {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, c) in rest.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => {
+                return (&rest[..index], &rest[index..])
+            }
+            None => {}
+        }
+        previous = c;
+    }
+    (rest, "")
+}
+
+fn insert(raw: &mut Value, path: &[&str], value: Value) -> Result<()> {
+    let (first, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+    if raw.is_null() {
+        *raw = Value::Mapping(Mapping::new());
+    }
+    let mapping = match raw {
+        Value::Mapping(mapping) => mapping,
+        _ => bail!(
+            "'{}' is a single value and has no settings within it",
+            first
+        ),
+    };
+
+    let key = Value::String(first.to_string());
+    if rest.is_empty() {
+        mapping.insert(key, value);
+        return Ok(());
+    }
+    if !mapping.contains_key(&key) {
+        mapping.insert(key.clone(), Value::Null);
+    }
+    insert(mapping.get_mut(&key).unwrap(), rest, value)
+}
+
+/// A YAML value as it would be written in the configuration
+fn to_yaml(value: &Value) -> Result<String> {
+    let yaml = serde_yaml::to_string(value)?;
+    Ok(yaml
+        .strip_prefix("---\n")
+        .unwrap_or(&yaml)
+        .trim_end()
+        .to_string())
 }
 
 /// Configuration we can get either from a file or from ENV variables
 #[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(default = "default_order")]
     pub sections: Vec<SectionName>,
@@ -41,16 +557,187 @@ pub struct Config {
 
     pub jira: Option<Enabled<JiraConfig>>,
 
+    pub jira_worklog: Option<Enabled<JiraWorklogConfig>>,
+
     pub pull_requests: Option<Enabled<PullRequestConfig>>,
+
+    pub scratchpad: Option<Enabled<ScratchpadConfig>>,
+
+    pub countdowns: Option<Enabled<CountdownConfig>>,
+
+    pub datafile: Option<Enabled<DatafileConfig>>,
+
+    pub secret_scanning: Option<Enabled<SecretScanConfig>>,
+
+    pub signing: Option<Enabled<SigningConfig>>,
+
+    pub cache: Option<Enabled<CacheConfig>>,
+
+    pub git: Option<Enabled<GitConfig>>,
+
+    pub front_matter: Option<Enabled<FrontMatterConfig>>,
+
+    pub index: Option<Enabled<IndexConfig>>,
+
+    #[serde(default)]
+    pub statuses: Statuses,
+
+    /// Only use plain ASCII in tables and entries, e.g. for screen readers or limited terminals
+    #[serde(default)]
+    pub ascii: bool,
+
+    /// Open new entries in the default editor right after creating them
+    #[serde(default = "default_open_on_create")]
+    pub open_on_create: bool,
+
+    /// A terminal editor like `nvim` to open new entries in, instead of the system default
+    pub editor: Option<String>,
+
+    /// Where to put the cursor when opening a new entry in `editor`
+    #[serde(default = "default_open_at")]
+    pub open_at: SectionName,
+
+    /// A handlebars template for the whole entry, with the `{{title}}`, the `{{date}}`, and either all `{{sections}}`
+    /// in order or each one on its own like `{{section.todos}}`. The default is `# {{title}} on {{date}}` and all sections.
+    pub entry_template: Option<String>,
+
+    /// How the `{{date}}` in the heading of new entries is written, e.g. `[weekday], [day padding:none] [month repr:long] [year]`
+    /// for "Monday, 3 June 2024", with month and weekday names in the `locale`. The filenames follow `date_stamp`.
+    #[serde(default)]
+    pub heading_date_format: DateFormat,
+
+    /// Different headings for the sections, like `todos: "### Aufgaben"` instead of `## TODOs`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headings: HashMap<SectionName, Heading>,
+
+    /// Other kinds of entries than the daily one, like `one-on-one` or `retro`, by their name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, EntryTemplate>,
+
+    /// Snippets every template can use, e.g. `checkbox: "* [ ] {{text}}"` as `{{> checkbox text=title}}`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub partials: BTreeMap<String, String>,
+
+    /// Applied to every new entry before it is written, in order
+    #[serde(default)]
+    pub post_processors: Vec<PostProcessor>,
+
+    /// The language of month and weekday names when creating reminders and in the heading of new entries
+    #[serde(default)]
+    pub locale: Locale,
+
+    /// How the date is written at the beginning of new entries' filenames
+    #[serde(default)]
+    pub date_stamp: DateStamp,
+
+    /// Where new entries go within `dir`, e.g. `{year}/{month}/{date}-{slug}.md`
+    pub filename: Option<String>,
+
+    /// What `new` does when there already is an entry for the day
+    #[serde(default)]
+    pub existing_entry: ExistingEntry,
+
+    /// Files in the journal directory that aren't entries, like `README*` or `drafts-*.md`
+    #[serde(default)]
+    pub ignore: Vec<String>,
+
+    /// Files with settings that are layered over these ones in order, like `~/.journal.work.yaml` on a work laptop
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<PathBuf>,
+
+    /// Separate journals like `work` and `personal`, picked with `--journal work`.
+    /// Each one overrides the settings above, e.g. with its own `dir` and integrations.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Value>,
 }
 
-#[derive(Serialize, Deserialize)]
+/// What to do when creating an entry for a day that already has one
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExistingEntry {
+    /// Open the existing entry instead
+    #[default]
+    Open,
+    /// Add the sections the existing entry doesn't have yet, e.g. after turning on pull requests
+    AddMissingSections,
+    /// Refuse to create another one unless `--force` is passed
+    Fail,
+}
+
+fn default_open_on_create() -> bool {
+    true
+}
+
+fn default_open_at() -> SectionName {
+    SectionName::Notes
+}
+
+#[derive(Serialize)]
 pub struct Enabled<T> {
     enabled: bool,
     #[serde(flatten)]
     inner: T,
 }
 
+/// Reads `enabled` on its own and hands every other setting to the section itself, instead of flattening it,
+/// so that the section can still refuse the settings it doesn't know about
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Enabled<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(EnabledVisitor(PhantomData))
+    }
+}
+
+struct EnabledVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for EnabledVisitor<T> {
+    type Value = Enabled<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("settings with `enabled: true` or `enabled: false`")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        let mut enabled = None;
+        let inner = T::deserialize(MapAccessDeserializer::new(WithoutEnabled {
+            map,
+            enabled: &mut enabled,
+        }))?;
+        Ok(Enabled {
+            enabled: enabled.ok_or_else(|| de::Error::missing_field("enabled"))?,
+            inner,
+        })
+    }
+}
+
+/// The settings of a section, taking out `enabled` along the way
+struct WithoutEnabled<'a, A> {
+    map: A,
+    enabled: &'a mut Option<bool>,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for WithoutEnabled<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        while let Some(key) = self.map.next_key::<String>()? {
+            if key != "enabled" {
+                return seed
+                    .deserialize(IntoDeserializer::<A::Error>::into_deserializer(key))
+                    .map(Some);
+            }
+            *self.enabled = Some(self.map.next_value()?);
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, A::Error> {
+        self.map.next_value_seed(seed)
+    }
+}
+
 impl<T: Default> Default for Enabled<T> {
     fn default() -> Self {
         Self::new(T::default())
@@ -70,14 +757,203 @@ impl<T> Enabled<T> {
 }
 
 impl Config {
+    /// The secret scanning configuration, if it is turned on
+    pub fn secret_scanning(&self) -> Option<&SecretScanConfig> {
+        self.secret_scanning
+            .as_ref()
+            .filter(|scanning| scanning.is_enabled())
+            .map(|scanning| &scanning.inner)
+    }
+
+    /// The front matter configuration, if new entries get any
+    pub(crate) fn front_matter(&self) -> Option<&FrontMatterConfig> {
+        self.front_matter
+            .as_ref()
+            .filter(|front_matter| front_matter.is_enabled())
+            .map(|front_matter| &front_matter.inner)
+    }
+
+    /// The git configuration, if changes are committed
+    pub(crate) fn git(&self) -> Option<&GitConfig> {
+        self.git
+            .as_ref()
+            .filter(|git| git.is_enabled())
+            .map(|git| &git.inner)
+    }
+
+    /// The registry to render templates with on `today`, with the configured statuses and partials
+    pub(crate) fn handlebars(&self, today: Date) -> Result<Handlebars<'static>> {
+        let mut handlebars = helpers::handlebars(&self.statuses, self.ascii, today);
+        helpers::register_partials(&mut handlebars, &self.partials)?;
+        Ok(handlebars)
+    }
+
+    /// The kind of entry configured under `templates` with that `name`
+    pub(crate) fn template(&self, name: &str) -> Result<&EntryTemplate> {
+        self.templates.get(name).ok_or_else(|| {
+            let known: Vec<_> = self.templates.keys().map(String::as_str).collect();
+            match known.is_empty() {
+                true => anyhow!("There is no template '{}', add it under 'templates'", name),
+                false => anyhow!(
+                    "There is no template '{}', only: {}",
+                    name,
+                    known.join(", ")
+                ),
+            }
+        })
+    }
+
+    /// Where the index of the entries is kept, if there is one
+    pub(crate) fn index_file(&self) -> Option<PathBuf> {
+        self.index
+            .as_ref()
+            .filter(|index| index.is_enabled())
+            .map(|index| index.inner.file(&self.dir))
+    }
+
+    /// Where the reminders are kept, which doesn't have to be inside the journal
+    pub fn reminders_file(&self) -> PathBuf {
+        self.dir.join(expand_home(&self.reminders.inner.file))
+    }
+
+    /// The signing configuration, if it is turned on
+    pub fn signing(&self) -> Option<&SigningConfig> {
+        self.signing
+            .as_ref()
+            .filter(|signing| signing.is_enabled())
+            .map(|signing| &signing.inner)
+    }
+
+    /// The heading TODOs are found beneath and added to
+    pub(crate) fn todo_heading(&self) -> Heading {
+        self.heading(SectionName::Todos, Heading::todos)
+    }
+
+    /// The heading notes are added beneath by `journal append`
+    pub(crate) fn notes_heading(&self) -> Heading {
+        self.heading(SectionName::Notes, Heading::notes)
+    }
+
+    /// The heading of the scratchpad that is carried over
+    pub(crate) fn scratchpad_heading(&self) -> Heading {
+        self.heading(SectionName::Scratchpad, Heading::scratchpad)
+    }
+
+    /// The headings of the sections other commands look for, which `journal fmt` keeps spelled the way they are configured
+    pub(crate) fn section_headings(&self) -> Vec<Heading> {
+        vec![
+            self.todo_heading(),
+            self.notes_heading(),
+            self.scratchpad_heading(),
+        ]
+    }
+
+    fn heading(&self, section: SectionName, default: fn() -> Heading) -> Heading {
+        self.headings.get(&section).cloned().unwrap_or_else(default)
+    }
+
+    /// The TODO configuration, if TODOs are carried over at all
+    pub(crate) fn todos(&self) -> Option<&TodoConfig> {
+        Some(&self.todos.inner).filter(|_| self.todos.is_enabled())
+    }
+
+    /// The reminders configuration, if reminders are turned on
+    pub(crate) fn reminders(&self) -> Option<&ReminderConfig> {
+        Some(&self.reminders.inner).filter(|_| self.reminders.is_enabled())
+    }
+
+    /// The credentials of every enabled integration, named after their config key
+    pub(crate) fn tokens(&self) -> Vec<(&'static str, &Token)> {
+        let mut tokens = Vec::new();
+
+        if let Some(ref pull_requests) = self.pull_requests {
+            if pull_requests.is_enabled() {
+                tokens.push(("pull_requests", pull_requests.inner.auth.token()));
+            }
+        }
+
+        if let Some(ref jira) = self.jira {
+            if jira.is_enabled() {
+                tokens.push(("jira", jira.inner.token()));
+            }
+        }
+
+        if let Some(ref worklog) = self.jira_worklog {
+            if worklog.is_enabled() {
+                tokens.push(("jira_worklog", worklog.inner.token()));
+            }
+        }
+
+        if let Some(passphrase) = self.signing().and_then(SigningConfig::passphrase) {
+            tokens.push(("signing", passphrase));
+        }
+
+        tokens
+    }
+
+    /// Tries to log into every enabled integration, named after their config key
+    pub(crate) async fn check_logins(&self) -> Vec<(&'static str, Result<String>)> {
+        let mut logins = Vec::new();
+
+        if let Some(ref pull_requests) = self.pull_requests {
+            if pull_requests.is_enabled() {
+                logins.push(("pull_requests", pull_requests.inner.check_login().await));
+            }
+        }
+
+        if let Some(ref jira) = self.jira {
+            if jira.is_enabled() {
+                logins.push(("jira", jira.inner.check_login().await));
+            }
+        }
+
+        if let Some(ref worklog) = self.jira_worklog {
+            if worklog.is_enabled() {
+                logins.push(("jira_worklog", worklog.inner.check_login().await));
+            }
+        }
+
+        logins
+    }
+
+    /// The cache for what remote sections fetched, if it is enabled
+    pub(crate) fn cache_config(&self) -> Option<&CacheConfig> {
+        self.cache
+            .as_ref()
+            .filter(|cache| cache.is_enabled())
+            .map(|cache| &cache.inner)
+    }
+
     pub fn enabled_sections(&self) -> HashMap<SectionName, Box<dyn Section>> {
+        self.sections_using(self.cache_config().map(|cache| cache.cache(&self.dir)))
+    }
+
+    /// The enabled sections that fetch from remotes, set up so what they fetch replaces what the cache kept
+    pub(crate) fn refreshing_remote_sections(&self) -> Vec<Box<dyn Section>> {
+        let cache = self
+            .cache_config()
+            .map(|cache| cache.refreshing().cache(&self.dir));
+        let mut sections = self.sections_using(cache);
+
+        [SectionName::Prs, SectionName::Tasks, SectionName::Worklog]
+            .iter()
+            .filter_map(|name| sections.remove(name))
+            .collect()
+    }
+
+    fn sections_using(&self, cache: Option<Cache>) -> HashMap<SectionName, Box<dyn Section>> {
         let mut sections = HashMap::new();
 
         if self.todos.is_enabled() {
-            sections.insert(
-                SectionName::Todos,
-                Box::new(self.todos.inner.clone()) as Box<dyn Section>,
-            );
+            let mut todos = self.todos.inner.clone();
+            if self.reminders.is_enabled() && self.reminders.inner.one_offs_as_todos {
+                todos.reminders = Some(self.reminders.inner.clone());
+            }
+            todos.date_stamp = self.date_stamp;
+            todos.filename = self.filename.clone();
+            todos.heading = self.todo_heading();
+
+            sections.insert(SectionName::Todos, Box::new(todos) as Box<dyn Section>);
         }
 
         if self.notes.is_enabled() {
@@ -96,18 +972,67 @@ impl Config {
 
         if let Some(ref jira) = self.jira {
             if jira.is_enabled() {
-                sections.insert(
-                    SectionName::Tasks,
-                    Box::new(jira.inner.clone()) as Box<dyn Section>,
-                );
+                let mut section = jira.inner.clone();
+                section.cache = cache.clone();
+                let section = jira
+                    .inner
+                    .failure
+                    .guard("jira", jira.inner.template(), section);
+                sections.insert(SectionName::Tasks, section);
+            }
+        }
+
+        if let Some(ref worklog) = self.jira_worklog {
+            if worklog.is_enabled() {
+                let mut section = worklog.inner.clone();
+                section.cache = cache.clone();
+                let section =
+                    worklog
+                        .inner
+                        .failure
+                        .guard("jira_worklog", worklog.inner.template(), section);
+                sections.insert(SectionName::Worklog, section);
             }
         }
 
         if let Some(ref pull_requests) = &self.pull_requests {
             if pull_requests.enabled {
+                let mut section = pull_requests.inner.clone();
+                section.cache = cache;
+                let section = pull_requests.inner.failure.guard(
+                    "pull_requests",
+                    pull_requests.inner.template(),
+                    section,
+                );
+                sections.insert(SectionName::Prs, section);
+            }
+        }
+
+        if let Some(ref scratchpad) = self.scratchpad {
+            if scratchpad.is_enabled() {
+                let mut scratchpad = scratchpad.inner.clone();
+                scratchpad.heading = self.scratchpad_heading();
+                sections.insert(
+                    SectionName::Scratchpad,
+                    Box::new(scratchpad) as Box<dyn Section>,
+                );
+            }
+        }
+
+        if let Some(ref countdowns) = self.countdowns {
+            if countdowns.is_enabled() {
                 sections.insert(
-                    SectionName::Prs,
-                    Box::new(pull_requests.inner.clone()) as Box<dyn Section>,
+                    SectionName::Countdowns,
+                    Box::new(countdowns.inner.clone()) as Box<dyn Section>,
+                );
+            }
+        }
+
+        if let Some(ref datafile) = self.datafile {
+            if datafile.is_enabled() {
+                sections.insert(
+                    SectionName::Datafile,
+                    Box::new(datafile.inner.clone()) as Box<dyn Section>,
                 );
             }
         }
@@ -118,7 +1043,12 @@ impl Config {
 
 #[async_trait::async_trait]
 pub trait Section {
-    async fn render(&self, journal: &Journal, clock: &dyn Clock) -> Result<String>;
+    async fn render(
+        &self,
+        journal: &Journal,
+        clock: &dyn Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String>;
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Hash)]
@@ -131,34 +1061,142 @@ pub enum SectionName {
     Prs,
     #[serde(rename = "jira")]
     Tasks,
+    #[serde(rename = "jira_worklog")]
+    Worklog,
     #[serde(rename = "reminders")]
     Reminders,
+    #[serde(rename = "scratchpad")]
+    Scratchpad,
+    #[serde(rename = "countdowns")]
+    Countdowns,
+    #[serde(rename = "datafile")]
+    Datafile,
 }
 
 pub fn default_order() -> Vec<SectionName> {
     use SectionName::*;
-    vec![Notes, Todos, Prs, Tasks, Reminders]
+    vec![
+        Notes, Todos, Prs, Tasks, Worklog, Reminders, Scratchpad, Countdowns, Datafile,
+    ]
 }
 
 impl Config {
-    pub fn config_path() -> Result<PathBuf> {
-        let config_path = std::env::var("JOURNAL__CONFIG").map_or_else(
+    /// Where the configuration is expected, whether it exists or not
+    pub fn location() -> PathBuf {
+        std::env::var("JOURNAL__CONFIG").map_or_else(
             |_| {
                 let home = dirs::home_dir().expect("Unable to get the the users 'home' directory");
                 home.join(".journal.yaml")
             },
             PathBuf::from,
-        );
+        )
+    }
+
+    pub fn config_path() -> Result<PathBuf> {
+        let config_path = Config::location();
 
         if !config_path.exists() {
-            bail!(format!("{} does not exist. We need a configuration file to work.\nYou can either use a '.journal.yaml' file in your HOME directory or configure it with the JOURNAL__CONFIG environment variable", config_path.to_string_lossy()));
+            bail!(format!("{} does not exist. We need a configuration file to work.\nRun 'journal init' to create one, use a '.journal.yaml' file in your HOME directory, or configure it with the JOURNAL__CONFIG environment variable", config_path.to_string_lossy()));
         }
 
         Ok(config_path)
     }
 
     pub fn from_reader(reader: impl Read) -> Result<Self> {
-        serde_yaml::from_reader(reader).map_err(|e| anyhow::anyhow!(e))
+        read(serde_yaml::Deserializer::from_reader(reader))
+    }
+
+    /// Reads the configuration at `path` with the files it includes, for the journal in `profile`
+    pub fn load(path: &Path, profile: Option<&str>) -> Result<Self> {
+        Config::with_profile(with_includes(path, &mut Vec::new())?, profile)
+    }
+
+    /// Reads the configuration of the journal in `profile`, or of the one at the top without a profile
+    pub fn for_profile(reader: impl Read, profile: Option<&str>) -> Result<Self> {
+        Config::with_profile(serde_yaml::from_reader(reader)?, profile)
+    }
+
+    fn with_profile(mut raw: Value, profile: Option<&str>) -> Result<Self> {
+        if let Some(profile) = profile {
+            let overrides = match raw
+                .get("profiles")
+                .and_then(|profiles| profiles.get(profile))
+            {
+                Some(overrides) => overrides.clone(),
+                None => {
+                    let known: Vec<_> = raw
+                        .get("profiles")
+                        .and_then(Value::as_mapping)
+                        .map(|profiles| {
+                            profiles
+                                .iter()
+                                .filter_map(|(name, _)| name.as_str())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    bail!(
+                        "There is no '{}' journal in the configuration, only: {}",
+                        profile,
+                        known.join(", ")
+                    );
+                }
+            };
+            override_with(&mut raw, overrides);
+        }
+        read(raw)
+    }
+}
+
+/// The configuration at `path` with the files it `include`s layered over it in order, so later ones win.
+/// Relative paths are relative to the file that includes them. `including` are the files on the way here.
+pub(crate) fn with_includes(path: &Path, including: &mut Vec<PathBuf>) -> Result<Value> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Could not find the configuration {:?}", path))?;
+    if including.contains(&canonical) {
+        bail!("{} includes itself", path.to_string_lossy());
+    }
+
+    let file = std::fs::File::open(path).with_context(|| format!("Could not open {:?}", path))?;
+    let mut raw: Value = serde_yaml::from_reader(file)
+        .with_context(|| format!("{} is not valid YAML", path.to_string_lossy()))?;
+    let includes: Vec<String> = match raw.get("include") {
+        Some(include) => serde_yaml::from_value(include.clone())
+            .context("'include' has to be a list of configuration files")?,
+        None => return Ok(raw),
+    };
+
+    including.push(canonical);
+    for include in includes {
+        let include = path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(expand_home(&include));
+        let mut overrides = with_includes(&include, including)?;
+        // What is included is already part of `overrides`
+        if let Value::Mapping(ref mut overrides) = overrides {
+            overrides.remove(&Value::from("include"));
+        }
+        override_with(&mut raw, overrides);
+    }
+    including.pop();
+    Ok(raw)
+}
+
+/// Replaces the settings in `base` with the ones in `overrides`, key by key within sections like `reminders`
+fn override_with(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Mapping(base), Value::Mapping(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => override_with(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
     }
 }
 
@@ -185,6 +1223,148 @@ mod tests {
         assert_eq!(sections, set(vec![Todos, Notes, Reminders]));
     }
 
+    #[test]
+    fn checks_the_heading_date_format_when_reading_it() {
+        let valid = "dir: journal\nheading_date_format: '[weekday], [day] [month repr:long]'\n";
+        assert!(Config::from_reader(valid.as_bytes()).is_ok());
+
+        let invalid = "dir: journal\nheading_date_format: '[weekday], [dya]'\n";
+        match Config::from_reader(invalid.as_bytes()) {
+            Ok(_) => panic!("'[dya]' should not be a valid date format"),
+            Err(e) => assert!(e.to_string().contains("is not a date format")),
+        }
+    }
+
+    #[test]
+    fn points_at_unused_keys_and_suggests_the_right_one() -> anyhow::Result<()> {
+        let yaml = indoc! {r#"
+            dir: /tmp/journal
+            reminders:
+              enabled: true
+              fiel: shared.json
+            profiles:
+              work:
+                acsii: true
+            "#};
+
+        let problems: Vec<_> = super::problems(yaml, &serde_yaml::from_str(yaml)?)?
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "4:3: 'reminders.fiel' is not a setting journal knows about, did you mean 'file'?",
+                "7:5: 'profiles.work.acsii' is not a setting journal knows about, did you mean 'ascii'?",
+            ]
+        );
+
+        let valid = indoc! {r#"
+            dir: /tmp/journal
+            headings: {}
+            templates: {}
+            reminders:
+              enabled: true
+              shared: []
+            "#};
+        assert_eq!(
+            super::problems(valid, &serde_yaml::from_str(valid)?)?,
+            vec![]
+        );
+
+        let nested = indoc! {r#"
+            dir: /tmp/journal
+            pull_requests:
+              enabled: true
+              auth:
+                personal_access_token: abc
+              select:
+                - repo: felipesere/journal
+                  label: [dependencies]
+              max_item: 5
+            "#};
+        let problems: Vec<_> = super::problems(nested, &serde_yaml::from_str(nested)?)?
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "8:7: 'pull_requests.select[0].label' is not a setting journal knows about",
+                "9:3: 'pull_requests.max_item' is not a setting journal knows about",
+            ]
+        );
+
+        let broken_yaml = "dir: /tmp/journal\nlocale: klingon\n";
+        let broken = super::problems(broken_yaml, &serde_yaml::from_str(broken_yaml)?)?;
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].location, Some((2, 9)));
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_settings_it_does_not_know_about() {
+        match Config::from_reader("dir: /tmp/journal\nacsii: true\n".as_bytes()) {
+            Ok(_) => panic!("'acsii' should not be a setting"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "'acsii' is not a setting journal knows about, did you mean 'ascii'?"
+            ),
+        }
+        match Config::from_reader(
+            "dir: /tmp/journal\ntodos:\n  enabled: true\n  look_back: 3\n".as_bytes(),
+        ) {
+            Ok(_) => panic!("'todos.look_back' should not be a setting"),
+            Err(e) => assert!(e
+                .to_string()
+                .starts_with("'todos.look_back' is not a setting journal knows about")),
+        }
+    }
+
+    #[test]
+    fn layers_included_files_over_the_config() -> anyhow::Result<()> {
+        let dir = assert_fs::TempDir::new()?;
+        let base = dir.path().join("journal.yaml");
+        std::fs::write(
+            &base,
+            indoc! {r#"
+            dir: /shared
+            ascii: true
+            reminders:
+              enabled: true
+            include: [machine.yaml]
+            profiles:
+              work:
+                dir: /work
+            "#},
+        )?;
+        std::fs::write(
+            dir.path().join("machine.yaml"),
+            "dir: /laptop\nreminders:\n  file: laptop.json\ninclude: [last.yaml]\n",
+        )?;
+        std::fs::write(dir.path().join("last.yaml"), "locale: de\n")?;
+
+        let config = Config::load(&base, None)?;
+        assert_eq!(config.dir, PathBuf::from("/laptop"));
+        assert_eq!(config.reminders.inner.file, PathBuf::from("laptop.json"));
+        assert_eq!(config.locale, crate::locale::Locale::De);
+        assert!(config.ascii);
+        assert_eq!(config.include, vec![PathBuf::from("machine.yaml")]);
+
+        // Profiles still have the last word
+        assert_eq!(
+            Config::load(&base, Some("work"))?.dir,
+            PathBuf::from("/work")
+        );
+
+        std::fs::write(dir.path().join("last.yaml"), "include: [journal.yaml]\n")?;
+        match Config::load(&base, None) {
+            Ok(_) => panic!("including a file in itself should fail"),
+            Err(e) => assert!(e.to_string().ends_with("journal.yaml includes itself")),
+        }
+        Ok(())
+    }
+
     #[test]
     fn minimal_config_with_all_defaults_disabled() {
         let r = indoc! { r#"
@@ -208,6 +1388,59 @@ mod tests {
         assert_eq!(sections, set(vec![]));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn writes_through_a_symlinked_configuration() -> anyhow::Result<()> {
+        let dir = assert_fs::TempDir::new()?;
+        let target = dir.path().join("dotfiles.yaml");
+        std::fs::write(&target, "dir: /journal\n")?;
+        let link = dir.path().join(".journal.yaml");
+        std::os::unix::fs::symlink(&target, &link)?;
+
+        super::write_config(&link, "dir: /elsewhere\n")?;
+
+        assert!(std::fs::symlink_metadata(&link)?.file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&target)?, "dir: /elsewhere\n");
+        Ok(())
+    }
+
+    #[test]
+    fn reminders_can_live_outside_the_journal() {
+        let config = Config::from_reader("dir: /journal".as_bytes()).unwrap();
+        assert_eq!(
+            config.reminders_file(),
+            PathBuf::from("/journal/reminders.json")
+        );
+
+        let r = indoc! { r#"
+                     dir: /journal
+
+                     reminders:
+                         enabled: true
+                         file: /sync/reminders.json
+                    "#
+        };
+        let config = Config::from_reader(r.as_bytes()).unwrap();
+        assert_eq!(
+            config.reminders_file(),
+            PathBuf::from("/sync/reminders.json")
+        );
+
+        let r = indoc! { r#"
+                     dir: /journal
+
+                     reminders:
+                         enabled: true
+                         file: ~/sync/reminders.json
+                    "#
+        };
+        let config = Config::from_reader(r.as_bytes()).unwrap();
+        assert_eq!(
+            config.reminders_file(),
+            dirs::home_dir().unwrap().join("sync/reminders.json")
+        );
+    }
+
     #[test]
     fn config_read_from_yml() {
         let r = indoc! { r#"
@@ -231,6 +1464,95 @@ mod tests {
         assert_eq!(sections, set(vec![Prs, Todos, Notes, Reminders]));
     }
 
+    #[test]
+    fn scratchpad_has_to_be_enabled_explicitly() {
+        let r = indoc! { r#"
+                    dir: file/from/yaml
+
+                    scratchpad:
+                      enabled: true
+                    "#
+        };
+
+        let config = Config::from_reader(r.as_bytes()).unwrap();
+
+        let sections: HashSet<_> = config.enabled_sections().into_keys().collect();
+        assert_eq!(sections, set(vec![Scratchpad, Todos, Notes, Reminders]));
+    }
+
+    #[test]
+    fn changes_settings_and_keeps_comments() -> anyhow::Result<()> {
+        let yaml = indoc! { r#"
+                    # Where the entries are kept
+                    dir: '/tmp/journal'
+
+                    todos:
+                      enabled: true   # carried over every day
+                    "#
+        };
+
+        assert_eq!(
+            super::set(yaml, "todos.enabled", "false")?,
+            indoc! { r#"
+                    # Where the entries are kept
+                    dir: '/tmp/journal'
+
+                    todos:
+                      enabled: false   # carried over every day
+                    "#
+            }
+        );
+
+        let added = super::set(yaml, "reminders.enabled", "false")?;
+        let config = Config::from_reader(added.as_bytes())?;
+        assert!(!config.reminders.is_enabled());
+        assert!(config.todos.is_enabled());
+
+        assert!(super::set(yaml, "todos.enabled", "maybe").is_err());
+        assert!(super::set(yaml, "todoz.enabled", "false").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn profiles_override_the_settings_above() -> anyhow::Result<()> {
+        let r = indoc! { r#"
+            dir: /journal/personal
+            ascii: true
+            reminders:
+              enabled: true
+              file: reminders.json
+            profiles:
+              work:
+                dir: /journal/work
+                reminders:
+                  file: work-reminders.json
+                scratchpad:
+                  enabled: true
+            "#
+        };
+
+        let personal = Config::for_profile(r.as_bytes(), None)?;
+        assert_eq!(personal.dir, PathBuf::from("/journal/personal"));
+        assert!(personal.scratchpad.is_none());
+
+        let work = Config::for_profile(r.as_bytes(), Some("work"))?;
+        assert_eq!(work.dir, PathBuf::from("/journal/work"));
+        assert!(work.ascii);
+        assert!(work.reminders.is_enabled());
+        assert_eq!(
+            work.reminders_file(),
+            PathBuf::from("/journal/work/work-reminders.json")
+        );
+        assert!(work.scratchpad.is_some());
+
+        let err = Config::for_profile(r.as_bytes(), Some("side-project")).err();
+        assert_eq!(
+            err.map(|e| e.to_string()),
+            Some("There is no 'side-project' journal in the configuration, only: work".to_string())
+        );
+        Ok(())
+    }
+
     fn set<T: std::hash::Hash + std::cmp::Eq>(elements: Vec<T>) -> HashSet<T> {
         HashSet::from_iter(elements)
     }
@@ -0,0 +1,147 @@
+use anyhow::Result;
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+use time::Date;
+
+use crate::config::Section;
+use crate::storage::Journal;
+use crate::Clock;
+
+const COUNTDOWNS: &str = r#"
+## Countdowns
+
+{{#each countdowns as | countdown | }}
+* {{countdown.description}}
+{{/each }}
+"#;
+
+/// Named dates the journal counts down to, e.g. releases or conference talks
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CountdownConfig {
+    #[serde(default)]
+    dates: Vec<Countdown>,
+    template: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+struct Countdown {
+    name: String,
+    date: Date,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct Remaining {
+    name: String,
+    days: i64,
+    description: String,
+}
+
+impl CountdownConfig {
+    /// The countdowns that have not passed yet, closest first
+    fn remaining(&self, today: Date) -> Vec<Remaining> {
+        let mut remaining = self
+            .dates
+            .iter()
+            .filter(|countdown| countdown.date >= today)
+            .map(|countdown| {
+                let days = (countdown.date - today).whole_days();
+                let description = match days {
+                    0 => format!("{} is today", countdown.name),
+                    1 => format!("1 day until {}", countdown.name),
+                    n => format!("{} days until {}", n, countdown.name),
+                };
+
+                Remaining {
+                    name: countdown.name.clone(),
+                    days,
+                    description,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        remaining.sort_by_key(|countdown| countdown.days);
+        remaining
+    }
+}
+
+#[async_trait::async_trait]
+impl Section for CountdownConfig {
+    async fn render(
+        &self,
+        _: &Journal,
+        clock: &dyn Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String> {
+        let countdowns = self.remaining(clock.today());
+
+        #[derive(Serialize)]
+        struct C {
+            countdowns: Vec<Remaining>,
+        }
+
+        let template = self
+            .template
+            .clone()
+            .unwrap_or_else(|| COUNTDOWNS.to_string());
+
+        let mut tt = handlebars.clone();
+        tt.register_template_string("countdowns", template)?;
+        tt.render("countdowns", &C { countdowns })
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use time::macros::date;
+
+    #[test]
+    fn only_shows_upcoming_countdowns_closest_first() {
+        let config: CountdownConfig = serde_yaml::from_str(indoc! {r#"
+        dates:
+          - name: RustConf talk
+            date: 2022-09-12
+          - name: the release
+            date: 2022-03-14
+          - name: the offsite
+            date: 2022-03-01
+          - name: the launch party
+            date: 2022-03-12
+        "#})
+        .unwrap();
+
+        let descriptions = config
+            .remaining(date!(2022 - 03 - 11))
+            .into_iter()
+            .map(|countdown| countdown.description)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            descriptions,
+            vec![
+                "1 day until the launch party",
+                "3 days until the release",
+                "185 days until RustConf talk",
+            ]
+        );
+    }
+
+    #[test]
+    fn countdowns_end_on_the_day_itself() {
+        let config: CountdownConfig = serde_yaml::from_str(indoc! {r#"
+        dates:
+          - name: the release
+            date: 2022-03-14
+        "#})
+        .unwrap();
+
+        let remaining = config.remaining(date!(2022 - 03 - 14));
+        assert_eq!(remaining[0].description, "the release is today");
+
+        assert!(config.remaining(date!(2022 - 03 - 15)).is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use clap::StructOpt;
+use notify_rust::Notification;
+use time::{Date, OffsetDateTime, UtcOffset};
+
+use crate::config::Section;
+use crate::reminders::TimeOfDay;
+use crate::storage::Journal;
+use crate::{Clock, Config};
+
+/// Checking more often than this wouldn't show anything new, reminders are due on the minute
+const MIN_INTERVAL_IN_MINUTES: u64 = 1;
+
+/// Keeps running to show reminders as they become due and to keep what remote sections
+/// fetched fresh in the cache, so new entries don't have to wait for them.
+#[derive(Debug, StructOpt)]
+pub struct DaemonCmd {
+    /// How many minutes to wait between checks for due reminders, at least 1
+    #[clap(long, default_value = "5")]
+    every_minutes: u64,
+    /// How many minutes to wait between fetching remote sections again, the cache's `ttl_minutes` if left out
+    #[clap(long)]
+    refresh_minutes: Option<u64>,
+}
+
+impl DaemonCmd {
+    pub(crate) async fn execute(
+        &self,
+        config: &Config,
+        journal: &Journal,
+        clock: &impl Clock,
+    ) -> Result<()> {
+        // Without a cache there is nowhere to keep what was fetched
+        let remote = match config.cache_config() {
+            Some(_) => config.refreshing_remote_sections(),
+            None => Vec::new(),
+        };
+        if !config.reminders.is_enabled() && remote.is_empty() {
+            bail!("There is nothing to do without reminders or a cache for remote sections, please enable them first");
+        }
+
+        let offset = clock.local_offset().unwrap_or_else(|| {
+            tracing::warn!(
+                "Could not determine the local time zone, times of reminders are in UTC"
+            );
+            UtcOffset::UTC
+        });
+
+        let mut check = tokio::time::interval(minutes(self.every_minutes));
+        let refresh_minutes = self
+            .refresh_minutes
+            .or_else(|| {
+                config
+                    .cache_config()
+                    .map(|cache| cache.ttl().whole_minutes().max(0) as u64)
+            })
+            .unwrap_or(MIN_INTERVAL_IN_MINUTES);
+        let mut refresh = tokio::time::interval(minutes(refresh_minutes));
+        let mut notifier = Notifier::default();
+
+        let stop = tokio::signal::ctrl_c();
+        tokio::pin!(stop);
+
+        println!("Running until stopped with Ctrl-C");
+        loop {
+            tokio::select! {
+                _ = check.tick(), if config.reminders.is_enabled() => {
+                    let now = OffsetDateTime::now_utc().to_offset(offset);
+                    notify_due(config, journal, clock, &mut notifier, now).await;
+                }
+                _ = refresh.tick(), if !remote.is_empty() => {
+                    tokio::select! {
+                        _ = refresh_all(&remote, journal, clock, config) => {}
+                        _ = &mut stop => break,
+                    }
+                }
+                _ = &mut stop => break,
+            }
+        }
+
+        println!("Stopped");
+        Ok(())
+    }
+}
+
+fn minutes(minutes: u64) -> Duration {
+    Duration::from_secs(60 * minutes.max(MIN_INTERVAL_IN_MINUTES))
+}
+
+async fn notify_due(
+    config: &Config,
+    journal: &Journal,
+    clock: &impl Clock,
+    notifier: &mut Notifier,
+    now: OffsetDateTime,
+) {
+    let reminders = match config.reminders() {
+        Some(reminders) => reminders,
+        None => return,
+    };
+
+    // Reminders are read again each time to pick up the ones added in the meantime
+    match reminders.load_with_shared(journal).await {
+        Ok(reminders) => {
+            let due = due_by(now, reminders.timed_for_today(clock));
+            for reminder in notifier.not_yet_notified(clock.today(), due) {
+                println!("{}", reminder);
+                if let Err(e) = notify(&reminder) {
+                    tracing::warn!("Could not show a notification: {:#}", e);
+                }
+            }
+        }
+        Err(e) => tracing::warn!("Could not check reminders: {:#}", e),
+    }
+}
+
+/// The reminders that are due by `now`, the ones without a time are due all day
+fn due_by(now: OffsetDateTime, reminders: Vec<(Option<TimeOfDay>, String)>) -> Vec<String> {
+    let now = TimeOfDay {
+        hour: now.hour(),
+        minute: now.minute(),
+    };
+
+    reminders
+        .into_iter()
+        .filter(|(at, _)| at.is_none_or(|at| at <= now))
+        .map(|(_, reminder)| reminder)
+        .collect()
+}
+
+/// Fetches every remote section again so the cache has what they have now
+async fn refresh_all(
+    sections: &[Box<dyn Section>],
+    journal: &Journal,
+    clock: &impl Clock,
+    config: &Config,
+) {
+    let handlebars = match config.handlebars(clock.today()) {
+        Ok(handlebars) => handlebars,
+        Err(e) => {
+            tracing::warn!("Could not refresh remote sections: {:#}", e);
+            return;
+        }
+    };
+
+    for section in sections {
+        if let Err(e) = section.render(journal, clock, &handlebars).await {
+            tracing::warn!("Could not refresh a remote section: {:#}", e);
+        }
+    }
+}
+
+/// Remembers which reminders were already shown so each one only pops up once a day
+#[derive(Default)]
+struct Notifier {
+    day: Option<Date>,
+    notified: HashSet<String>,
+}
+
+impl Notifier {
+    fn not_yet_notified(&mut self, today: Date, due: Vec<String>) -> Vec<String> {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.notified.clear();
+        }
+
+        due.into_iter()
+            .filter(|reminder| self.notified.insert(reminder.clone()))
+            .collect()
+    }
+}
+
+/// Shows a desktop notification, through the notification center on macOS and D-Bus on Linux
+pub(crate) fn notify(reminder: &str) -> Result<()> {
+    Notification::new()
+        .summary("journal")
+        .body(reminder)
+        .show()
+        .context("Could not show the notification")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime};
+
+    #[test]
+    fn reminders_with_a_time_are_due_from_then_on() {
+        let at = |hour, minute| Some(TimeOfDay { hour, minute });
+        let reminders = || {
+            vec![
+                (at(9, 0), "09:00 — Standup".to_string()),
+                (at(14, 30), "14:30 — Dentist".to_string()),
+                (None, "Water the plants".to_string()),
+            ]
+        };
+
+        assert_eq!(
+            due_by(datetime!(2022-03-11 8:59 UTC), reminders()),
+            vec!["Water the plants"]
+        );
+        assert_eq!(
+            due_by(datetime!(2022-03-11 14:30 UTC), reminders()),
+            vec!["09:00 — Standup", "14:30 — Dentist", "Water the plants"]
+        );
+    }
+
+    #[test]
+    fn notifies_once_per_reminder_and_day() {
+        let mut notifier = Notifier::default();
+        let due = || vec!["Water the plants".to_string(), "Dentist".to_string()];
+
+        assert_eq!(
+            notifier.not_yet_notified(date!(2022 - 03 - 11), due()),
+            due()
+        );
+        assert!(notifier
+            .not_yet_notified(date!(2022 - 03 - 11), due())
+            .is_empty());
+        assert_eq!(
+            notifier.not_yet_notified(
+                date!(2022 - 03 - 11),
+                vec!["Dentist".to_string(), "Added later".to_string()]
+            ),
+            vec!["Added later"]
+        );
+
+        assert_eq!(
+            notifier.not_yet_notified(date!(2022 - 03 - 12), due()),
+            due()
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use time::Date;
+
+use crate::config::Section;
+use crate::storage::{Journal, YEAR_MONTH_DAY};
+use crate::Clock;
+
+const DATAFILE: &str = r#"
+## Today's data
+
+{{#each rows as | row | }}
+* {{#each row}}{{@key}}: {{this}} {{/each}}
+{{/each }}
+"#;
+
+/// A local CSV or JSON file, e.g. an export of a sleep tracker,
+/// of which today's rows are shown in the entry
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DatafileConfig {
+    /// Relative to the journal, unless it is absolute or starts with `~/`
+    path: PathBuf,
+    /// Explicitly set the format, otherwise it is guessed from the file extension
+    format: Option<Format>,
+    /// The column or key that contains the date of the row
+    #[serde(default = "default_date_field")]
+    date_field: String,
+    /// How dates are written in the file, as a `time` format description
+    date_format: Option<String>,
+    template: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Format {
+    Csv,
+    Json,
+}
+
+fn default_date_field() -> String {
+    "date".to_string()
+}
+
+impl DatafileConfig {
+    fn format(&self) -> Result<Format> {
+        if let Some(format) = self.format {
+            return Ok(format);
+        }
+
+        match self.path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => Ok(Format::Csv),
+            Some("json") => Ok(Format::Json),
+            _ => bail!(
+                "Can't tell whether {:?} is CSV or JSON, please set 'format'",
+                self.path
+            ),
+        }
+    }
+
+    fn rows(&self, path: &Path) -> Result<Vec<Map<String, Value>>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read datafile {:?}", path))?;
+
+        match self.format()? {
+            Format::Json => serde_json::from_str(&content)
+                .with_context(|| format!("Expected a list of objects in {:?}", path)),
+            Format::Csv => {
+                let mut reader = csv::Reader::from_reader(content.as_bytes());
+                let headers = reader.headers()?.clone();
+
+                let mut rows = Vec::new();
+                for record in reader.records() {
+                    let row = headers
+                        .iter()
+                        .zip(record?.iter())
+                        .map(|(header, value)| (header.to_string(), Value::from(value)))
+                        .collect();
+                    rows.push(row);
+                }
+                Ok(rows)
+            }
+        }
+    }
+
+    /// The rows of the datafile, which is at `path` in the journal, that are about `today`
+    fn rows_for(&self, path: &Path, today: Date) -> Result<Vec<Map<String, Value>>> {
+        let today = match self.date_format {
+            Some(ref format) => {
+                let format = time::format_description::parse(format)
+                    .with_context(|| format!("Invalid date_format '{}'", format))?;
+                today.format(&format)?
+            }
+            None => today.format(YEAR_MONTH_DAY)?,
+        };
+
+        // Comparing the start allows for timestamps like `2022-03-11T07:30`
+        Ok(self
+            .rows(path)?
+            .into_iter()
+            .filter(|row| {
+                row.get(&self.date_field)
+                    .and_then(Value::as_str)
+                    .map(|date| date.starts_with(&today))
+                    .unwrap_or(false)
+            })
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl Section for DatafileConfig {
+    async fn render(
+        &self,
+        journal: &Journal,
+        clock: &dyn Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String> {
+        let rows = self.rows_for(&journal.child_file(&self.path), clock.today())?;
+
+        #[derive(Serialize)]
+        struct C {
+            rows: Vec<Map<String, Value>>,
+        }
+
+        let template = self
+            .template
+            .clone()
+            .unwrap_or_else(|| DATAFILE.to_string());
+
+        let mut tt = handlebars.clone();
+        tt.register_template_string("datafile", template)?;
+        tt.render("datafile", &C { rows })
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+    use indoc::indoc;
+    use time::macros::date;
+
+    fn config(path: PathBuf) -> DatafileConfig {
+        DatafileConfig {
+            path,
+            format: None,
+            date_field: default_date_field(),
+            date_format: None,
+            template: None,
+        }
+    }
+
+    #[test]
+    fn picks_todays_rows_from_a_csv_file() -> Result<()> {
+        let dir = TempDir::new()?;
+        let file = dir.child("sleep.csv");
+        file.write_str(indoc! {r#"
+        date,hours,quality
+        2022-03-10,7.5,good
+        2022-03-11T06:30,6,bad
+        "#})?;
+
+        let rows = config(file.to_path_buf()).rows_for(file.path(), date!(2022 - 03 - 11))?;
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["quality"], "bad");
+        Ok(())
+    }
+
+    #[test]
+    fn picks_todays_rows_from_a_json_file_with_custom_dates() -> Result<()> {
+        let dir = TempDir::new()?;
+        let file = dir.child("workouts.json");
+        file.write_str(indoc! {r#"
+        [
+            {"day": "11.03.2022", "workout": "running"},
+            {"day": "12.03.2022", "workout": "rowing"}
+        ]
+        "#})?;
+
+        let mut config = config(file.to_path_buf());
+        config.date_field = "day".to_string();
+        config.date_format = Some("[day].[month].[year]".to_string());
+
+        let rows = config.rows_for(file.path(), date!(2022 - 03 - 11))?;
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["workout"], "running");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reads_the_file_relative_to_the_journal() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("data/sleep.csv")
+            .write_str("date,hours\n2022-03-11,8\n")?;
+
+        let rendered = config(PathBuf::from("data/sleep.csv"))
+            .render(
+                &Journal::new_at(dir.path()),
+                &crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 11)?,
+                &Handlebars::new(),
+            )
+            .await?;
+
+        assert!(rendered.contains("hours: 8"));
+        Ok(())
+    }
+
+    #[test]
+    fn needs_a_format_for_unknown_extensions() {
+        let config = config(PathBuf::from("data.txt"));
+
+        assert!(config.format().is_err());
+    }
+}
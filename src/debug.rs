@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::StructOpt;
+use serde_yaml::Value;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::storage::{date_from_filename, YEAR_MONTH_DAY};
+use crate::Config;
+
+/// Config keys whose values could give away credentials
+const SENSITIVE_KEYS: &[&str] = &["token", "passphrase", "password", "secret", "key"];
+
+/// How much of each trace file is included, the end being the interesting part
+const TRACE_LINES: usize = 2000;
+
+#[derive(Debug, StructOpt)]
+pub enum DebugCmd {
+    /// Collect the redacted configuration, version, and the layout of the journal into a zip
+    /// to attach to a bug report. The contents of entries are never included.
+    Bundle {
+        /// Where to write the bundle to
+        #[clap(long, default_value = "journal-debug.zip")]
+        output: PathBuf,
+        /// Trace files written with `--trace-file` to include
+        #[clap(long = "trace")]
+        traces: Vec<PathBuf>,
+    },
+}
+
+impl DebugCmd {
+    pub(crate) fn execute(&self, config: &Config) -> Result<()> {
+        match self {
+            DebugCmd::Bundle { output, traces } => {
+                let file = std::fs::File::create(output)
+                    .with_context(|| format!("Could not create {:?}", output))?;
+                let mut zip = ZipWriter::new(file);
+                let options = FileOptions::default();
+
+                let mut add = |name: &str, content: &str| -> Result<()> {
+                    zip.start_file(name, options)?;
+                    zip.write_all(content.as_bytes())?;
+                    Ok(())
+                };
+
+                add("version.txt", &version_info())?;
+                add("config.yml", &redacted_config(config)?)?;
+                add("listing.txt", &listing(&config.dir)?)?;
+                for trace in traces {
+                    let content = std::fs::read_to_string(trace)
+                        .with_context(|| format!("Could not read trace file {:?}", trace))?;
+                    let name = trace
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "journal.trace".to_string());
+                    add(&format!("traces/{}", name), &tail(&content, TRACE_LINES))?;
+                }
+                zip.finish()?;
+
+                println!("Wrote {}", output.to_string_lossy());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn version_info() -> String {
+    format!(
+        "journal {}\nos: {}\narch: {}\nconfig: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        Config::config_path()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|e| e.to_string()),
+    )
+}
+
+/// The configuration as `journal config show` prints it, without anything that looks like a credential
+fn redacted_config(config: &Config) -> Result<String> {
+    let mut value = serde_yaml::to_value(config)?;
+    redact(&mut value);
+    Ok(serde_yaml::to_string(&value)?)
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, value) in mapping.iter_mut() {
+                let sensitive = key
+                    .as_str()
+                    .map(|key| SENSITIVE_KEYS.iter().any(|s| key.contains(s)))
+                    .unwrap_or(false);
+                if sensitive {
+                    *value = Value::String("***".to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Sequence(values) => values.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// The size and modification time of every file in the journal, but not what is in them.
+/// Filenames carry the titles of entries, so only the date of an entry is shown and other files are just counted.
+fn listing(dir: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+
+    let mut entries = Vec::new();
+    let mut others: BTreeMap<PathBuf, usize> = BTreeMap::new();
+    for path in files {
+        let folder = path
+            .parent()
+            .and_then(|parent| parent.strip_prefix(dir).ok())
+            .unwrap_or_else(|| Path::new(""))
+            .to_path_buf();
+        match date_from_filename(&path) {
+            Some(date) => entries.push((folder, date, path)),
+            None => *others.entry(folder).or_default() += 1,
+        }
+    }
+    entries.sort();
+
+    let mut listing = String::new();
+    for (folder, date, path) in entries {
+        let metadata = std::fs::metadata(&path)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| OffsetDateTime::from(modified).format(&Rfc3339).ok())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        listing.push_str(&format!(
+            "{}\tentry on {}\t{} bytes\t{}\n",
+            shown(&folder),
+            date.format(YEAR_MONTH_DAY)?,
+            metadata.len(),
+            modified
+        ));
+    }
+    for (folder, count) in others {
+        listing.push_str(&format!("{}\t{} other files\n", shown(&folder), count));
+    }
+    Ok(listing)
+}
+
+fn shown(folder: &Path) -> String {
+    if folder.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        folder.to_string_lossy().to_string()
+    }
+}
+
+/// Every file in `dir` and its folders, leaving out hidden ones like `.git`
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Could not list {:?}", dir))? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn tail(content: &str, lines: usize) -> String {
+    let all = content.lines().collect::<Vec<_>>();
+    let start = all.len().saturating_sub(lines);
+    all[start..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn removes_anything_that_looks_like_a_credential() -> Result<()> {
+        let mut value: Value = serde_yaml::from_str(indoc! {r#"
+            dir: /Users/me/journal
+            pull_requests:
+              enabled: true
+              auth:
+                personal_access_token:
+                  command: "echo abc"
+            signing:
+              passphrase: "hunter2"
+              tool: gpg
+            "#})?;
+
+        redact(&mut value);
+
+        assert_eq!(
+            value,
+            serde_yaml::from_str::<Value>(indoc! {r#"
+            dir: /Users/me/journal
+            pull_requests:
+              enabled: true
+              auth:
+                personal_access_token: "***"
+            signing:
+              passphrase: "***"
+              tool: gpg
+            "#})?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lists_files_without_their_contents_or_titles() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("2022-03-11-planning.md")
+            .write_str("# Planning on 2022-03-11")?;
+        dir.child("work/2022-03-10-standup.md")
+            .write_str("# Standup")?;
+        dir.child("projects/apollo.md").write_str("# Project")?;
+        dir.child("projects/zeus.md").write_str("# Project")?;
+        dir.child(".git/HEAD").write_str("ref: refs/heads/main")?;
+
+        let listing = listing(dir.path())?;
+
+        let lines = listing
+            .lines()
+            .map(|line| line.split('\t').take(3).collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                ". entry on 2022-03-11 24 bytes",
+                "work entry on 2022-03-10 9 bytes",
+                "projects 2 other files",
+            ]
+        );
+        assert!(!listing.contains("Planning"));
+        assert!(!listing.contains("planning"));
+        assert!(!listing.contains("apollo"));
+        assert!(!listing.contains(".git"));
+        Ok(())
+    }
+}
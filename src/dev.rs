@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::StructOpt;
+use time::{Date, Duration};
+
+use crate::config::SectionName;
+use crate::storage::Journal;
+use crate::template::Template;
+use crate::todo::FindTodos;
+use crate::{normalize_filename, Clock, Config};
+
+const TOPICS: &[&str] = &[
+    "Planning the migration",
+    "Retro with team Apollo",
+    "Through the Looking-Glass",
+    "Debugging the flaky deploy",
+    "Quarterly goals",
+    "One-on-one with Jessica",
+    "On-call handover",
+    "Reading group",
+];
+
+const TASKS: &[&str] = &[
+    "Review the PR for the new importer",
+    "Send the marketing figures",
+    "Update the runbook",
+    "Book the offsite venue",
+    "Write up the incident report",
+    "Check in with team Mars",
+    "Rotate the API keys",
+];
+
+#[derive(Debug, StructOpt)]
+pub enum DevCmd {
+    /// Fill a journal with synthetic entries, e.g. to measure performance on a decade of entries
+    Seed {
+        /// How many days worth of entries to create, ending today
+        #[clap(long, default_value = "365")]
+        days: u32,
+        /// Where to create the entries instead of the configured journal
+        #[clap(long)]
+        into: Option<PathBuf>,
+    },
+}
+
+impl DevCmd {
+    pub(crate) fn execute(&self, config: &Config, clock: &dyn Clock) -> Result<()> {
+        match self {
+            DevCmd::Seed { days, into } => {
+                let dir = into.clone().unwrap_or_else(|| config.dir.clone());
+                std::fs::create_dir_all(&dir)?;
+                let journal = Journal::new_at(dir);
+
+                if !journal.entries()?.is_empty() {
+                    bail!("Refusing to seed a journal that already has entries");
+                }
+
+                let created = seed(&journal, clock.today(), *days)?;
+                println!("Created {} entries", created);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A tiny deterministic generator so every seeded journal is the same workload
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % bound
+    }
+}
+
+/// Writes one entry per day for the `days` days up to and including `last_day`
+pub fn seed(journal: &Journal, last_day: Date, days: u32) -> Result<usize> {
+    let mut rng = Lcg(days as u64);
+
+    for offset in (0..days).rev() {
+        let today = last_day - Duration::days(offset as i64);
+        let title = TOPICS[rng.next(TOPICS.len())].to_string();
+
+        let todos = (0..1 + rng.next(6))
+            .map(|_| {
+                let done = if rng.next(3) == 0 { "x" } else { " " };
+                format!("* [{}] {}", done, TASKS[rng.next(TASKS.len())])
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let notes = (0..rng.next(4))
+            .map(|_| format!("* {}", TOPICS[rng.next(TOPICS.len())]))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let template = Template {
+            title: title.clone(),
+            today,
+            sections: HashMap::from([
+                (SectionName::Notes, format!("## Notes\n\n{}", notes)),
+                (SectionName::Todos, format!("## TODOs\n\n{}\n", todos)),
+            ]),
+        };
+
+        let filename = format!("{}-{}.md", today, normalize_filename(&title));
+        journal.add_entry(&filename, &template.render(Vec::new())?)?;
+    }
+
+    Ok(days as usize)
+}
+
+/// The open TODOs that would be carried over from `markdown` into the next entry
+pub fn carry_over(markdown: &str) -> Vec<String> {
+    FindTodos::new().process(markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use time::macros::date;
+
+    #[test]
+    fn seeds_one_entry_per_day() -> Result<()> {
+        let dir = TempDir::new()?;
+        let journal = Journal::new_at(dir.path());
+
+        seed(&journal, date!(2022 - 03 - 11), 30)?;
+
+        let entries = journal.entries()?;
+        assert_eq!(entries.len(), 30);
+
+        let latest = journal.latest_entry()?.unwrap();
+        assert_eq!(latest.date(), Some(date!(2022 - 03 - 11)));
+        assert!(latest.markdown.contains("## TODOs"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use clap::StructOpt;
+use handlebars::Handlebars;
+use indoc::indoc;
+use serde::Serialize;
+use time::{Date, Duration};
+
+use crate::scratchpad::find_section;
+use crate::storage::{Journal, YEAR_MONTH_DAY};
+use crate::template::Heading;
+use crate::todo::{todo_text, FindTodos};
+use crate::{Clock, Config};
+
+/// The section of an entry whose bullets make it into the digest as they are
+const HIGHLIGHTS: &str = "## Highlights";
+
+/// How many of the longest carried-over TODOs make it into the digest
+const TOP_CARRIED_OVER: usize = 5;
+
+const DIGEST: &str = indoc! {r#"
+# Digest from {{from}} to {{to}}
+
+## Entries ({{len entries}})
+
+{{#each entries}}
+* {{date}}: {{title}}
+{{else}}
+Nothing written this week.
+{{/each}}
+
+## Completed TODOs ({{len completed}})
+
+{{#each completed}}
+* {{this}}
+{{else}}
+Nothing completed this week.
+{{/each}}
+
+## Highlights
+
+{{#each highlights}}
+* {{this}}
+{{else}}
+No highlights this week.
+{{/each}}
+
+## Carried over the longest
+
+{{#each carried_over}}
+* {{todo}} (in {{entries}} entries)
+{{else}}
+Nothing was carried over.
+{{/each}}
+"#};
+
+#[derive(Debug, StructOpt)]
+pub struct DigestCmd {
+    /// Summarize the last seven days, including today
+    #[clap(long)]
+    week: bool,
+    /// Either `markdown` or `html`
+    #[clap(long, default_value = "markdown")]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Markdown,
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(Format::Markdown),
+            "html" => Ok(Format::Html),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+impl DigestCmd {
+    pub(crate) fn execute(
+        &self,
+        config: &Config,
+        journal: &Journal,
+        clock: &dyn Clock,
+    ) -> Result<()> {
+        if !self.week {
+            bail!("Pass --week to summarize the last seven days");
+        }
+
+        let today = clock.today();
+        let digest = Digest::collect(
+            journal,
+            today - Duration::days(6),
+            today,
+            &config.todo_heading(),
+        )?;
+        let markdown = digest.render(&config.handlebars(today)?)?;
+
+        match self.format {
+            Format::Markdown => print!("{}", markdown),
+            Format::Html => {
+                let mut html = String::new();
+                pulldown_cmark::html::push_html(&mut html, pulldown_cmark::Parser::new(&markdown));
+                print!("{}", html);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Digest {
+    from: String,
+    to: String,
+    entries: Vec<EntrySummary>,
+    completed: Vec<String>,
+    highlights: Vec<String>,
+    carried_over: Vec<CarriedOver>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct EntrySummary {
+    date: String,
+    title: String,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct CarriedOver {
+    todo: String,
+    entries: usize,
+}
+
+impl Digest {
+    /// Summarizes the entries written between `from` and `to`, both inclusive,
+    /// with the TODOs found beneath `heading`
+    fn collect(journal: &Journal, from: Date, to: Date, heading: &Heading) -> Result<Digest> {
+        let mut entries = Vec::new();
+        let mut completed = Vec::new();
+        let mut highlights = Vec::new();
+        let highlights_heading: Heading = HIGHLIGHTS.parse().map_err(anyhow::Error::msg)?;
+        let mut open_in = HashMap::new();
+        let mut still_open = Vec::new();
+
+        for path in journal.entries()? {
+            let entry = journal.read_entry(path)?;
+            match entry.date() {
+                Some(date) if from <= date && date <= to => {}
+                _ => continue,
+            }
+
+            entries.push(EntrySummary {
+                date: entry.describe(),
+                title: entry.title(),
+            });
+
+            for range in FindTodos::new()
+                .under(heading)
+                .completed_todo_ranges(&entry.markdown)
+            {
+                let todo = todo_text(&entry.markdown[range]);
+                if !completed.contains(&todo) {
+                    completed.push(todo);
+                }
+            }
+
+            if let Some(section) = find_section(&entry.markdown, &highlights_heading) {
+                highlights.extend(
+                    section
+                        .lines()
+                        .map(|line| line.trim().trim_start_matches(['*', '-', '+']).trim())
+                        .filter(|line| !line.is_empty())
+                        .map(str::to_string),
+                );
+            }
+
+            still_open = FindTodos::new()
+                .under(heading)
+                .open_todo_ranges(&entry.markdown)
+                .into_iter()
+                .map(|range| todo_text(&entry.markdown[range]))
+                .collect::<Vec<_>>();
+            for todo in &still_open {
+                *open_in.entry(todo.clone()).or_insert(0) += 1;
+            }
+        }
+
+        // Only what is still open in the latest entry counts, and only if it
+        // was actually carried over at least once
+        let mut carried_over = still_open
+            .into_iter()
+            .map(|todo| CarriedOver {
+                entries: open_in[&todo],
+                todo,
+            })
+            .filter(|carried| carried.entries > 1)
+            .collect::<Vec<_>>();
+        carried_over.sort_by_key(|carried| std::cmp::Reverse(carried.entries));
+        carried_over.truncate(TOP_CARRIED_OVER);
+
+        Ok(Digest {
+            from: from.format(YEAR_MONTH_DAY)?,
+            to: to.format(YEAR_MONTH_DAY)?,
+            entries,
+            completed,
+            highlights,
+            carried_over,
+        })
+    }
+
+    fn render(&self, handlebars: &Handlebars<'static>) -> Result<String> {
+        let mut tt = handlebars.clone();
+        tt.register_template_string("digest", DIGEST)?;
+        tt.render("digest", self).map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+    use pretty_assertions::assert_eq;
+    use time::macros::date;
+
+    fn week_of_entries() -> Result<TempDir> {
+        let dir = TempDir::new()?;
+        dir.child("2022-03-01-too-old.md").write_str(indoc! {r#"
+            # Too old on 2022-03-01
+
+            ## TODOs
+
+            * [x] Something from last week
+            "#})?;
+        dir.child("2022-03-07-planning.md").write_str(indoc! {r#"
+            # Planning on 2022-03-07
+
+            ## TODOs
+
+            * [ ] Update the runbook
+            * [ ] Send the marketing figures
+            "#})?;
+        dir.child("2022-03-09-retro.md").write_str(indoc! {r#"
+            # Retro on 2022-03-09
+
+            ## Highlights
+
+            * Shipped the importer
+
+            ## TODOs
+
+            * [ ] Update the runbook
+            * [x] Send the marketing figures
+            "#})?;
+        dir.child("2022-03-11-reading-group.md")
+            .write_str(indoc! {r#"
+            # Reading group on 2022-03-11
+
+            ## TODOs
+
+            * [ ] Update the runbook
+            * [ ] Book the offsite venue
+            "#})?;
+        Ok(dir)
+    }
+
+    #[test]
+    fn summarizes_the_entries_of_the_week() -> Result<()> {
+        let dir = week_of_entries()?;
+        let journal = Journal::new_at(dir.path());
+
+        let digest = Digest::collect(
+            &journal,
+            date!(2022 - 03 - 05),
+            date!(2022 - 03 - 11),
+            &Heading::todos(),
+        )?;
+
+        assert_eq!(
+            digest.entries,
+            vec![
+                EntrySummary {
+                    date: "2022-03-07".to_string(),
+                    title: "Planning".to_string()
+                },
+                EntrySummary {
+                    date: "2022-03-09".to_string(),
+                    title: "Retro".to_string()
+                },
+                EntrySummary {
+                    date: "2022-03-11".to_string(),
+                    title: "Reading group".to_string()
+                },
+            ]
+        );
+        assert_eq!(digest.completed, vec!["Send the marketing figures"]);
+        assert_eq!(digest.highlights, vec!["Shipped the importer"]);
+        assert_eq!(
+            digest.carried_over,
+            vec![CarriedOver {
+                todo: "Update the runbook".to_string(),
+                entries: 3
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn renders_the_digest_as_markdown() -> Result<()> {
+        let dir = week_of_entries()?;
+        let journal = Journal::new_at(dir.path());
+
+        let digest = Digest::collect(
+            &journal,
+            date!(2022 - 03 - 05),
+            date!(2022 - 03 - 11),
+            &Heading::todos(),
+        )?;
+        let markdown = digest.render(&crate::helpers::handlebars(
+            &Default::default(),
+            false,
+            date!(2022 - 03 - 11),
+        ))?;
+
+        assert_eq!(
+            markdown,
+            indoc! {r#"
+            # Digest from 2022-03-05 to 2022-03-11
+
+            ## Entries (3)
+
+            * 2022-03-07: Planning
+            * 2022-03-09: Retro
+            * 2022-03-11: Reading group
+
+            ## Completed TODOs (1)
+
+            * Send the marketing figures
+
+            ## Highlights
+
+            * Shipped the importer
+
+            ## Carried over the longest
+
+            * Update the runbook (in 3 entries)
+            "#}
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use clap::StructOpt;
+
+use crate::config::problems_at;
+use crate::{Config, Reminders};
+
+#[derive(Debug, StructOpt)]
+pub struct DoctorCmd {
+    /// Skip the checks that need the network, like logging into GitHub
+    #[clap(long)]
+    offline: bool,
+}
+
+/// The outcome of a single check, with what was found or what to do about it
+struct Check {
+    name: String,
+    result: Result<String>,
+}
+
+impl DoctorCmd {
+    pub(crate) async fn execute(&self, config: &Config) -> Result<()> {
+        let mut checks = vec![
+            Check {
+                name: "configuration".to_string(),
+                result: Config::config_path().and_then(|path| check_config(&path)),
+            },
+            Check {
+                name: "journal directory".to_string(),
+                result: check_dir(config),
+            },
+        ];
+
+        if config.reminders.is_enabled() {
+            let location = config.reminders_file();
+            checks.push(Check {
+                name: "reminders".to_string(),
+                result: Reminders::load(&location)
+                    .map(|_| format!("{} can be read", location.to_string_lossy()))
+                    .map_err(|e| anyhow!("{:#}, fix or remove the file", e)),
+            });
+        }
+
+        for (integration, token) in config.tokens() {
+            checks.push(Check {
+                name: format!("{} credentials", integration),
+                result: token
+                    .resolve()
+                    .map(|_| format!("found in {:?}", token))
+                    .map_err(|e| anyhow!("{:#}", e)),
+            });
+        }
+
+        if !self.offline {
+            for (integration, login) in config.check_logins().await {
+                checks.push(Check {
+                    name: format!("{} login", integration),
+                    result: login
+                        .map_err(|e| anyhow!("{:#}, check that the token is still valid", e)),
+                });
+            }
+        }
+
+        let mut failed = 0;
+        for check in &checks {
+            match check.result {
+                Ok(ref found) => println!("{} {}: {}", mark(true, config.ascii), check.name, found),
+                Err(ref e) => {
+                    failed += 1;
+                    println!("{} {}: {}", mark(false, config.ascii), check.name, e);
+                }
+            }
+        }
+
+        if failed > 0 {
+            bail!("{} of {} checks failed", failed, checks.len());
+        }
+        Ok(())
+    }
+}
+
+fn mark(ok: bool, ascii: bool) -> &'static str {
+    match (ok, ascii) {
+        (true, false) => "✔",
+        (false, false) => "✘",
+        (true, true) => "[ok]",
+        (false, true) => "[failed]",
+    }
+}
+
+/// The same problems `journal config check` finds, in the configuration and every profile
+fn check_config(path: &Path) -> Result<String> {
+    let problems = problems_at(path)?;
+    if !problems.is_empty() {
+        let problems: Vec<_> = problems
+            .iter()
+            .map(|problem| problem.to_string().trim().to_string())
+            .collect();
+        bail!(
+            "{} has problems, `journal config check` shows where: {}",
+            path.to_string_lossy(),
+            problems.join("; ")
+        );
+    }
+    Ok(format!("{} is valid", path.to_string_lossy()))
+}
+
+fn check_dir(config: &Config) -> Result<String> {
+    let dir = &config.dir;
+    if !dir.exists() {
+        bail!(
+            "{} does not exist, create it or change 'dir'",
+            dir.to_string_lossy()
+        );
+    }
+    if !dir.is_dir() {
+        bail!("{} is not a directory, change 'dir'", dir.to_string_lossy());
+    }
+    Ok(format!("{} exists", dir.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn reports_unknown_settings_but_not_empty_ones() -> Result<()> {
+        let dir = assert_fs::TempDir::new()?;
+        let path = dir.path().join("journal.yaml");
+        std::fs::write(
+            &path,
+            indoc! {r#"
+            dir: /tmp/journal
+            headings: {}
+            templates: {}
+            reminders:
+              enabled: true
+              shared: []
+            "#},
+        )?;
+        assert!(check_config(&path).is_ok());
+
+        std::fs::write(
+            &path,
+            "dir: /tmp/journal\ntodo:\n  enabled: true\nreminders:\n  enabled: true\n  fiel: shared.json\n",
+        )?;
+        let error = check_config(&path).unwrap_err().to_string();
+        assert!(error.contains("'todo' is not a setting"));
+        assert!(error.contains("'reminders.fiel' is not a setting"));
+        Ok(())
+    }
+}
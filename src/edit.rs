@@ -0,0 +1,289 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use clap::StructOpt;
+use similar::TextDiff;
+
+use crate::storage::write_atomically;
+
+/// Flags shared by all commands that modify existing entries in place
+#[derive(Debug, Default, StructOpt)]
+pub struct DryRun {
+    /// Print a unified diff of the proposed change instead of writing it
+    #[clap(long)]
+    diff: bool,
+
+    /// Don't write anything, but exit with an error if changes would be made
+    #[clap(long)]
+    check: bool,
+}
+
+impl DryRun {
+    pub fn is_dry_run(&self) -> bool {
+        self.diff || self.check
+    }
+
+    /// Writes `updated` to `path` unless this is a dry run.
+    /// Returns whether `updated` differs from `original`.
+    pub fn apply(&self, path: &Path, original: &str, updated: &str) -> Result<bool> {
+        if original == updated {
+            tracing::info!("No changes for {:?}", path);
+            return Ok(false);
+        }
+
+        if self.diff {
+            print!("{}", unified_diff(path, original, updated));
+        } else if self.check {
+            println!("{} would be changed", path.to_string_lossy());
+        }
+
+        if !self.is_dry_run() {
+            write_atomically(path, updated.as_bytes())?;
+        }
+
+        Ok(true)
+    }
+
+    /// Renames `from` to `to` unless this is a dry run. Never replaces a file that is already at `to`.
+    pub fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        if to.exists() {
+            bail!(
+                "Not renaming {} as {} already exists",
+                from.to_string_lossy(),
+                to.to_string_lossy()
+            );
+        }
+
+        if self.diff {
+            println!(
+                "rename from {}\nrename to {}",
+                from.to_string_lossy(),
+                to.to_string_lossy()
+            );
+        } else if self.check {
+            println!(
+                "{} would be renamed to {}",
+                from.to_string_lossy(),
+                to.to_string_lossy()
+            );
+        }
+
+        if !self.is_dry_run() {
+            std::fs::rename(from, to)?;
+        }
+        Ok(())
+    }
+
+    /// Fails when `--check` was passed and any of the files would have changed
+    pub fn finish(&self, changed: usize) -> Result<()> {
+        if self.check && changed > 0 {
+            bail!("{} file(s) would be changed", changed);
+        }
+        Ok(())
+    }
+}
+
+pub fn unified_diff(path: &Path, original: &str, updated: &str) -> String {
+    let name = path.to_string_lossy();
+
+    TextDiff::from_lines(original, updated)
+        .unified_diff()
+        .header(&name, &name)
+        .to_string()
+}
+
+/// Lets the user change `text` in their `$VISUAL` or `$EDITOR` and returns the result
+pub fn in_editor(name: &str, text: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    // Editors like `code --wait` come with their own arguments
+    let mut parts = editor.split_whitespace();
+    let program = match parts.next() {
+        Some(program) => program,
+        None => bail!("$EDITOR is empty"),
+    };
+
+    // Only we can open the file, and it is removed again however editing goes
+    let mut file = tempfile::Builder::new()
+        .prefix("journal-")
+        .suffix(&format!("-{}", name))
+        .tempfile()
+        .context("Could not create a file to edit")?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(file.path())
+        .status()
+        .with_context(|| format!("Could not start editor '{}'", editor))?;
+
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor, status);
+    }
+
+    // Some editors save by replacing the file, so read it again by its name
+    Ok(std::fs::read_to_string(file.path())?)
+}
+
+/// Asks a yes/no question on the terminal, anything but "y" or "yes" is a no
+pub fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Asks for a line of text on the terminal, empty if nothing was typed
+pub fn ask(question: &str) -> Result<String> {
+    print!("{} ", question);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+/// Asks for several lines of text on the terminal until an empty one
+pub fn ask_many(question: &str) -> Result<Vec<String>> {
+    println!("{} (an empty line to finish)", question);
+
+    let mut answers = Vec::new();
+    loop {
+        let answer = ask(">")?;
+        if answer.is_empty() {
+            return Ok(answers);
+        }
+        answers.push(answer);
+    }
+}
+
+/// Opens `path` in a terminal `editor` with the cursor on `line`, for the editors we know how to do that for
+pub fn open_at_line(editor: &str, path: &Path, line: Option<usize>) -> Result<()> {
+    let mut parts = editor.split_whitespace();
+    let program = match parts.next() {
+        Some(program) => program,
+        None => bail!("The configured editor is empty"),
+    };
+
+    let status = Command::new(program)
+        .args(parts)
+        .args(line_args(program, path, line))
+        .status()
+        .with_context(|| format!("Could not start editor '{}'", editor))?;
+
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+/// How to tell each editor which line to jump to
+fn line_args(program: &str, path: &Path, line: Option<usize>) -> Vec<String> {
+    let file = path.to_string_lossy().to_string();
+    let line = match line {
+        Some(line) => line,
+        None => return vec![file],
+    };
+
+    let name = Path::new(program)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match name.as_str() {
+        "vi" | "vim" | "nvim" | "nano" | "emacs" | "emacsclient" | "kak" | "micro" => {
+            vec![format!("+{}", line), file]
+        }
+        "code" | "codium" => vec!["--goto".to_string(), format!("{}:{}", file, line)],
+        "subl" | "hx" | "helix" | "zed" => vec![format!("{}:{}", file, line)],
+        _ => {
+            tracing::info!("Don't know how to open {} at a line", program);
+            vec![file]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+    use indoc::indoc;
+    use predicates::str::diff;
+
+    #[test]
+    fn tells_each_editor_about_the_line_in_its_own_way() {
+        let path = Path::new("entry.md");
+
+        assert_eq!(line_args("nvim", path, Some(3)), vec!["+3", "entry.md"]);
+        assert_eq!(
+            line_args("/usr/local/bin/code", path, Some(3)),
+            vec!["--goto", "entry.md:3"]
+        );
+        assert_eq!(line_args("hx", path, Some(3)), vec!["entry.md:3"]);
+        assert_eq!(line_args("ed", path, Some(3)), vec!["entry.md"]);
+        assert_eq!(line_args("nvim", path, None), vec!["entry.md"]);
+    }
+
+    #[test]
+    fn edits_text_in_the_editor() -> Result<()> {
+        // The only test that looks at $VISUAL
+        std::env::set_var("VISUAL", "sed -i s/before/after/");
+        assert_eq!(in_editor("reminder.txt", "before")?, "after");
+
+        std::env::set_var("VISUAL", "false");
+        assert!(in_editor("reminder.txt", "before").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn prints_changes_as_unified_diff() {
+        let original = indoc! {r#"
+        ## TODOs
+        * [ ] first
+        * [ ] second
+        "#};
+        let updated = original.replace("* [ ] first", "* [x] first");
+
+        let diff = unified_diff(Path::new("entry.md"), original, &updated);
+
+        assert_eq!(
+            diff,
+            indoc! {r#"
+            --- entry.md
+            +++ entry.md
+            @@ -1,3 +1,3 @@
+             ## TODOs
+            -* [ ] first
+            +* [x] first
+             * [ ] second
+            "#}
+        );
+    }
+
+    #[test]
+    fn dry_runs_leave_the_file_alone() -> Result<()> {
+        let dir = TempDir::new()?;
+        let entry = dir.child("entry.md");
+        entry.write_str("before")?;
+
+        let dry_run = DryRun {
+            diff: false,
+            check: true,
+        };
+        assert!(dry_run.apply(entry.path(), "before", "after")?);
+        entry.assert(diff("before"));
+        assert!(dry_run.finish(1).is_err());
+
+        DryRun::default().apply(entry.path(), "before", "after")?;
+        entry.assert(diff("after"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use clap::StructOpt;
+use pulldown_cmark::{escape, html, Options, Parser};
+use time::Date;
+
+use crate::storage::{parse_date, Entry, Journal};
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; line-height: 1.5; max-width: 46em; margin: 2em auto; padding: 0 1em; color: #24292f; }
+article + article { border-top: 1px solid #d0d7de; margin-top: 3em; padding-top: 1em; page-break-before: always; }
+h1, h2 { border-bottom: 1px solid #d0d7de; padding-bottom: 0.3em; }
+blockquote { color: #57606a; border-left: 0.25em solid #d0d7de; margin: 0; padding: 0 1em; }
+code, pre { background: #f6f8fa; border-radius: 6px; font-size: 85%; }
+pre { padding: 1em; overflow: auto; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #d0d7de; padding: 0.3em 0.8em; }
+li { list-style: none; }
+"#;
+
+#[derive(Debug, StructOpt)]
+pub struct ExportCmd {
+    /// Either `html` or `pdf`
+    #[clap(long, default_value = "html")]
+    format: Format,
+    /// Only export entries from this day on, e.g. 2022-03-01
+    #[clap(long, parse(try_from_str = parse_date))]
+    since: Option<Date>,
+    /// Only export entries up to and including this day, e.g. 2022-03-31
+    #[clap(long, parse(try_from_str = parse_date))]
+    until: Option<Date>,
+    /// Write one file per entry into the `--out` directory instead of a single document
+    #[clap(long)]
+    split: bool,
+    /// Where to write the export, `journal.html` or `journal.pdf` by default and `export` with `--split`
+    #[clap(long)]
+    out: Option<PathBuf>,
+    /// The program that turns HTML into a PDF, called with the HTML file and the PDF to write
+    #[clap(long, default_value = "wkhtmltopdf")]
+    pdf_with: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Html,
+    Pdf,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Pdf => "pdf",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "html" => Ok(Format::Html),
+            "pdf" => Ok(Format::Pdf),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+impl ExportCmd {
+    pub(crate) fn execute(&self, journal: &Journal) -> Result<()> {
+        let mut entries = Vec::new();
+        for path in journal.entries()? {
+            let entry = journal.read_entry(path)?;
+            if within(entry.date(), self.since, self.until) {
+                entries.push(entry);
+            }
+        }
+        if entries.is_empty() {
+            bail!("There are no entries to export");
+        }
+
+        if self.split {
+            let dir = self.out.clone().unwrap_or_else(|| PathBuf::from("export"));
+            std::fs::create_dir_all(&dir).with_context(|| format!("Could not create {:?}", dir))?;
+            for entry in &entries {
+                let name = entry.path.with_extension(self.format.extension());
+                let out = dir.join(name.file_name().unwrap_or_default());
+                self.write(&document(&entry.title(), std::slice::from_ref(entry)), &out)?;
+            }
+            println!(
+                "Exported {} entries into {}",
+                entries.len(),
+                dir.to_string_lossy()
+            );
+        } else {
+            let out = self.out.clone().unwrap_or_else(|| {
+                PathBuf::from("journal").with_extension(self.format.extension())
+            });
+            self.write(&document("Journal", &entries), &out)?;
+            println!(
+                "Exported {} entries to {}",
+                entries.len(),
+                out.to_string_lossy()
+            );
+        }
+        Ok(())
+    }
+
+    fn write(&self, html: &str, out: &Path) -> Result<()> {
+        match self.format {
+            Format::Html => {
+                std::fs::write(out, html).with_context(|| format!("Could not write {:?}", out))
+            }
+            Format::Pdf => {
+                // The PDF is rendered from an intermediate HTML file next to it
+                let intermediate = out.with_extension("export.html");
+                std::fs::write(&intermediate, html)
+                    .with_context(|| format!("Could not write {:?}", intermediate))?;
+
+                let mut parts = self.pdf_with.split_whitespace();
+                let program = match parts.next() {
+                    Some(program) => program,
+                    None => bail!("--pdf-with is empty"),
+                };
+                let status = Command::new(program)
+                    .args(parts)
+                    .arg(&intermediate)
+                    .arg(out)
+                    .status()
+                    .with_context(|| {
+                        format!(
+                            "Could not run '{}' to create the PDF, install it or pass --pdf-with",
+                            self.pdf_with
+                        )
+                    });
+                std::fs::remove_file(&intermediate)?;
+
+                if !status?.success() {
+                    bail!("Failed to create {:?} with '{}'", out, self.pdf_with);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn within(date: Option<Date>, since: Option<Date>, until: Option<Date>) -> bool {
+    match (date, since, until) {
+        (Some(date), Some(since), _) if date < since => false,
+        (Some(date), _, Some(until)) if date > until => false,
+        (Some(_), _, _) => true,
+        // Entries without a date can't be placed between the two
+        (None, since, until) => since.is_none() && until.is_none(),
+    }
+}
+
+/// A standalone HTML page with each of the `entries` in its own `<article>`
+fn document(title: &str, entries: &[Entry]) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    let mut body = String::new();
+    for entry in entries {
+        body.push_str(&format!("<article id=\"{}\">\n", entry.describe()));
+        html::push_html(&mut body, Parser::new_ext(entry.body(), options));
+        body.push_str("</article>\n");
+    }
+
+    let mut escaped = String::new();
+    escape::escape_html(&mut escaped, title).unwrap();
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escaped, STYLE, body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn renders_entries_into_one_document() {
+        let entries = vec![
+            Entry {
+                path: PathBuf::from("2022-03-10-planning.md"),
+                markdown: "# Planning on 2022-03-10\n\n* [x] Book the flights\n".to_string(),
+            },
+            Entry {
+                path: PathBuf::from("2022-03-11-retro.md"),
+                markdown: "# Retro on 2022-03-11\n\n| went well |\n|---|\n| shipping |\n"
+                    .to_string(),
+            },
+        ];
+
+        let html = document("Journal", &entries);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<article id=\"2022-03-10\">\n<h1>Planning on 2022-03-10</h1>"));
+        assert!(html.contains("<input disabled=\"\" type=\"checkbox\" checked=\"\"/>"));
+        assert!(html.contains("<td>shipping</td>"));
+    }
+
+    #[test]
+    fn only_exports_entries_within_the_dates() {
+        let since = Some(date!(2022 - 03 - 01));
+        let until = Some(date!(2022 - 03 - 31));
+
+        assert!(within(Some(date!(2022 - 03 - 01)), since, until));
+        assert!(within(Some(date!(2022 - 03 - 31)), since, until));
+        assert!(!within(Some(date!(2022 - 04 - 01)), since, until));
+        assert!(!within(None, since, None));
+        assert!(within(None, None, None));
+    }
+}
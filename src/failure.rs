@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Section;
+use crate::storage::Journal;
+use crate::Clock;
+
+/// What a section that talks to a remote service does when it is slow or down,
+/// so GitHub or Jira being unreachable doesn't hold up or break `journal new`
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct FailurePolicy {
+    /// Gives up on the section after this many seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+    /// What happens when the section fails or takes too long, `fail` unless configured otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<OnError>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnError {
+    /// Don't create the entry at all
+    #[default]
+    Fail,
+    /// Leave the section out of the entry
+    Skip,
+    /// Put a note that the section was skipped, and why, in its place
+    Placeholder,
+}
+
+impl FailurePolicy {
+    /// Wraps `section`, named after its config key and rendered with `template`, so that it follows this policy
+    pub fn guard<S: Section + Send + Sync + 'static>(
+        &self,
+        name: &'static str,
+        template: &str,
+        section: S,
+    ) -> Box<dyn Section> {
+        if *self == FailurePolicy::default() {
+            return Box::new(section);
+        }
+        // The placeholder starts like the section would have
+        let heading = template
+            .lines()
+            .find(|line| line.starts_with('#'))
+            .map_or_else(|| format!("## {}", name), str::to_string);
+        Box::new(Guarded {
+            name,
+            heading,
+            section: Box::new(section),
+            policy: self.clone(),
+        })
+    }
+}
+
+struct Guarded {
+    name: &'static str,
+    heading: String,
+    section: Box<dyn Section + Send + Sync>,
+    policy: FailurePolicy,
+}
+
+#[async_trait::async_trait]
+impl Section for Guarded {
+    async fn render(
+        &self,
+        journal: &Journal,
+        clock: &dyn Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String> {
+        let rendering = self.section.render(journal, clock, handlebars);
+        let rendered = match self.policy.timeout_seconds {
+            Some(seconds) => tokio::time::timeout(Duration::from_secs(seconds), rendering)
+                .await
+                .unwrap_or_else(|_| Err(anyhow!("it took longer than {} seconds", seconds))),
+            None => rendering.await,
+        };
+
+        let error = match rendered {
+            Ok(rendered) => return Ok(rendered),
+            Err(error) => error,
+        };
+        match self.policy.on_error.unwrap_or_default() {
+            OnError::Fail => {
+                Err(error.context(format!("Could not render the {} section", self.name)))
+            }
+            OnError::Skip => {
+                tracing::warn!("Skipped the {} section: {:#}", self.name, error);
+                eprintln!("Warning: skipped the {} section, {:#}", self.name, error);
+                Ok(String::new())
+            }
+            OnError::Placeholder => {
+                tracing::warn!("Skipped the {} section: {:#}", self.name, error);
+                Ok(format!(
+                    "{}\n\n> Skipped, {:#}\n",
+                    self.heading.trim_end(),
+                    error
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controlled_clock::ControlledClock;
+
+    /// Never finishes in time, or fails right away
+    struct Unreachable {
+        hangs: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl Section for Unreachable {
+        async fn render(
+            &self,
+            _: &Journal,
+            _: &dyn Clock,
+            _: &Handlebars<'static>,
+        ) -> Result<String> {
+            if self.hangs {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+            Err(anyhow!("GitHub is down"))
+        }
+    }
+
+    async fn render(policy: &str, hangs: bool) -> Result<String> {
+        let policy: FailurePolicy = serde_yaml::from_str(policy)?;
+        let section = policy.guard(
+            "pull_requests",
+            "\n## Pull Requests:\n\n{{#each prs}}{{/each}}",
+            Unreachable { hangs },
+        );
+        let clock = ControlledClock::new(2022, time::Month::March, 11)?;
+        let journal = Journal::new_at(std::env::temp_dir());
+        section.render(&journal, &clock, &Handlebars::new()).await
+    }
+
+    #[tokio::test]
+    async fn follows_the_policy_when_a_section_fails() -> Result<()> {
+        assert_eq!(render("on_error: skip", false).await?, "");
+        assert_eq!(
+            render("on_error: placeholder", false).await?,
+            "## Pull Requests:\n\n> Skipped, GitHub is down\n"
+        );
+        assert_eq!(
+            render("{timeout_seconds: 0, on_error: placeholder}", true).await?,
+            "## Pull Requests:\n\n> Skipped, it took longer than 0 seconds\n"
+        );
+
+        let error = render("timeout_seconds: 0", true).await.unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "Could not render the pull_requests section: it took longer than 0 seconds"
+        );
+        Ok(())
+    }
+}
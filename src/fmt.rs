@@ -0,0 +1,272 @@
+use std::ops::Range;
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::StructOpt;
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag};
+use time::Date;
+
+use crate::edit::DryRun;
+use crate::post_process;
+use crate::storage::{parse_date, Journal, YEAR_MONTH_DAY};
+use crate::template::Heading;
+
+#[derive(Debug, StructOpt)]
+pub struct FmtCmd {
+    /// The date of the entry to format, e.g. 2022-03-11. Defaults to the latest entry.
+    #[clap(parse(try_from_str = parse_date))]
+    date: Option<Date>,
+
+    /// Format all entries in the journal
+    #[clap(long, conflicts_with = "date")]
+    all: bool,
+
+    #[clap(flatten)]
+    dry_run: DryRun,
+}
+
+impl FmtCmd {
+    /// Formats the selected entries, spelling the `headings` other commands look for the way they are configured
+    pub(crate) fn execute(self, journal: &Journal, headings: &[Heading]) -> Result<()> {
+        let paths = self.selected_entries(journal)?;
+
+        let mut unformatted = 0;
+        for path in paths {
+            let original = std::fs::read_to_string(&path)?;
+            let formatted = post_process::update_toc(&normalize(&original, headings));
+
+            if self.dry_run.apply(&path, &original, &formatted)? {
+                unformatted += 1;
+                if !self.dry_run.is_dry_run() {
+                    println!("Formatted {}", path.to_string_lossy());
+                }
+            }
+        }
+
+        self.dry_run.finish(unformatted)
+    }
+
+    fn selected_entries(&self, journal: &Journal) -> Result<Vec<PathBuf>> {
+        if self.all {
+            return journal.entries();
+        }
+
+        match self.date {
+            Some(date) => {
+                let entries = journal.entries_on(date)?;
+                if entries.is_empty() {
+                    bail!("There is no entry for {}", date.format(YEAR_MONTH_DAY)?);
+                }
+                Ok(entries)
+            }
+            None => Ok(journal.entries()?.pop().into_iter().collect()),
+        }
+    }
+}
+
+/// Re-writes the parts of an entry that the carry-over parser is picky about:
+/// the section `headings` become ATX headings of their configured level and spelling,
+/// bullets use `*` and completed tasks use a lowercase `[x]`. Everything else is left untouched.
+pub fn normalize(markdown: &str, headings: &[Heading]) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TASKLISTS);
+    let parser = Parser::new_ext(markdown, options).into_offset_iter();
+
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    let mut heading: Option<(HeadingLevel, Range<usize>, Option<Range<usize>>)> = None;
+    let mut ordered_lists = Vec::new();
+
+    for (event, range) in parser {
+        match event {
+            Event::Start(Tag::Heading(level, _, _)) => heading = Some((level, range, None)),
+            Event::End(Tag::Heading(..)) => {
+                if let Some((level, range, Some(text))) = heading.take() {
+                    if let Some(edit) = normalize_heading(markdown, level, range, text, headings) {
+                        edits.push(edit);
+                    }
+                }
+            }
+            Event::Start(Tag::List(first)) => ordered_lists.push(first.is_some()),
+            Event::End(Tag::List(_)) => {
+                ordered_lists.pop();
+            }
+            Event::Start(Tag::Item) if ordered_lists.last() == Some(&false) => {
+                let indent =
+                    markdown[range.clone()].len() - markdown[range.clone()].trim_start().len();
+                let marker = range.start + indent..range.start + indent + 1;
+                if matches!(&markdown[marker.clone()], "-" | "+") {
+                    edits.push((marker, "*".to_string()));
+                }
+            }
+            Event::TaskListMarker(true) if &markdown[range.clone()] == "[X]" => {
+                edits.push((range, "[x]".to_string()));
+            }
+            _ => {
+                if let Some((_, _, ref mut text)) = heading {
+                    let start = text.as_ref().map_or(range.start, |t| t.start);
+                    *text = Some(start..range.end);
+                }
+            }
+        }
+    }
+
+    let mut normalized = markdown.to_string();
+    for (range, replacement) in edits.into_iter().rev() {
+        normalized.replace_range(range, &replacement);
+    }
+    normalized
+}
+
+fn normalize_heading(
+    markdown: &str,
+    level: HeadingLevel,
+    range: Range<usize>,
+    text: Range<usize>,
+    headings: &[Heading],
+) -> Option<(Range<usize>, String)> {
+    let title = markdown[text].trim();
+
+    let (level, title) = match headings.iter().find(|known| {
+        title
+            .trim_end_matches(':')
+            .eq_ignore_ascii_case(known.text())
+    }) {
+        Some(known) => (known.level(), known.text()),
+        None => (level as usize, title),
+    };
+
+    let original = &markdown[range.clone()];
+    let mut replacement = format!("{} {}", "#".repeat(level), title);
+    if original.ends_with('\n') {
+        replacement.push('\n');
+    }
+
+    if original == replacement {
+        None
+    } else {
+        Some((range, replacement))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::Template;
+    use crate::todo::FindTodos;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use time::macros::date;
+
+    fn defaults() -> Vec<Heading> {
+        vec![Heading::todos(), Heading::notes(), Heading::scratchpad()]
+    }
+
+    #[test]
+    fn rendered_entries_are_already_formatted() {
+        let template = Template {
+            title: "Round trip".to_string(),
+            today: date!(2022 - 03 - 11),
+            sections: maplit::hashmap! {
+                crate::config::SectionName::Notes => indoc! {r"
+                ## Notes
+
+                > This is where your notes will go!
+                "}.to_string(),
+                crate::config::SectionName::Todos => indoc! {r"
+                ## TODOs
+
+                * [ ] a todo
+                * [x] a done one
+                "}.to_string(),
+            },
+        };
+        let rendered = template.render(Vec::new()).unwrap();
+
+        assert_eq!(normalize(&rendered, &defaults()), rendered);
+    }
+
+    #[test]
+    fn fixes_hand_edited_todo_sections() {
+        let markdown = indoc! {r#"
+        # Some entry on 2022-03-11
+
+        ### todos
+
+        - [ ] first
+        + [X] second
+            - [ ] second.dot.one
+
+        1. numbered stays numbered
+        "#};
+
+        let expected = indoc! {r#"
+        # Some entry on 2022-03-11
+
+        ## TODOs
+
+        * [ ] first
+        * [x] second
+            * [ ] second.dot.one
+
+        1. numbered stays numbered
+        "#};
+
+        assert_eq!(normalize(markdown, &defaults()), expected);
+        assert_eq!(
+            FindTodos::new()
+                .process(&normalize(markdown, &defaults()))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn turns_setext_headings_into_atx_headings() {
+        let markdown = indoc! {r#"
+        Notes
+        -----
+
+        Some text
+        "#};
+
+        let expected = indoc! {r#"
+        ## Notes
+
+        Some text
+        "#};
+
+        assert_eq!(normalize(markdown, &defaults()), expected);
+    }
+
+    #[test]
+    fn keeps_configured_headings_at_their_level() {
+        let aufgaben: Vec<Heading> = vec!["### Aufgaben".parse().unwrap()];
+        let markdown = "# Planung\n\n## aufgaben:\n\n- [ ] first\n";
+
+        assert_eq!(
+            normalize(markdown, &aufgaben),
+            "# Planung\n\n### Aufgaben\n\n* [ ] first\n"
+        );
+        assert_eq!(
+            FindTodos::new()
+                .under(&aufgaben[0])
+                .process(&normalize(markdown, &aufgaben))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn is_idempotent() {
+        let markdown = indoc! {r#"
+        # Entry
+
+        ## TODOs:
+
+        - [ ] first
+        "#};
+
+        let once = normalize(markdown, &defaults());
+        assert_eq!(normalize(&once, &defaults()), once);
+    }
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use time::Date;
+
+use crate::helpers;
+use crate::storage::YEAR_MONTH_DAY;
+use crate::Config;
+
+/// Commits every change `journal` makes to the git repository in the journal directory,
+/// which gives a history of the entries and, with `push`, keeps other machines in sync.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitConfig {
+    /// The commit message, with the `{{action}}` that was taken and the `{{date}}` it was taken on
+    #[serde(default = "default_message")]
+    message: String,
+    /// Push to the configured upstream after each commit
+    #[serde(default)]
+    push: bool,
+}
+
+fn default_message() -> String {
+    "{{action}}".to_string()
+}
+
+impl GitConfig {
+    fn commit(&self, dir: &Path, action: &str, today: Date) -> Result<()> {
+        let handlebars = helpers::handlebars(&Default::default(), false, today);
+        let message = handlebars.render_template(
+            &self.message,
+            &json!({ "action": action, "date": today.format(YEAR_MONTH_DAY)? }),
+        )?;
+
+        git(dir, &["add", "--all"])?;
+        // Nothing to commit when e.g. listing reminders didn't change them
+        if git(dir, &["status", "--porcelain"])?.trim().is_empty() {
+            return Ok(());
+        }
+        git(dir, &["commit", "--quiet", "--message", &message])?;
+
+        if self.push {
+            git(dir, &["push", "--quiet"])?;
+        }
+        tracing::info!("Committed '{}'", message);
+        Ok(())
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Could not run git")?;
+
+    if !output.status.success() {
+        bail!(
+            "'git {}' failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Commits what changed in the journal if `git` is turned on. The change itself was already made,
+/// so failing to commit it only warns instead of failing the command.
+pub(crate) fn commit(config: &Config, action: &str, today: Date) {
+    if let Some(git) = config.git() {
+        if let Err(e) = git.commit(&config.dir, action, today) {
+            eprintln!("Warning: could not commit the change to git: {:#}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+    use time::macros::date;
+
+    #[test]
+    fn commits_changes_with_the_message() -> Result<()> {
+        let dir = TempDir::new()?;
+        git(dir.path(), &["init", "--quiet"])?;
+        git(dir.path(), &["config", "user.name", "Journal"])?;
+        git(dir.path(), &["config", "user.email", "journal@example.com"])?;
+        let config = GitConfig {
+            message: "{{action}} on {{date}}".to_string(),
+            push: false,
+        };
+
+        dir.child("2022-03-11-planning.md")
+            .write_str("# Planning")?;
+        config.commit(
+            dir.path(),
+            "Add 2022-03-11-planning.md",
+            date!(2022 - 03 - 11),
+        )?;
+        config.commit(dir.path(), "Nothing changed", date!(2022 - 03 - 11))?;
+
+        assert_eq!(
+            git(dir.path(), &["log", "--format=%s"])?,
+            "Add 2022-03-11-planning.md on 2022-03-11\n"
+        );
+        Ok(())
+    }
+}
@@ -5,19 +5,30 @@ use anyhow::Result;
 use futures::future::join_all;
 use handlebars::Handlebars;
 use octocrab::{models::pulls::PullRequest, Octocrab, OctocrabBuilder, Page};
-use secrecy::{ExposeSecret, Secret};
+use secrecy::ExposeSecret;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tokio::task::JoinHandle;
 use tracing::{instrument, Instrument};
 
+use crate::auth::Token;
+use crate::cache::{self, Cache};
 use crate::config::Section;
+use crate::failure::FailurePolicy;
+use crate::limits::Limits;
 
 /// Configuration for how journal should get outstanding Pull/Merge requests
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct PullRequestConfig {
     pub(crate) auth: Auth,
     select: Vec<PrSelector>,
     template: Option<String>,
+    #[serde(flatten)]
+    limits: Limits,
+    #[serde(flatten)]
+    pub(crate) failure: FailurePolicy,
+    #[serde(skip)]
+    pub(crate) cache: Option<Cache>,
 }
 
 const PRS: &str = r#"
@@ -26,30 +37,70 @@ const PRS: &str = r#"
 {{#each prs as | pr | }}
 * [ ] `{{pr.title}}` on [{{pr.repo}}]({{pr.url}}) by {{pr.author}}
 {{/each }}
+{{#if more }}
+…and {{more}} more
+{{/if }}
 "#;
 
 #[async_trait::async_trait]
 impl Section for PullRequestConfig {
-    async fn render(&self, _: &crate::storage::Journal, _: &dyn crate::Clock) -> Result<String> {
-        let prs = self.get_matching_prs().await?;
+    async fn render(
+        &self,
+        _: &crate::storage::Journal,
+        clock: &dyn crate::Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String> {
+        let prs = cache::fetch(
+            self.cache.as_ref(),
+            "pull_requests",
+            &(&self.auth, &self.select),
+            clock.today(),
+            self.get_matching_prs(),
+        )
+        .await?;
+        let (prs, more) = self.limits.truncate_items(prs);
 
         #[derive(Serialize)]
         struct C {
             prs: Vec<Pr>,
+            more: usize,
         }
 
-        let template = self.template.clone().unwrap_or_else(|| PRS.to_string());
+        let template = self.template();
 
-        let mut tt = Handlebars::new();
+        let mut tt = handlebars.clone();
         tt.register_template_string("prs", template)?;
-        tt.register_escape_fn(handlebars::no_escape);
-        tt.render("prs", &C { prs }).map_err(|e| anyhow::anyhow!(e))
+        let rendered = tt.render("prs", &C { prs, more })?;
+
+        Ok(self.limits.truncate_text(rendered))
     }
 }
 
 impl PullRequestConfig {
+    /// The template the section is rendered with
+    pub(crate) fn template(&self) -> &str {
+        self.template.as_deref().unwrap_or(PRS)
+    }
+
+    pub(crate) async fn check_login(&self) -> Result<String> {
+        let Auth::PersonalAccessToken(ref token) = self.auth;
+        let octocrab = OctocrabBuilder::new()
+            .personal_token(
+                token
+                    .resolve_in_background()
+                    .await?
+                    .expose_secret()
+                    .to_string(),
+            )
+            .build()?;
+        let user = octocrab.current().user().await?;
+
+        Ok(format!("logged in as {}", user.login))
+    }
+
     pub async fn get_matching_prs(&self) -> Result<Vec<Pr>> {
         let Auth::PersonalAccessToken(ref token) = self.auth;
+        let token = token.resolve_in_background().await?;
 
         let octocrab = OctocrabBuilder::new()
             .personal_token(token.expose_secret().to_string())
@@ -87,6 +138,7 @@ impl PullRequestConfig {
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 struct PrSelector {
     repo: Repo,
     #[serde(flatten)]
@@ -189,34 +241,31 @@ pub(crate) struct LocalFilter {
     pub(crate) labels: HashSet<String>,
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub(crate) enum Auth {
-    #[serde(rename = "personal_access_token", serialize_with = "only_asterisk")]
-    PersonalAccessToken(Secret<String>),
+    #[serde(rename = "personal_access_token")]
+    PersonalAccessToken(Token),
 }
 
-impl std::fmt::Debug for Auth {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            &Self::PersonalAccessToken(_) => f.write_str("***"),
-        }
+impl Auth {
+    pub(crate) fn token(&self) -> &Token {
+        let Auth::PersonalAccessToken(ref token) = self;
+        token
     }
 }
 
-fn only_asterisk<S>(_: &Secret<String>, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    serializer.serialize_str("***")
-}
-
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Pr {
     pub(crate) author: String,
     pub(crate) labels: HashSet<String>,
     pub(crate) repo: String,
     pub(crate) title: String,
     pub(crate) url: String,
+    /// When it was opened and last changed, like `2022-03-11T09:30:00+00:00`
+    #[serde(default)]
+    pub(crate) created: Option<String>,
+    #[serde(default)]
+    pub(crate) updated: Option<String>,
 }
 
 impl From<&PullRequest> for Pr {
@@ -241,6 +290,8 @@ impl From<&PullRequest> for Pr {
                 .to_string(),
             title: raw.title.clone().unwrap(),
             url: raw.html_url.as_ref().unwrap().to_string(),
+            created: raw.created_at.map(|at| at.to_rfc3339()),
+            updated: raw.updated_at.map(|at| at.to_rfc3339()),
         }
     }
 }
@@ -257,7 +308,6 @@ mod tests {
         #[test]
         fn parse_config() -> Result<()> {
             let input = indoc! { r#"
-            enabled: true
             auth:
               personal_access_token: abc
             select:
@@ -291,6 +341,8 @@ mod tests {
                 repo: "...".into(),
                 title: "...".into(),
                 url: "...".into(),
+                created: None,
+                updated: None,
             };
 
             assert!(filter.apply(&pr));
@@ -312,6 +364,8 @@ mod tests {
                 repo: "...".into(),
                 title: "...".into(),
                 url: "...".into(),
+                created: None,
+                updated: None,
             };
 
             assert!(filter.apply(&pr));
@@ -333,6 +387,8 @@ mod tests {
                 repo: "...".into(),
                 title: "...".into(),
                 url: "...".into(),
+                created: None,
+                updated: None,
             };
 
             assert!(filter.apply(&pr));
@@ -343,6 +399,8 @@ mod tests {
                 repo: "...".into(),
                 title: "...".into(),
                 url: "...".into(),
+                created: None,
+                updated: None,
             };
             assert!(!filter.apply(&pr));
 
@@ -352,6 +410,8 @@ mod tests {
                 repo: "...".into(),
                 title: "...".into(),
                 url: "...".into(),
+                created: None,
+                updated: None,
             };
             assert!(!filter.apply(&pr));
 
@@ -361,6 +421,8 @@ mod tests {
                 repo: "...".into(),
                 title: "...".into(),
                 url: "...".into(),
+                created: None,
+                updated: None,
             };
             assert!(!filter.apply(&pr));
         }
@@ -0,0 +1,253 @@
+use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderError};
+use std::collections::BTreeMap;
+
+use anyhow::{Context as _, Result};
+use time::format_description::well_known::Rfc3339;
+use time::{format_description, Date, OffsetDateTime};
+
+use crate::status::Statuses;
+use crate::storage::YEAR_MONTH_DAY;
+
+/// The registry every template is rendered with, so that the same helpers are available everywhere:
+///
+/// * `{{status "urgent"}}` for the marker of a status
+/// * `{{format_date due "[day].[month].[year]"}}` to write a date like 2022-03-11 (or a timestamp) differently
+/// * `{{relative_day due}}` for "today", "tomorrow", "in 3 days", or "2 days ago", seen from `today`
+/// * `{{truncate title 40}}` to cut text down to at most that many characters
+/// * `{{upper name}}` and `{{lower name}}`
+pub fn handlebars(statuses: &Statuses, ascii: bool, today: Date) -> Handlebars<'static> {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    statuses.register_helper(&mut handlebars, ascii);
+
+    handlebars.register_helper("format_date", Box::new(format_date));
+    handlebars.register_helper(
+        "relative_day",
+        Box::new(
+            move |h: &Helper,
+                  _: &Handlebars,
+                  _: &Context,
+                  _: &mut RenderContext,
+                  out: &mut dyn Output|
+                  -> HelperResult {
+                let day = date_param(h, "relative_day")?;
+                out.write(&relative_day(day, today))?;
+                Ok(())
+            },
+        ),
+    );
+    handlebars.register_helper(
+        "truncate",
+        Box::new(
+            move |h: &Helper,
+                  _: &Handlebars,
+                  _: &Context,
+                  _: &mut RenderContext,
+                  out: &mut dyn Output|
+                  -> HelperResult {
+                let text = text_param(h, "truncate")?;
+                let max = h
+                    .param(1)
+                    .and_then(|param| param.value().as_u64())
+                    .ok_or_else(|| RenderError::new("`truncate` needs the number of characters"))?;
+                out.write(&truncate(&text, max as usize, ascii))?;
+                Ok(())
+            },
+        ),
+    );
+    handlebars.register_helper("upper", Box::new(upper));
+    handlebars.register_helper("lower", Box::new(lower));
+    handlebars
+}
+
+/// Registers the configured `partials`, so any template can use them like `{{> checkbox text=title}}`
+pub fn register_partials(
+    handlebars: &mut Handlebars<'static>,
+    partials: &BTreeMap<String, String>,
+) -> Result<()> {
+    for (name, partial) in partials {
+        handlebars
+            .register_partial(name, partial)
+            .with_context(|| format!("The partial '{}' is not a valid template", name))?;
+    }
+    Ok(())
+}
+
+/// The first parameter as text, also accepting numbers and other plain values
+fn text_param(h: &Helper, helper: &str) -> Result<String, RenderError> {
+    match h.param(0).map(|param| param.value()) {
+        Some(serde_json::Value::String(text)) => Ok(text.clone()),
+        Some(serde_json::Value::Null) | None => Err(RenderError::new(format!(
+            "`{}` needs the text to change",
+            helper
+        ))),
+        Some(value) => Ok(value.to_string()),
+    }
+}
+
+/// The first parameter as a date written like 2022-03-11
+fn date_param(h: &Helper, helper: &str) -> Result<Date, RenderError> {
+    let raw = h
+        .param(0)
+        .and_then(|param| param.value().as_str())
+        .ok_or_else(|| RenderError::new(format!("`{}` needs a date like 2022-03-11", helper)))?;
+    // Timestamps like a pull request's `updated` are on the day they have in their own offset
+    Date::parse(raw, YEAR_MONTH_DAY)
+        .or_else(|_| OffsetDateTime::parse(raw, &Rfc3339).map(|at| at.date()))
+        .map_err(|_| {
+            RenderError::new(format!(
+                "'{}' is not a date like 2022-03-11 or 2022-03-11T09:30:00Z",
+                raw
+            ))
+        })
+}
+
+fn format_date(
+    h: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let day = date_param(h, "format_date")?;
+    let formatted = match h.param(1).and_then(|param| param.value().as_str()) {
+        Some(format) => {
+            let format = format_description::parse(format).map_err(|e| {
+                RenderError::new(format!("'{}' is not a date format: {}", format, e))
+            })?;
+            day.format(&format)
+        }
+        None => day.format(YEAR_MONTH_DAY),
+    };
+    out.write(&formatted.map_err(|e| RenderError::new(e.to_string()))?)?;
+    Ok(())
+}
+
+fn upper(
+    h: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    out.write(&text_param(h, "upper")?.to_uppercase())?;
+    Ok(())
+}
+
+fn lower(
+    h: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    out.write(&text_param(h, "lower")?.to_lowercase())?;
+    Ok(())
+}
+
+fn relative_day(day: Date, today: Date) -> String {
+    match (day - today).whole_days() {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        -1 => "yesterday".to_string(),
+        days if days > 1 => format!("in {} days", days),
+        days => format!("{} days ago", -days),
+    }
+}
+
+fn truncate(text: &str, max: usize, ascii: bool) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let ellipsis = if ascii { "..." } else { "…" };
+    let kept: String = text
+        .chars()
+        .take(max.saturating_sub(ellipsis.chars().count()))
+        .collect();
+    format!("{}{}", kept.trim_end(), ellipsis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use time::macros::date;
+
+    #[test]
+    fn formats_dates_and_text() -> anyhow::Result<()> {
+        let handlebars = handlebars(&Statuses::default(), false, date!(2022 - 03 - 11));
+        let render = |template: &str| {
+            handlebars.render_template(
+                template,
+                &json!({ "due": "2022-03-14", "title": "Renew the TLS certificate" }),
+            )
+        };
+
+        assert_eq!(
+            render(r#"{{format_date due "[day].[month].[year]"}}"#)?,
+            "14.03.2022"
+        );
+        assert_eq!(render("{{relative_day due}}")?, "in 3 days");
+        assert_eq!(render("{{truncate title 12}}")?, "Renew the T…");
+        assert_eq!(render("{{upper title}}")?, "RENEW THE TLS CERTIFICATE");
+        assert_eq!(render("{{lower title}}")?, "renew the tls certificate");
+        assert!(render("{{format_date title}}").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn takes_timestamps_as_dates() -> anyhow::Result<()> {
+        let handlebars = handlebars(&Statuses::default(), false, date!(2022 - 03 - 11));
+        let pr = json!({ "updated": "2022-03-09T23:30:00-02:00" });
+
+        assert_eq!(
+            handlebars.render_template("{{relative_day updated}}", &pr)?,
+            "2 days ago"
+        );
+        assert_eq!(
+            handlebars.render_template(r#"{{format_date updated "[day].[month]."}}"#, &pr)?,
+            "09.03."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn shares_partials_between_templates() -> anyhow::Result<()> {
+        let mut handlebars = handlebars(&Statuses::default(), false, date!(2022 - 03 - 11));
+        register_partials(
+            &mut handlebars,
+            &BTreeMap::from([(
+                "checkbox".to_string(),
+                "* [ ] {{text}} ({{relative_day due}})".to_string(),
+            )]),
+        )?;
+
+        assert_eq!(
+            handlebars.render_template(
+                "{{> checkbox text=(upper title) due=due}}",
+                &json!({ "due": "2022-03-12", "title": "Renew" }),
+            )?,
+            "* [ ] RENEW (tomorrow)"
+        );
+        assert!(register_partials(
+            &mut handlebars,
+            &BTreeMap::from([("broken".to_string(), "{{#each}}".to_string())]),
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn phrases_days_relative_to_today() {
+        let today = date!(2022 - 03 - 11);
+        assert_eq!(relative_day(date!(2022 - 03 - 11), today), "today");
+        assert_eq!(relative_day(date!(2022 - 03 - 12), today), "tomorrow");
+        assert_eq!(relative_day(date!(2022 - 03 - 10), today), "yesterday");
+        assert_eq!(relative_day(date!(2022 - 03 - 01), today), "10 days ago");
+        assert_eq!(truncate("Short", 12, true), "Short");
+        assert_eq!(
+            truncate("Renew the TLS certificate", 12, true),
+            "Renew the..."
+        );
+    }
+}
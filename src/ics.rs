@@ -0,0 +1,776 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use sha2::{Digest, Sha256};
+use time::{Date, Duration, Month, PrimitiveDateTime, Time, UtcOffset, Weekday};
+
+use crate::reminders::{ExportedReminder, Occurrence, Period, Priority, RepeatingDate, TimeOfDay};
+
+/// How far ahead we look for the last of `COUNT` occurrences
+const LOOKAHEAD_IN_DAYS: i64 = 5 * 366;
+
+/// The parts of a `VEVENT` that can be turned into a reminder
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct CalendarEvent {
+    pub(crate) summary: String,
+    pub(crate) start: Date,
+    /// The time it starts at in local time, `None` for events that last all day
+    pub(crate) at: Option<TimeOfDay>,
+    rrule: Option<String>,
+    /// Occurrences that were taken out of the recurrence with `EXDATE`
+    pub(crate) excluded: Vec<Date>,
+}
+
+/// When a calendar event should remind us
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Schedule {
+    Once(Date),
+    /// Repeats until the date it ends on, if there is one
+    Every(RepeatingDate, Option<Date>),
+}
+
+impl CalendarEvent {
+    /// Maps the (simple) recurrence rules we understand onto our own recurrences
+    pub(crate) fn schedule(&self) -> Result<Schedule, String> {
+        let rrule = match self.rrule {
+            Some(ref rrule) => rrule,
+            None => return Ok(Schedule::Once(self.start)),
+        };
+
+        let parts = rrule
+            .split(';')
+            .filter_map(|part| part.split_once('='))
+            .collect::<HashMap<_, _>>();
+
+        let interval = match parts.get("INTERVAL") {
+            Some(interval) => interval
+                .parse::<usize>()
+                .map_err(|e| format!("invalid INTERVAL: {}", e))?,
+            None => 1,
+        };
+
+        let by_day = match parts.get("BYDAY") {
+            Some(days) => days
+                .split(',')
+                .map(parse_ics_weekday)
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+
+        let repeating = match parts.get("FREQ") {
+            Some(&"DAILY") => RepeatingDate::Periodic {
+                amount: interval,
+                period: Period::Days,
+            },
+            Some(&"WEEKLY") => match (interval, &by_day[..]) {
+                (1, []) => RepeatingDate::Weekday(self.start.weekday()),
+                (1, [weekday]) => RepeatingDate::Weekday(*weekday),
+                (1, weekdays) => RepeatingDate::Weekdays(weekdays.to_vec()),
+                (n, []) => RepeatingDate::Periodic {
+                    amount: n,
+                    period: Period::Weeks,
+                },
+                (n, [weekday]) if *weekday == self.start.weekday() => RepeatingDate::Periodic {
+                    amount: n,
+                    period: Period::Weeks,
+                },
+                (n, [weekday]) => RepeatingDate::WeeklyOn {
+                    amount: n,
+                    weekday: *weekday,
+                },
+                _ => return Err(format!("unsupported weekly recurrence: {}", rrule)),
+            },
+            Some(&"YEARLY") => match interval {
+                1 => RepeatingDate::Yearly {
+                    day: self.start.day(),
+                    month: self.start.month(),
+                },
+                n => RepeatingDate::Periodic {
+                    amount: n,
+                    period: Period::Years,
+                },
+            },
+            _ => return Err(format!("unsupported recurrence: {}", rrule)),
+        };
+
+        let until = match (parts.get("UNTIL"), parts.get("COUNT")) {
+            (Some(until), _) => Some(parse_ics_date(until).map_err(|e| e.to_string())?),
+            (None, Some(count)) => {
+                let count = count
+                    .parse::<usize>()
+                    .map_err(|e| format!("invalid COUNT: {}", e))?;
+                self.last_of(&repeating, count)
+            }
+            (None, None) => None,
+        };
+
+        Ok(Schedule::Every(repeating, until))
+    }
+
+    /// The day of the `count`th occurrence, counting the ones that were excluded like calendars do
+    fn last_of(&self, repeating: &RepeatingDate, count: usize) -> Option<Date> {
+        (0..LOOKAHEAD_IN_DAYS)
+            .map(|days| self.start + Duration::days(days))
+            .filter(|date| repeating.matches(self.start, *date))
+            .nth(count.checked_sub(1)?)
+    }
+}
+
+/// Reads all `VEVENT`s from the content of an `.ics` file, with their times in the `local` offset.
+/// Events that can't be read are kept as errors so the others can still be used.
+pub(crate) fn parse_events(content: &str, local: UtcOffset) -> Vec<Result<CalendarEvent>> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<(String, String)>> = None;
+
+    for line in unfold(content) {
+        let (name, value) = match line.split_once(':') {
+            Some((name, value)) => (name, value),
+            None => continue,
+        };
+        // Parameters like `DTSTART;VALUE=DATE` are not needed to get the date itself
+        let name = name.split(';').next().unwrap_or(name).to_uppercase();
+
+        match (name.as_str(), value) {
+            ("BEGIN", "VEVENT") => current = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(properties) = current.take() {
+                    events.push(to_event(properties, local));
+                }
+            }
+            _ => {
+                if let Some(ref mut properties) = current {
+                    properties.push((name, value.to_string()));
+                }
+            }
+        }
+    }
+
+    events
+}
+
+fn to_event(properties: Vec<(String, String)>, local: UtcOffset) -> Result<CalendarEvent> {
+    let find = |wanted: &str| {
+        properties
+            .iter()
+            .find(|(name, _)| name == wanted)
+            .map(|(_, value)| value)
+    };
+
+    let summary = match find("SUMMARY") {
+        Some(summary) => unescape(summary),
+        None => bail!("Found an event without a SUMMARY"),
+    };
+    let (start, at) = match find("DTSTART") {
+        Some(start) => parse_ics_date_time(start, local)
+            .map_err(|e| anyhow!("Event '{}' has an invalid DTSTART: {}", summary, e))?,
+        None => bail!("Event '{}' has no DTSTART", summary),
+    };
+
+    // There can be several EXDATEs, each with one or more dates
+    let mut excluded = Vec::new();
+    for (_, exdates) in properties.iter().filter(|(name, _)| name == "EXDATE") {
+        for exdate in exdates.split(',') {
+            let (date, _) = parse_ics_date_time(exdate, local)
+                .map_err(|e| anyhow!("Event '{}' has an invalid EXDATE: {}", summary, e))?;
+            excluded.push(date);
+        }
+    }
+
+    Ok(CalendarEvent {
+        summary,
+        start,
+        at,
+        rrule: find("RRULE").cloned(),
+        excluded,
+    })
+}
+
+/// Long lines in `.ics` files are folded by starting the continuation with whitespace
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (
+            line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')),
+            lines.last_mut(),
+        ) {
+            (Some(continuation), Some(previous)) => previous.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+/// Both `20220311` and `20220311T090000Z` start with the date we care about
+fn parse_ics_date(raw: &str) -> Result<Date> {
+    let digits = match raw.get(0..8) {
+        Some(digits) if digits.chars().all(|c| c.is_ascii_digit()) => digits,
+        _ => bail!("Unrecognized date: {}", raw),
+    };
+
+    let year: i32 = digits[0..4].parse()?;
+    let month = Month::try_from(digits[4..6].parse::<u8>()?)?;
+    let day: u8 = digits[6..8].parse()?;
+
+    Ok(Date::from_calendar_date(year, month, day)?)
+}
+
+/// The day and time of a value like `20220311T090000Z`, in the `local` offset for times in UTC.
+/// Times in other time zones (`TZID=...`) and "floating" ones are taken as they are.
+fn parse_ics_date_time(raw: &str, local: UtcOffset) -> Result<(Date, Option<TimeOfDay>)> {
+    let date = parse_ics_date(raw)?;
+    let time = match raw.get(8..) {
+        Some(rest) if rest.starts_with('T') => &rest[1..],
+        _ => return Ok((date, None)),
+    };
+    let (time, utc) = match time.strip_suffix('Z') {
+        Some(time) => (time, true),
+        None => (time, false),
+    };
+    let digits = match time.get(0..4) {
+        Some(digits) if digits.chars().all(|c| c.is_ascii_digit()) => digits,
+        _ => bail!("Unrecognized time: {}", raw),
+    };
+    let time = Time::from_hms(digits[0..2].parse()?, digits[2..4].parse()?, 0)?;
+
+    let local_time = match utc {
+        true => PrimitiveDateTime::new(date, time)
+            .assume_utc()
+            .to_offset(local),
+        false => PrimitiveDateTime::new(date, time).assume_offset(local),
+    };
+
+    Ok((
+        local_time.date(),
+        Some(TimeOfDay {
+            hour: local_time.hour(),
+            minute: local_time.minute(),
+        }),
+    ))
+}
+
+fn parse_ics_weekday(raw: &str) -> Result<Weekday, String> {
+    match raw {
+        "MO" => Ok(Weekday::Monday),
+        "TU" => Ok(Weekday::Tuesday),
+        "WE" => Ok(Weekday::Wednesday),
+        "TH" => Ok(Weekday::Thursday),
+        "FR" => Ok(Weekday::Friday),
+        "SA" => Ok(Weekday::Saturday),
+        "SU" => Ok(Weekday::Sunday),
+        _ => Err(format!("unsupported BYDAY: {}", raw)),
+    }
+}
+
+/// Writes reminders as a calendar other tools can subscribe to.
+/// Recurring reminders become `RRULE`s that start on their first occurrence.
+pub(crate) fn to_calendar(reminders: &[ExportedReminder], today: Date) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//felipesere//journal//EN".to_string(),
+    ];
+
+    for reminder in reminders {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", uid(reminder)));
+        lines.push(format!("DTSTAMP:{}T000000Z", ics_date(today)));
+        // Times are "floating", they are due at the same time in whatever timezone you are in
+        match reminder.at {
+            Some(at) => lines.push(format!(
+                "DTSTART:{}T{:02}{:02}00",
+                ics_date(reminder.date),
+                at.hour,
+                at.minute
+            )),
+            None => lines.push(format!("DTSTART;VALUE=DATE:{}", ics_date(reminder.date))),
+        }
+        if let Some(ref interval) = reminder.interval {
+            let mut rule = rrule(interval);
+            // Calendars can't move occurrences to Monday, leaving out the weekend comes closest.
+            // BYDAY only limits daily rules, it would add occurrences to the others.
+            if reminder.weekdays_only && rule.starts_with("FREQ=DAILY") {
+                rule.push_str(";BYDAY=MO,TU,WE,TH,FR");
+            }
+            lines.push(format!("RRULE:{}", rule));
+        }
+        // 1 is the highest and 9 the lowest priority, normal ones are left undefined
+        match reminder.priority {
+            Priority::High => lines.push("PRIORITY:1".to_string()),
+            Priority::Normal => {}
+            Priority::Low => lines.push("PRIORITY:9".to_string()),
+        }
+        lines.push(format!("SUMMARY:{}", escape(&reminder.reminder)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .map(|line| line + "\r\n")
+        .collect()
+}
+
+/// The recurrence rule for one of our own recurrences
+pub(crate) fn rrule(interval: &RepeatingDate) -> String {
+    let every = |freq: &str, amount: usize| match amount {
+        1 => format!("FREQ={}", freq),
+        n => format!("FREQ={};INTERVAL={}", freq, n),
+    };
+
+    match interval {
+        RepeatingDate::Weekday(weekday) => format!("FREQ=WEEKLY;BYDAY={}", ics_weekday(*weekday)),
+        RepeatingDate::Weekdays(weekdays) => {
+            let days = weekdays
+                .iter()
+                .copied()
+                .map(ics_weekday)
+                .collect::<Vec<_>>();
+            format!("FREQ=WEEKLY;BYDAY={}", days.join(","))
+        }
+        RepeatingDate::Periodic { amount, period } => match period {
+            Period::Days => every("DAILY", *amount),
+            Period::Weeks => every("WEEKLY", *amount),
+            Period::Years => every("YEARLY", *amount),
+        },
+        RepeatingDate::WeeklyOn { amount, weekday } => format!(
+            "{};BYDAY={}",
+            every("WEEKLY", *amount),
+            ics_weekday(*weekday)
+        ),
+        RepeatingDate::Yearly { .. } => "FREQ=YEARLY".to_string(),
+        RepeatingDate::Monthly {
+            occurrence,
+            weekday,
+        } => {
+            let nth = match occurrence {
+                Occurrence::Nth(n) => n.to_string(),
+                Occurrence::Last => "-1".to_string(),
+            };
+            format!("FREQ=MONTHLY;BYDAY={}{}", nth, ics_weekday(*weekday))
+        }
+    }
+}
+
+/// Stays the same across exports, and versions of journal, so subscribed calendars update events instead of duplicating them
+fn uid(reminder: &ExportedReminder) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(reminder.reminder.as_bytes());
+    hasher.update(ics_date(reminder.date).as_bytes());
+    if let Some(ref interval) = reminder.interval {
+        hasher.update(rrule(interval).as_bytes());
+    }
+
+    let hash = hasher.finalize()[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    format!("{}@journal", hash)
+}
+
+fn ics_date(date: Date) -> String {
+    format!(
+        "{:04}{:02}{:02}",
+        date.year(),
+        date.month() as u8,
+        date.day()
+    )
+}
+
+fn ics_weekday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "MO",
+        Weekday::Tuesday => "TU",
+        Weekday::Wednesday => "WE",
+        Weekday::Thursday => "TH",
+        Weekday::Friday => "FR",
+        Weekday::Saturday => "SA",
+        Weekday::Sunday => "SU",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace('\n', "\\n")
+}
+
+/// Lines longer than 75 bytes are continued on the next line, starting with a space
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use time::macros::{date, offset};
+
+    const CALENDAR: &str = indoc! {r#"
+    BEGIN:VCALENDAR
+    VERSION:2.0
+    BEGIN:VEVENT
+    SUMMARY:Dentist
+    DTSTART;VALUE=DATE:20220314
+    END:VEVENT
+    BEGIN:VEVENT
+    SUMMARY:Team sync\, with a very long description that had to be
+      folded
+    DTSTART;TZID=Europe/London:20220307T093000
+    RRULE:FREQ=WEEKLY;BYDAY=MO
+    END:VEVENT
+    BEGIN:VEVENT
+    SUMMARY:Water the plants
+    DTSTART:20220301T080000Z
+    RRULE:FREQ=DAILY;INTERVAL=3
+    END:VEVENT
+    BEGIN:VEVENT
+    SUMMARY:Anna's birthday
+    DTSTART;VALUE=DATE:19900412
+    RRULE:FREQ=YEARLY
+    END:VEVENT
+    BEGIN:VEVENT
+    SUMMARY:Pay rent
+    DTSTART:20220301
+    RRULE:FREQ=MONTHLY;BYMONTHDAY=1
+    END:VEVENT
+    END:VCALENDAR
+    "#};
+
+    #[test]
+    fn reads_all_events() -> Result<()> {
+        let events = parse_events(CALENDAR, UtcOffset::UTC)
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        let summaries = events
+            .iter()
+            .map(|event| event.summary.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summaries,
+            vec![
+                "Dentist",
+                "Team sync, with a very long description that had to be folded",
+                "Water the plants",
+                "Anna's birthday",
+                "Pay rent",
+            ]
+        );
+        assert_eq!(events[1].start, date!(2022 - 03 - 07));
+        assert_eq!(
+            events[1].at,
+            Some(TimeOfDay {
+                hour: 9,
+                minute: 30
+            })
+        );
+        assert_eq!(events[3].at, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn maps_recurrence_rules_onto_reminders() -> Result<()> {
+        let schedules = parse_events(CALENDAR, UtcOffset::UTC)
+            .into_iter()
+            .map(|event| event.map(|event| event.schedule()))
+            .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(
+            schedules,
+            vec![
+                Ok(Schedule::Once(date!(2022 - 03 - 14))),
+                Ok(Schedule::Every(
+                    RepeatingDate::Weekday(Weekday::Monday),
+                    None
+                )),
+                Ok(Schedule::Every(
+                    RepeatingDate::Periodic {
+                        amount: 3,
+                        period: Period::Days
+                    },
+                    None
+                )),
+                Ok(Schedule::Every(
+                    RepeatingDate::Yearly {
+                        day: 12,
+                        month: Month::April
+                    },
+                    None
+                )),
+                Err("unsupported recurrence: FREQ=MONTHLY;BYMONTHDAY=1".to_string()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn skips_events_that_cant_be_read() {
+        let events = parse_events(
+            indoc! {r#"
+            BEGIN:VEVENT
+            DTSTART:20220314
+            END:VEVENT
+            BEGIN:VEVENT
+            SUMMARY:Dentist
+            DTSTART:20220314
+            END:VEVENT
+            "#},
+            UtcOffset::UTC,
+        );
+
+        assert_eq!(events.len(), 2);
+        assert!(events[0].is_err());
+        assert_eq!(events[1].as_ref().unwrap().summary, "Dentist");
+    }
+
+    #[test]
+    fn times_in_utc_are_moved_to_the_local_time() -> Result<()> {
+        let events = parse_events(
+            indoc! {r#"
+            BEGIN:VEVENT
+            SUMMARY:Late call
+            DTSTART:20220311T233000Z
+            END:VEVENT
+            "#},
+            offset!(+1),
+        );
+        let event = events.into_iter().next().unwrap()?;
+
+        assert_eq!(event.start, date!(2022 - 03 - 12));
+        assert_eq!(
+            event.at,
+            Some(TimeOfDay {
+                hour: 0,
+                minute: 30
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn recurrences_end_and_leave_out_occurrences() -> Result<()> {
+        let events = parse_events(
+            indoc! {r#"
+            BEGIN:VEVENT
+            SUMMARY:Onboarding
+            DTSTART;VALUE=DATE:20220307
+            RRULE:FREQ=WEEKLY;COUNT=3
+            EXDATE;VALUE=DATE:20220314,20220321
+            END:VEVENT
+            BEGIN:VEVENT
+            SUMMARY:Course
+            DTSTART:20220301T080000Z
+            RRULE:FREQ=DAILY;UNTIL=20220310T080000Z
+            END:VEVENT
+            "#},
+            UtcOffset::UTC,
+        )
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(
+            events[0].schedule(),
+            Ok(Schedule::Every(
+                RepeatingDate::Weekday(Weekday::Monday),
+                Some(date!(2022 - 03 - 21))
+            ))
+        );
+        assert_eq!(
+            events[0].excluded,
+            vec![date!(2022 - 03 - 14), date!(2022 - 03 - 21)]
+        );
+        assert_eq!(
+            events[1].schedule(),
+            Ok(Schedule::Every(
+                RepeatingDate::Periodic {
+                    amount: 1,
+                    period: Period::Days
+                },
+                Some(date!(2022 - 03 - 10))
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn biweekly_events_become_periodic_reminders() {
+        let event = CalendarEvent {
+            summary: "Retro".to_string(),
+            start: date!(2022 - 03 - 10),
+            at: None,
+            rrule: Some("FREQ=WEEKLY;INTERVAL=2;BYDAY=TH".to_string()),
+            excluded: Vec::new(),
+        };
+
+        assert_eq!(
+            event.schedule(),
+            Ok(Schedule::Every(
+                RepeatingDate::Periodic {
+                    amount: 2,
+                    period: Period::Weeks
+                },
+                None
+            ))
+        );
+    }
+
+    #[test]
+    fn several_weekdays_become_a_single_reminder() {
+        let event = CalendarEvent {
+            summary: "Gym".to_string(),
+            start: date!(2022 - 03 - 07),
+            at: None,
+            rrule: Some("FREQ=WEEKLY;BYDAY=MO,WE,FR".to_string()),
+            excluded: Vec::new(),
+        };
+
+        assert_eq!(
+            event.schedule(),
+            Ok(Schedule::Every(
+                RepeatingDate::Weekdays(vec![Weekday::Monday, Weekday::Wednesday, Weekday::Friday]),
+                None
+            ))
+        );
+    }
+
+    #[test]
+    fn exported_reminders_can_be_imported_again() -> Result<()> {
+        let reminders = vec![
+            ExportedReminder {
+                nr: 1,
+                date: date!(2022 - 03 - 14),
+                at: None,
+                repeats: None,
+                priority: Priority::High,
+                reminder: "Dentist, bring the forms; all of them".to_string(),
+                interval: None,
+                weekdays_only: false,
+            },
+            ExportedReminder {
+                nr: 2,
+                date: date!(2022 - 03 - 07),
+                at: None,
+                repeats: Some("Monday, Wednesday, Friday".to_string()),
+                priority: Priority::Normal,
+                reminder: "Go to the gym".to_string(),
+                interval: Some(RepeatingDate::Weekdays(vec![
+                    Weekday::Monday,
+                    Weekday::Wednesday,
+                    Weekday::Friday,
+                ])),
+                weekdays_only: false,
+            },
+            ExportedReminder {
+                nr: 3,
+                date: date!(2022 - 03 - 01),
+                at: None,
+                repeats: Some("every 3 Days".to_string()),
+                priority: Priority::Low,
+                reminder: "Water the plants in the office and the ones at home before they dry out"
+                    .to_string(),
+                interval: Some(RepeatingDate::Periodic {
+                    amount: 3,
+                    period: Period::Days,
+                }),
+                weekdays_only: true,
+            },
+        ];
+
+        let calendar = to_calendar(&reminders, date!(2022 - 03 - 01));
+        assert!(calendar.lines().all(|line| line.len() <= 75));
+        assert!(calendar.contains("PRIORITY:1\r\n"));
+        assert!(calendar.contains("RRULE:FREQ=DAILY;INTERVAL=3;BYDAY=MO,TU,WE,TH,FR\r\n"));
+        assert_eq!(
+            to_calendar(&reminders, date!(2022 - 03 - 02))
+                .lines()
+                .find(|line| line.starts_with("UID:")),
+            Some("UID:a8cddbb7ea80643b@journal")
+        );
+
+        let schedules = parse_events(&calendar, UtcOffset::UTC)
+            .into_iter()
+            .map(|event| event.map(|event| (event.summary.clone(), event.schedule())))
+            .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(
+            schedules,
+            vec![
+                (
+                    "Dentist, bring the forms; all of them".to_string(),
+                    Ok(Schedule::Once(date!(2022 - 03 - 14)))
+                ),
+                (
+                    "Go to the gym".to_string(),
+                    Ok(Schedule::Every(
+                        RepeatingDate::Weekdays(vec![
+                            Weekday::Monday,
+                            Weekday::Wednesday,
+                            Weekday::Friday
+                        ]),
+                        None
+                    ))
+                ),
+                (
+                    "Water the plants in the office and the ones at home before they dry out"
+                        .to_string(),
+                    Ok(Schedule::Every(
+                        RepeatingDate::Periodic {
+                            amount: 3,
+                            period: Period::Days
+                        },
+                        None
+                    ))
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn monthly_reminders_count_from_the_end_for_the_last_weekday() {
+        assert_eq!(
+            rrule(&RepeatingDate::Monthly {
+                occurrence: Occurrence::Last,
+                weekday: Weekday::Friday
+            }),
+            "FREQ=MONTHLY;BYDAY=-1FR"
+        );
+        assert_eq!(
+            rrule(&RepeatingDate::Periodic {
+                amount: 2,
+                period: Period::Weeks
+            }),
+            "FREQ=WEEKLY;INTERVAL=2"
+        );
+        assert_eq!(
+            rrule(&RepeatingDate::WeeklyOn {
+                amount: 2,
+                weekday: Weekday::Tuesday
+            }),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU"
+        );
+    }
+}
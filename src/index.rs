@@ -0,0 +1,402 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use time::Date;
+
+use crate::post_process::outside_of_code;
+use crate::storage::{Entry, YEAR_MONTH_DAY};
+
+/// Bumped whenever the tables change, which rebuilds an existing index from the entries
+const VERSION: i64 = 1;
+
+const SCHEMA: &str = "
+    CREATE TABLE entries (path TEXT PRIMARY KEY, modified INTEGER NOT NULL, date TEXT, title TEXT NOT NULL, markdown TEXT NOT NULL);
+    CREATE TABLE headings (path TEXT NOT NULL, level INTEGER NOT NULL, heading TEXT NOT NULL);
+    CREATE TABLE todos (path TEXT NOT NULL, text TEXT NOT NULL, done INTEGER NOT NULL);
+    CREATE TABLE reminders (path TEXT NOT NULL, text TEXT NOT NULL);
+    CREATE INDEX headings_by_path ON headings (path);
+    CREATE INDEX todos_by_path ON todos (path);
+    CREATE INDEX reminders_by_path ON reminders (path);
+";
+
+/// Keeps what the entries contain in a SQLite database, so years of entries don't have to be
+/// read again for every search or `journal stats`. Entries are only read again once they changed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexConfig {
+    /// Where the index is kept, the user's cache directory if left out.
+    /// Deleting it is fine, it is built again from the entries.
+    file: Option<PathBuf>,
+}
+
+impl IndexConfig {
+    pub(crate) fn file(&self, dir: &Path) -> PathBuf {
+        match self.file {
+            Some(ref file) => dir.join(file),
+            None => dirs::cache_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("journal")
+                .join("index.sqlite"),
+        }
+    }
+}
+
+/// Which part of the entries to search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Text,
+    Headings,
+    Todos,
+    Reminders,
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Scope::Text),
+            "headings" => Ok(Scope::Headings),
+            "todos" => Ok(Scope::Todos),
+            "reminders" => Ok(Scope::Reminders),
+            _ => Err(format!("unknown part of the entries: {}", s)),
+        }
+    }
+}
+
+/// A line of an entry that matched a search
+#[derive(Debug, PartialEq, Eq)]
+pub struct Hit {
+    pub path: PathBuf,
+    pub date: Option<Date>,
+    pub line: String,
+}
+
+/// The parts of an entry that are kept in their own tables
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Outline {
+    headings: Vec<(usize, String)>,
+    todos: Vec<(String, bool)>,
+    reminders: Vec<String>,
+}
+
+fn outline(markdown: &str) -> Outline {
+    let todo = Regex::new(r"^\s*[-*] \[([ xX])\] (.*)$").unwrap();
+    let item = Regex::new(r"^\s*[-*] (.*)$").unwrap();
+
+    let mut outline = Outline::default();
+    let mut in_reminders = false;
+    outside_of_code(markdown, |line| {
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if level > 0 && line[level..].starts_with(' ') {
+            let heading = line[level..].trim().to_string();
+            in_reminders = heading.to_lowercase().contains("reminders");
+            outline.headings.push((level, heading));
+        } else if let Some(captures) = todo.captures(line) {
+            outline
+                .todos
+                .push((captures[2].trim().to_string(), &captures[1] != " "));
+        } else if let Some(captures) = item.captures(line).filter(|_| in_reminders) {
+            outline.reminders.push(captures[1].trim().to_string());
+        }
+        line.to_string()
+    });
+    outline
+}
+
+pub struct Index {
+    // Sections are rendered on other threads, which need to share the journal and its index
+    connection: Mutex<Connection>,
+}
+
+impl Index {
+    /// Opens the index kept in `file`, creating it if there is none yet
+    pub(crate) fn open(file: &Path) -> Result<Index> {
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Could not create {:?}", dir))?;
+        }
+        let connection = Connection::open(file)
+            .with_context(|| format!("Could not open the index at {:?}", file))?;
+        Index::prepare(connection)
+    }
+
+    /// An index that only lives as long as the command, for journals without a configured one
+    pub(crate) fn in_memory() -> Result<Index> {
+        Index::prepare(Connection::open_in_memory()?)
+    }
+
+    fn prepare(connection: Connection) -> Result<Index> {
+        let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version != VERSION {
+            tracing::info!("Building the index from scratch");
+            connection.execute_batch(&format!(
+                "DROP TABLE IF EXISTS entries; DROP TABLE IF EXISTS headings; DROP TABLE IF EXISTS todos; DROP TABLE IF EXISTS reminders; {} PRAGMA user_version = {};",
+                SCHEMA, VERSION
+            ))?;
+        }
+        Ok(Index {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        // Nothing is left half done when panicking, as every change is made atomically
+        self.connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The entry at `path`, read from the index unless the file changed since it was indexed
+    pub(crate) fn read(&self, path: PathBuf) -> Result<Entry> {
+        read(&self.connection(), path)
+    }
+
+    /// Drops the entries within `location` that are gone, e.g. because they were archived or deleted
+    pub(crate) fn forget_all_but(&self, location: &Path, entries: &[PathBuf]) -> Result<()> {
+        forget_all_but(&self.connection(), location, entries)
+    }
+
+    /// Brings the index up to date with the `entries` within `location`
+    pub(crate) fn refresh(&self, location: &Path, entries: &[PathBuf]) -> Result<()> {
+        let connection = self.connection();
+        // One change for all of them, as committing each entry on its own is slow
+        atomically(&connection, || {
+            forget_all_but(&connection, location, entries)?;
+            for path in entries {
+                read(&connection, path.clone())?;
+            }
+            Ok(())
+        })
+    }
+
+    /// The lines in the `scope` of every indexed entry that contain `text`, ignoring case, oldest first
+    pub(crate) fn search(&self, text: &str, scope: Scope) -> Result<Vec<Hit>> {
+        let query = match scope {
+            Scope::Text => "SELECT path, date, markdown FROM entries WHERE instr(lower(markdown), lower(?1)) > 0 ORDER BY date, path",
+            Scope::Headings => "SELECT path, date, heading FROM headings JOIN entries USING (path) WHERE instr(lower(heading), lower(?1)) > 0 ORDER BY date, path, headings.rowid",
+            Scope::Todos => "SELECT path, date, text FROM todos JOIN entries USING (path) WHERE NOT done AND instr(lower(text), lower(?1)) > 0 ORDER BY date, path, todos.rowid",
+            Scope::Reminders => "SELECT path, date, text FROM reminders JOIN entries USING (path) WHERE instr(lower(text), lower(?1)) > 0 ORDER BY date, path, reminders.rowid",
+        };
+        let rows = self
+            .connection()
+            .prepare(query)?
+            .query_map([text], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let needle = text.to_lowercase();
+        let mut hits = Vec::new();
+        for (path, date, found) in rows {
+            let date = date.and_then(|date| Date::parse(&date, YEAR_MONTH_DAY).ok());
+            // Whole entries only narrow it down to the lines that matched
+            let lines: Vec<&str> = match scope {
+                Scope::Text => found
+                    .lines()
+                    .filter(|line| line.to_lowercase().contains(&needle))
+                    .collect(),
+                _ => vec![&found],
+            };
+            for line in lines {
+                hits.push(Hit {
+                    path: PathBuf::from(&path),
+                    date,
+                    line: line.trim().to_string(),
+                });
+            }
+        }
+        Ok(hits)
+    }
+}
+
+fn read(connection: &Connection, path: PathBuf) -> Result<Entry> {
+    let modified = modified(&path)?;
+    let key = path.to_string_lossy().to_string();
+    let indexed: Option<String> = connection
+        .prepare_cached("SELECT markdown FROM entries WHERE path = ?1 AND modified = ?2")?
+        .query_row(params![key, modified], |row| row.get(0))
+        .optional()?;
+
+    if let Some(markdown) = indexed {
+        return Ok(Entry { path, markdown });
+    }
+
+    tracing::info!("Indexing {:?}", path);
+    let markdown = std::fs::read_to_string(&path)?;
+    let entry = Entry { path, markdown };
+    store(connection, &entry, modified)?;
+    Ok(entry)
+}
+
+fn store(connection: &Connection, entry: &Entry, modified: i64) -> Result<()> {
+    let key = entry.path.to_string_lossy().to_string();
+    let date = entry
+        .date()
+        .map(|date| date.format(YEAR_MONTH_DAY))
+        .transpose()?;
+    let outline = outline(entry.body());
+
+    atomically(connection, || {
+        forget(connection, &key)?;
+        connection.execute(
+            "INSERT INTO entries (path, modified, date, title, markdown) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![key, modified, date, entry.title(), entry.markdown],
+        )?;
+        for (level, heading) in outline.headings {
+            connection.execute(
+                "INSERT INTO headings (path, level, heading) VALUES (?1, ?2, ?3)",
+                params![key, level, heading],
+            )?;
+        }
+        for (text, done) in outline.todos {
+            connection.execute(
+                "INSERT INTO todos (path, text, done) VALUES (?1, ?2, ?3)",
+                params![key, text, done],
+            )?;
+        }
+        for text in outline.reminders {
+            connection.execute(
+                "INSERT INTO reminders (path, text) VALUES (?1, ?2)",
+                params![key, text],
+            )?;
+        }
+        Ok(())
+    })
+}
+
+/// Runs `change` as a whole or not at all, also as part of a bigger change
+fn atomically(connection: &Connection, change: impl FnOnce() -> Result<()>) -> Result<()> {
+    connection.execute_batch("SAVEPOINT change")?;
+    match change() {
+        Ok(()) => {
+            connection.execute_batch("RELEASE change")?;
+            Ok(())
+        }
+        Err(e) => {
+            connection.execute_batch("ROLLBACK TO change; RELEASE change")?;
+            Err(e)
+        }
+    }
+}
+
+fn forget(connection: &Connection, key: &str) -> Result<()> {
+    for table in ["entries", "headings", "todos", "reminders"] {
+        connection.execute(&format!("DELETE FROM {} WHERE path = ?1", table), [key])?;
+    }
+    Ok(())
+}
+
+fn forget_all_but(connection: &Connection, location: &Path, entries: &[PathBuf]) -> Result<()> {
+    let entries: HashSet<_> = entries.iter().collect();
+    let indexed = connection
+        .prepare("SELECT path FROM entries")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    atomically(connection, || {
+        for key in indexed {
+            let path = PathBuf::from(&key);
+            if path.starts_with(location) && !entries.contains(&path) {
+                forget(connection, &key)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// When the file at `path` was last changed, to tell whether the index is still up to date
+fn modified(path: &Path) -> Result<i64> {
+    let modified = std::fs::metadata(path)
+        .with_context(|| format!("Could not read {:?}", path))?
+        .modified()?;
+    Ok(modified.duration_since(UNIX_EPOCH)?.as_nanos() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+    use indoc::indoc;
+    use time::macros::date;
+
+    #[test]
+    fn outlines_headings_todos_and_reminders() {
+        let markdown = indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## TODOs
+            * [ ] Book the flights
+            * [x] Pack
+
+            ## Your reminders for today:
+            * Call the dentist
+
+            ```
+            ## not a heading
+            ```
+        "#};
+
+        assert_eq!(
+            outline(markdown),
+            Outline {
+                headings: vec![
+                    (1, "Planning on 2022-03-11".to_string()),
+                    (2, "TODOs".to_string()),
+                    (2, "Your reminders for today:".to_string()),
+                ],
+                todos: vec![
+                    ("Book the flights".to_string(), false),
+                    ("Pack".to_string(), true),
+                ],
+                reminders: vec!["Call the dentist".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_up_with_changed_and_removed_entries() -> Result<()> {
+        let dir = TempDir::new()?;
+        let planning = dir.child("2022-03-10-planning.md");
+        planning.write_str("# Planning\n\n* [ ] Book the flights\n")?;
+        let retro = dir.child("2022-03-11-retro.md");
+        retro.write_str("# Retro\n\nThe flights were booked\n")?;
+        let index = Index::open(&dir.path().join("index.sqlite"))?;
+        let entries = vec![planning.to_path_buf(), retro.to_path_buf()];
+
+        index.refresh(dir.path(), &entries)?;
+        assert_eq!(
+            index.search("FLIGHTS", Scope::Text)?,
+            vec![
+                Hit {
+                    path: planning.to_path_buf(),
+                    date: Some(date!(2022 - 03 - 10)),
+                    line: "* [ ] Book the flights".to_string(),
+                },
+                Hit {
+                    path: retro.to_path_buf(),
+                    date: Some(date!(2022 - 03 - 11)),
+                    line: "The flights were booked".to_string(),
+                },
+            ]
+        );
+        assert_eq!(index.search("flights", Scope::Todos)?.len(), 1);
+
+        planning.write_str("# Planning\n\n* [x] Book the flights\n")?;
+        std::fs::remove_file(&retro)?;
+        index.refresh(dir.path(), &entries[..1])?;
+        assert!(index.search("flights", Scope::Todos)?.is_empty());
+        assert_eq!(index.search("booked", Scope::Text)?, vec![]);
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::StructOpt;
+
+use crate::edit;
+use crate::Reminders;
+
+#[derive(Debug, StructOpt)]
+pub struct InitCmd {
+    /// Where to keep the journal, asked for if left out
+    #[clap(long)]
+    dir: Option<PathBuf>,
+    /// Replace a configuration that already exists
+    #[clap(long)]
+    force: bool,
+}
+
+impl InitCmd {
+    /// Sets up a new journal with its configuration at `config_path`
+    pub(crate) fn execute(&self, config_path: &Path) -> Result<()> {
+        if config_path.exists() && !self.force {
+            bail!(
+                "{} already exists, pass --force to replace it",
+                config_path.to_string_lossy()
+            );
+        }
+
+        let default_dir = dirs::home_dir().unwrap_or_default().join("journal");
+        let dir = match self.dir {
+            Some(ref dir) => dir.clone(),
+            None => {
+                let answer = edit::ask(&format!(
+                    "Where should the journal be kept? [{}]",
+                    default_dir.to_string_lossy()
+                ))?;
+                match answer.as_str() {
+                    "" => default_dir,
+                    answer => expand_home(answer),
+                }
+            }
+        };
+
+        std::fs::create_dir_all(&dir).with_context(|| format!("Could not create {:?}", dir))?;
+        std::fs::write(config_path, starter_config(&dir))
+            .with_context(|| format!("Could not write {:?}", config_path))?;
+
+        let reminders = dir.join("reminders.json");
+        if !reminders.exists() {
+            let file = std::fs::File::create(&reminders)
+                .with_context(|| format!("Could not create {:?}", reminders))?;
+            Reminders::default().to_writer(file)?;
+        }
+
+        println!(
+            "Wrote the configuration to {}",
+            config_path.to_string_lossy()
+        );
+        println!("Create your first entry with: journal new \"First day\"");
+        Ok(())
+    }
+}
+
+/// `~/journal` as typed on a shell
+pub(crate) fn expand_home<P: AsRef<Path>>(dir: P) -> PathBuf {
+    let dir = dir.as_ref();
+    match (dir.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => dir.to_path_buf(),
+    }
+}
+
+fn starter_config(dir: &Path) -> String {
+    format!(
+        r#"# Where the entries are kept
+dir: '{}'
+
+# Open TODOs from the previous entry are carried over into the new one
+todos:
+  enabled: true
+
+# Remind yourself of things with `journal reminders new`
+reminders:
+  enabled: true
+
+# A terminal editor to open new entries in, instead of the system default
+# editor: nvim
+
+# Pull requests you're involved in, from GitHub
+# pull_requests:
+#   enabled: true
+#   auth:
+#     personal_access_token: "your-access-token"
+#   select:
+#     - repo: felipesere/journal
+"#,
+        // Single quotes keep the backslashes of Windows paths as they are
+        dir.to_string_lossy().replace('\'', "''")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use assert_fs::TempDir;
+
+    #[test]
+    fn sets_up_a_journal_that_can_be_loaded() -> Result<()> {
+        let dir = TempDir::new()?;
+        let config_path = dir.path().join(".journal.yaml");
+        let init = InitCmd {
+            dir: Some(dir.path().join("journal")),
+            force: false,
+        };
+
+        init.execute(&config_path)?;
+
+        let config = Config::from_reader(std::fs::File::open(&config_path)?)?;
+        assert_eq!(config.dir, dir.path().join("journal"));
+        Reminders::load(&config.reminders_file())?;
+
+        assert!(init.execute(&config_path).is_err());
+        Ok(())
+    }
+}
@@ -3,23 +3,46 @@ use std::collections::HashMap;
 
 use handlebars::Handlebars;
 use jsonpath::Selector;
-use secrecy::{ExposeSecret, Secret};
-use serde::{Deserialize, Serialize, Serializer};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use time::{Date, Duration};
 
+use crate::auth::Token;
+use crate::cache::{self, Cache};
 use crate::config::Section;
+use crate::failure::FailurePolicy;
+use crate::limits::Limits;
+use crate::storage::YEAR_MONTH_DAY;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
 struct JiraAuth {
     user: String,
-    #[serde(serialize_with = "only_asterisk")]
-    personal_access_token: Secret<String>,
+    personal_access_token: Token,
 }
-fn only_asterisk<S>(_: &Secret<String>, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    serializer.serialize_str("***")
+
+impl JiraAuth {
+    /// Asks Jira for nothing at all, which only works with valid credentials
+    async fn check_login(&self, base_url: &str) -> Result<String> {
+        reqwest::Client::new()
+            .get(base_url)
+            .basic_auth(
+                self.user.to_string(),
+                Some(
+                    self.personal_access_token
+                        .resolve_in_background()
+                        .await?
+                        .expose_secret(),
+                ),
+            )
+            .query(&[("maxResults", "0")])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(format!("logged in as {}", self.user))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -38,49 +61,81 @@ impl Jql {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct JiraConfig {
     base_url: String,
     auth: JiraAuth,
     query: Jql,
     template: Option<String>,
+    #[serde(flatten)]
+    limits: Limits,
+    #[serde(flatten)]
+    pub(crate) failure: FailurePolicy,
+    #[serde(skip)]
+    pub(crate) cache: Option<Cache>,
 }
 
 #[async_trait::async_trait]
 impl Section for JiraConfig {
-    async fn render(&self, _: &crate::storage::Journal, _: &dyn crate::Clock) -> Result<String> {
-        let tasks = self.get_matching_tasks().await?;
+    async fn render(
+        &self,
+        _: &crate::storage::Journal,
+        clock: &dyn crate::Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String> {
+        let tasks = cache::fetch(
+            self.cache.as_ref(),
+            "jira",
+            &(&self.base_url, &self.auth, &self.query),
+            clock.today(),
+            self.get_matching_tasks(),
+        )
+        .await?;
+        let (tasks, more) = self.limits.truncate_items(tasks);
 
         #[derive(Serialize)]
         struct C {
             tasks: Vec<Task>,
+            more: usize,
         }
 
-        let template = self.template.clone().unwrap_or_else(|| TASKS.to_string());
+        let template = self.template();
 
-        let mut tt = Handlebars::new();
+        let mut tt = handlebars.clone();
         tt.register_template_string("tasks", template)?;
-        tt.register_escape_fn(handlebars::no_escape);
-        tt.render("tasks", &C { tasks }).map_err(|e| e.into())
+        let rendered = tt.render("tasks", &C { tasks, more })?;
+
+        Ok(self.limits.truncate_text(rendered))
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Task {
     summary: String,
     href: String,
+    /// The day it is due, like `2022-03-14`, if it has one
+    #[serde(default)]
+    due: Option<String>,
 }
 
 struct Selection {
     summary: Selector,
     href: Selector,
+    due: Selector,
 }
 
 impl Selection {
     fn extract_from(&self, issue: &Value) -> Option<Task> {
         let summary: String = self.summary.find(issue).next()?.as_str()?.to_string();
         let href: String = self.href.find(issue).next()?.as_str()?.to_string();
+        let due = self
+            .due
+            .find(issue)
+            .next()
+            .and_then(|due| due.as_str())
+            .map(str::to_string);
 
-        Some(Task { summary, href })
+        Some(Task { summary, href, due })
     }
 }
 
@@ -90,9 +145,25 @@ const TASKS: &str = r#"
 {{#each tasks as | task | }}
 * [ ] {{task.summary}} [here]({{task.task.href}})
 {{/each }}
+{{#if more }}
+…and {{more}} more
+{{/if }}
 "#;
 
 impl JiraConfig {
+    /// The template the section is rendered with
+    pub(crate) fn template(&self) -> &str {
+        self.template.as_deref().unwrap_or(TASKS)
+    }
+
+    pub(crate) fn token(&self) -> &Token {
+        &self.auth.personal_access_token
+    }
+
+    pub(crate) async fn check_login(&self) -> Result<String> {
+        self.auth.check_login(&self.base_url).await
+    }
+
     pub async fn get_matching_tasks(&self) -> Result<Vec<Task>> {
         let params = [
             ("jql", self.query.to_query()),
@@ -103,7 +174,13 @@ impl JiraConfig {
             .get(&self.base_url)
             .basic_auth(
                 self.auth.user.to_string(),
-                Some(self.auth.personal_access_token.expose_secret()),
+                Some(
+                    self.auth
+                        .personal_access_token
+                        .resolve_in_background()
+                        .await?
+                        .expose_secret(),
+                ),
             )
             .query(&params)
             .send()
@@ -121,6 +198,7 @@ impl JiraConfig {
         let selection = Selection {
             summary: Selector::new("$.fields.summary").unwrap(),
             href: Selector::new("$.self").unwrap(),
+            due: Selector::new("$.fields.duedate").unwrap(),
         };
 
         let mut tasks = Vec::new();
@@ -137,6 +215,172 @@ impl JiraConfig {
     }
 }
 
+const WORKLOGS: &str = r#"
+## Time booked yesterday
+
+{{#each worklogs as | worklog | }}
+* {{worklog.key}} {{worklog.summary}}: {{worklog.time_spent}}
+{{/each }}
+{{#if more }}
+…and {{more}} more
+{{/if }}
+"#;
+
+/// Lists the time the user booked in Jira on the previous day
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JiraWorklogConfig {
+    base_url: String,
+    auth: JiraAuth,
+    template: Option<String>,
+    #[serde(flatten)]
+    limits: Limits,
+    #[serde(flatten)]
+    pub(crate) failure: FailurePolicy,
+    #[serde(skip)]
+    pub(crate) cache: Option<Cache>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Worklog {
+    key: String,
+    summary: String,
+    seconds: i64,
+    time_spent: String,
+}
+
+#[async_trait::async_trait]
+impl Section for JiraWorklogConfig {
+    async fn render(
+        &self,
+        _: &crate::storage::Journal,
+        clock: &dyn crate::Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String> {
+        let yesterday = clock.today() - Duration::days(1);
+        let worklogs = cache::fetch(
+            self.cache.as_ref(),
+            "jira_worklog",
+            &(&self.base_url, &self.auth),
+            clock.today(),
+            self.get_worklogs(yesterday),
+        )
+        .await?;
+        let (worklogs, more) = self.limits.truncate_items(worklogs);
+
+        #[derive(Serialize)]
+        struct C {
+            worklogs: Vec<Worklog>,
+            more: usize,
+        }
+
+        let mut tt = handlebars.clone();
+        tt.register_template_string("worklogs", self.template())?;
+        let rendered = tt.render("worklogs", &C { worklogs, more })?;
+
+        Ok(self.limits.truncate_text(rendered))
+    }
+}
+
+impl JiraWorklogConfig {
+    /// The template the section is rendered with
+    pub(crate) fn template(&self) -> &str {
+        self.template.as_deref().unwrap_or(WORKLOGS)
+    }
+
+    pub(crate) fn token(&self) -> &Token {
+        &self.auth.personal_access_token
+    }
+
+    pub(crate) async fn check_login(&self) -> Result<String> {
+        self.auth.check_login(&self.base_url).await
+    }
+
+    pub async fn get_worklogs(&self, day: Date) -> Result<Vec<Worklog>> {
+        let day = day.format(YEAR_MONTH_DAY)?;
+        let params = [
+            (
+                "jql",
+                format!(r#"worklogAuthor=currentUser() and worklogDate="{}""#, day),
+            ),
+            ("fields", "summary,worklog".to_string()),
+            ("maxResults", "50".to_string()),
+        ];
+        let client = reqwest::Client::new();
+        let res = client
+            .get(&self.base_url)
+            .basic_auth(
+                self.auth.user.to_string(),
+                Some(
+                    self.auth
+                        .personal_access_token
+                        .resolve_in_background()
+                        .await?
+                        .expose_secret(),
+                ),
+            )
+            .query(&params)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let body: Value = res.json::<Value>().await?;
+
+        Ok(worklogs_from(&body, &self.auth.user, &day))
+    }
+}
+
+/// Sums up the time `user` booked on `day` per issue
+fn worklogs_from(body: &Value, user: &str, day: &str) -> Vec<Worklog> {
+    let mut worklogs = Vec::new();
+
+    let issues = body["issues"].as_array().cloned().unwrap_or_default();
+    for issue in issues {
+        let entries = issue["fields"]["worklog"]["worklogs"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        let seconds: i64 = entries
+            .iter()
+            .filter(|entry| {
+                let author = &entry["author"];
+                author["emailAddress"] == user || author["name"] == user
+            })
+            .filter(|entry| {
+                entry["started"]
+                    .as_str()
+                    .map(|started| started.starts_with(day))
+                    .unwrap_or(false)
+            })
+            .filter_map(|entry| entry["timeSpentSeconds"].as_i64())
+            .sum();
+
+        if seconds > 0 {
+            worklogs.push(Worklog {
+                key: issue["key"].as_str().unwrap_or_default().to_string(),
+                summary: issue["fields"]["summary"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                seconds,
+                time_spent: hours_and_minutes(seconds),
+            });
+        }
+    }
+
+    worklogs
+}
+
+fn hours_and_minutes(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -159,6 +403,7 @@ mod tests {
         let config: JiraConfig = serde_yaml::from_str(raw).unwrap();
 
         assert_eq!(config.base_url, "https://x.y/abc");
+        assert_eq!(config.limits, Limits::default());
 
         let JiraAuth {
             user,
@@ -166,7 +411,10 @@ mod tests {
         } = config.auth;
 
         assert_eq!(user, "foo".to_string(),);
-        assert_eq!(*personal_access_token.expose_secret(), "bar".to_string(),);
+        assert_eq!(
+            *personal_access_token.resolve().unwrap().expose_secret(),
+            "bar".to_string(),
+        );
 
         assert_eq!(
             config.query,
@@ -177,4 +425,45 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn sums_up_the_time_booked_per_issue() {
+        let body = serde_json::json!({
+            "issues": [
+                {
+                    "key": "EOPS-1",
+                    "fields": {
+                        "summary": "Rotate the certificates",
+                        "worklog": { "worklogs": [
+                            { "author": { "emailAddress": "foo" }, "started": "2022-03-10T09:00:00.000+0000", "timeSpentSeconds": 3600 },
+                            { "author": { "emailAddress": "foo" }, "started": "2022-03-10T14:00:00.000+0000", "timeSpentSeconds": 1800 },
+                            { "author": { "emailAddress": "foo" }, "started": "2022-03-09T14:00:00.000+0000", "timeSpentSeconds": 1800 },
+                            { "author": { "emailAddress": "bar" }, "started": "2022-03-10T14:00:00.000+0000", "timeSpentSeconds": 1800 }
+                        ]}
+                    }
+                },
+                {
+                    "key": "EOPS-2",
+                    "fields": {
+                        "summary": "Somebody else's work",
+                        "worklog": { "worklogs": [
+                            { "author": { "emailAddress": "bar" }, "started": "2022-03-10T14:00:00.000+0000", "timeSpentSeconds": 1800 }
+                        ]}
+                    }
+                }
+            ]
+        });
+
+        let worklogs = worklogs_from(&body, "foo", "2022-03-10");
+
+        assert_eq!(
+            worklogs,
+            vec![Worklog {
+                key: "EOPS-1".to_string(),
+                summary: "Rotate the certificates".to_string(),
+                seconds: 5400,
+                time_spent: "1h 30m".to_string(),
+            }]
+        );
+    }
 }
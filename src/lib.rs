@@ -1,21 +1,84 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use clap::{AppSettings, StructOpt};
+use time::{Date, OffsetDateTime};
+use tracing::Instrument;
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use archive::ArchiveCmd;
+use auth::AuthCmd;
+use close::CloseCmd;
 use config::ConfigCmd;
-pub use reminders::{Clock, ReminderCmd, ReminderConfig, Reminders, WallClock};
-use storage::Journal;
-use template::Template;
+use daemon::DaemonCmd;
+use debug::DebugCmd;
+use dev::DevCmd;
+use digest::DigestCmd;
+use doctor::DoctorCmd;
+use export::ExportCmd;
+use fmt::FmtCmd;
+use init::InitCmd;
+use list::ListCmd;
+use migrate::MigrateCmd;
+use project::ProjectCmd;
+pub use reminders::{
+    Clock, DueReminder, Priority, ReminderCmd, ReminderConfig, ReminderRef, Reminders,
+    RepeatingDate, TimeOfDay, WallClock,
+};
+use search::SearchCmd;
+use signing::VerifyCmd;
+use stats::StatsCmd;
+use template::{EntryTemplate, Layout, Template};
+use todo::TodoCmd;
 
 pub use config::Config;
+use config::ExistingEntry;
+pub use dev::{carry_over, seed};
+use storage::FrontMatter;
+pub use storage::Journal;
 
+mod accessibility;
+mod archive;
+mod auth;
+mod browse;
+mod cache;
+mod close;
 mod config;
+mod countdowns;
+mod daemon;
+mod datafile;
+mod debug;
+mod dev;
+mod digest;
+mod doctor;
+mod edit;
+mod export;
+mod failure;
+mod fmt;
+mod git;
 mod github;
+mod helpers;
+mod ics;
+mod index;
+mod init;
 mod jira;
+mod limits;
+mod list;
+mod locale;
+mod manage;
+mod migrate;
 mod notes;
+mod post_process;
+mod pretty;
+mod project;
+mod remind_me;
 mod reminders;
+mod scratchpad;
+mod search;
+mod secret_scan;
+mod signing;
+mod stats;
+mod status;
 mod storage;
 mod template;
 mod todo;
@@ -28,22 +91,174 @@ mod todo;
     setting = AppSettings::DeriveDisplayOrder,
 )]
 pub struct Cli {
+    /// Write a detailed trace of what `journal` did to this file, e.g. to attach to a bug report
+    #[clap(long, global = true)]
+    pub trace_file: Option<PathBuf>,
+
+    /// Also look at the entries that `journal archive` moved away
+    #[clap(long, global = true)]
+    include_archive: bool,
+
+    /// Which of the `profiles` in the configuration to use, also set with `JOURNAL__PROFILE`
+    #[clap(long = "journal", global = true)]
+    profile: Option<String>,
+
     #[clap(subcommand)]
     cmd: Cmd,
 }
 
 #[derive(Debug, StructOpt)]
 enum Cmd {
+    /// Set up a new journal and its configuration
+    Init(InitCmd),
     New {
         title: String,
         #[clap(short = 's', long = "stdout")]
         write_to_stdout: bool,
+        /// With colors and bold headings instead of plain markdown
+        #[clap(long, requires = "write-to-stdout")]
+        pretty: bool,
+        /// Don't open the new entry, e.g. when running from cron
+        #[clap(long)]
+        no_open: bool,
+        /// Write the entry for an earlier day, e.g. 2022-03-11, as if it was that day
+        #[clap(long, parse(try_from_str = storage::parse_date))]
+        date: Option<Date>,
+        /// Create the entry even if there already is one for the day
+        #[clap(long)]
+        force: bool,
+        /// Use one of the `templates` in the configuration, e.g. `one-on-one`, instead of a daily entry
+        #[clap(long)]
+        template: Option<String>,
+    },
+    /// Add a note with the time of day to today's entry, e.g. `journal append "Talked to Sam"`
+    Append {
+        note: String,
+        /// The title of today's entry if it has to be created first
+        #[clap(long, default_value = "Notes")]
+        title: String,
+        #[clap(flatten)]
+        dry_run: edit::DryRun,
+    },
+    /// Print what `new` would write today without creating an entry, e.g. to peek at the day's plan
+    Today {
+        /// The title to render the entry with
+        #[clap(default_value = "Today")]
+        title: String,
+        /// Render one of the `templates` in the configuration instead of a daily entry
+        #[clap(long)]
+        template: Option<String>,
+        /// With colors and bold headings instead of plain markdown
+        #[clap(long)]
+        pretty: bool,
+    },
+    /// Look through past entries in the terminal, with a preview and search
+    Browse,
+    /// Open an existing entry, the latest one by default
+    Open {
+        /// Which entry: `latest`, `today`, `yesterday`, or a date like 2022-03-11
+        #[clap(default_value = "latest")]
+        which: String,
     },
     #[clap(subcommand)]
     Reminder(ReminderCmd),
 
     #[clap(subcommand)]
     Config(ConfigCmd),
+
+    /// Check the credentials of your integrations
+    #[clap(subcommand)]
+    Auth(AuthCmd),
+
+    /// Check the configuration, the journal, and logging into integrations for problems
+    Doctor(DoctorCmd),
+
+    #[clap(subcommand)]
+    Todo(TodoCmd),
+
+    /// Wrap up the day: check off TODOs, note what got done, and queue things for tomorrow
+    Close(CloseCmd),
+
+    /// Normalize entries so that they can be read by the TODO carry-over
+    Fmt(FmtCmd),
+
+    /// Show what the configured status emoji mean
+    Legend,
+
+    /// List the entries with their titles, all of them unless filtered
+    List(ListCmd),
+
+    /// Check the integrity of your entries
+    Verify(VerifyCmd),
+
+    /// Summarize what happened recently, e.g. to send to your manager
+    Digest(DigestCmd),
+
+    /// Move old entries into an `archive/<year>/` folder, out of the way of everyday commands
+    Archive(ArchiveCmd),
+
+    /// Turn entries into styled HTML or a PDF, e.g. to print or to keep outside of the journal
+    Export(ExportCmd),
+
+    /// Find the lines of past entries mentioning something, e.g. `journal search flights --in todos`
+    Search(SearchCmd),
+
+    /// Show how many entries there are, the journaling streak, and which sections they have
+    Stats(StatsCmd),
+
+    /// Bring existing entries up to date with changes to the configuration
+    #[clap(subcommand)]
+    Migrate(MigrateCmd),
+
+    /// Collect all entries mentioning a `#tag` into a project page
+    Project(ProjectCmd),
+
+    /// Keep running in the background and show a notification for each reminder when it is due
+    Daemon(DaemonCmd),
+
+    /// Collect details for a bug report
+    #[clap(subcommand)]
+    Debug(DebugCmd),
+
+    /// Tools for working on `journal` itself
+    #[clap(subcommand)]
+    Dev(DevCmd),
+}
+
+/// The entry `which` refers to, the last one written that day if there are several
+fn entry_to_open(journal: &Journal, which: &str, today: Date) -> Result<PathBuf> {
+    let date = match which {
+        "latest" => {
+            return journal
+                .entries()?
+                .pop()
+                .ok_or_else(|| anyhow!("There are no entries yet"))
+        }
+        "today" => today,
+        "yesterday" => today.previous_day().unwrap(),
+        date => Date::parse(date, storage::YEAR_MONTH_DAY).map_err(|_| {
+            anyhow!(
+                "'{}' should be 'latest', 'today', 'yesterday', or a date like 2022-03-11",
+                date
+            )
+        })?,
+    };
+
+    journal.entries_on(date)?.pop().ok_or_else(|| {
+        anyhow!(
+            "There is no entry for {}",
+            date.format(storage::YEAR_MONTH_DAY).unwrap()
+        )
+    })
+}
+
+/// A clock stuck on the day an entry is written for
+struct OnDate(Date);
+
+impl Clock for OnDate {
+    fn today(&self) -> Date {
+        self.0
+    }
 }
 
 fn normalize_filename(raw: &str) -> String {
@@ -52,19 +267,79 @@ fn normalize_filename(raw: &str) -> String {
     r.replace_all(&lower, "").to_string()
 }
 
+/// The rendered entry as it is printed, dressed up for reading in the terminal if it should be `pretty`
+fn for_terminal(markdown: String, pretty: bool, ascii: bool) -> String {
+    match pretty {
+        true => pretty::render(&markdown, ascii),
+        false => markdown,
+    }
+}
+
+impl Cli {
+    /// The profile picked with `--journal`, or else with the `JOURNAL__PROFILE` environment variable
+    pub fn profile(&self) -> Option<String> {
+        self.profile
+            .clone()
+            .or_else(|| std::env::var("JOURNAL__PROFILE").ok())
+    }
+
+    /// Runs the commands that work without a configuration, like `init`
+    pub fn execute_without_config(&self) -> Option<Result<()>> {
+        match self.cmd {
+            Cmd::Init(ref cmd) => Some(cmd.execute(&Config::location())),
+            // The configuration may not even load, which is what this is meant to explain
+            Cmd::Config(ConfigCmd::Check) => {
+                Some(Config::config_path().and_then(|path| config::check(&path)))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[tracing::instrument(name = "Running command", skip_all, fields(command = ?cli.cmd))]
 pub async fn run<O>(cli: Cli, config: &Config, clock: &impl Clock, open: O) -> Result<()>
 where
     O: FnOnce(&Path) -> Result<()>,
 {
-    let journal = Journal::new_at(config.dir.clone());
+    let journal = Journal::new_at(config.dir.clone())
+        .ignoring(&config.ignore)?
+        .including_archive(cli.include_archive)
+        .indexed(config.index_file().as_deref())?;
 
     match cli.cmd {
+        Cmd::Init(cmd) => cmd.execute(&Config::location())?,
         Cmd::Config(cmd) => cmd.execute(config)?,
+        Cmd::Auth(cmd) => cmd.execute(config)?,
+        Cmd::Doctor(cmd) => cmd.execute(config).await?,
+        Cmd::Fmt(cmd) => cmd.execute(&journal, &config.section_headings())?,
+        Cmd::Todo(cmd) => cmd.execute(config, &journal, clock)?,
+        Cmd::Close(cmd) => cmd.execute(&journal, &config.todo_heading())?,
+        Cmd::Daemon(cmd) => cmd.execute(config, &journal, clock).await?,
+        Cmd::Debug(cmd) => cmd.execute(config)?,
+        Cmd::Dev(cmd) => cmd.execute(config, clock)?,
+        Cmd::Verify(cmd) => cmd.execute(config, &journal)?,
+        Cmd::List(cmd) => cmd.execute(&journal, config.ascii)?,
+        Cmd::Digest(cmd) => cmd.execute(config, &journal, clock)?,
+        Cmd::Archive(cmd) => cmd.execute(&journal)?,
+        Cmd::Export(cmd) => cmd.execute(&journal)?,
+        Cmd::Search(cmd) => cmd.execute(&journal)?,
+        Cmd::Stats(cmd) => cmd.execute(&journal, clock.today(), config.ascii)?,
+        Cmd::Migrate(cmd) => cmd.execute(config, &journal)?,
+        Cmd::Project(cmd) => cmd.execute(&journal)?,
+        Cmd::Legend => println!("{}", config.statuses.legend(config.ascii)),
+        Cmd::Browse => browse::browse(&journal, config.ascii)?,
+        Cmd::Open { which } => {
+            let entry = entry_to_open(&journal, &which, clock.today())?;
+            match config.editor {
+                Some(ref editor) => edit::open_at_line(editor, &entry, None)?,
+                None => open(&entry)?,
+            }
+        }
         Cmd::Reminder(cmd) => {
             let with_reminders = config.reminders.is_enabled();
 
             if with_reminders {
-                cmd.execute(config, clock)?;
+                cmd.execute(config, &journal, clock).await?;
             } else {
                 println!("No reminder configuration set. Please add it first");
             }
@@ -72,37 +347,255 @@ where
         Cmd::New {
             title,
             write_to_stdout,
+            pretty,
+            no_open,
+            date,
+            force,
+            template,
         } => {
-            let mut sections = HashMap::new();
+            let backfilling = date.is_some();
+            let clock = &OnDate(date.unwrap_or_else(|| clock.today()));
 
-            for (name, section) in &config.enabled_sections() {
-                sections.insert(name.clone(), section.render(&journal, clock).await?);
+            if write_to_stdout {
+                let (out, _) =
+                    render_entry(config, &journal, clock, &title, template.as_deref()).await?;
+                print!("{}", for_terminal(out, pretty, config.ascii));
+                return Ok(());
             }
 
+            // Entries from a template, like meeting notes, come in addition to the daily one
+            let existing = journal
+                .entries_on(clock.today())?
+                .pop()
+                .filter(|_| !force && template.is_none());
+            let (stored, line, created) = match existing {
+                Some(existing) => {
+                    let day = clock.today().format(storage::YEAR_MONTH_DAY)?;
+                    match config.existing_entry {
+                        ExistingEntry::Fail => bail!(
+                            "There already is an entry for {} in {}, pass --force to create another one",
+                            day,
+                            existing.to_string_lossy()
+                        ),
+                        ExistingEntry::Open => {
+                            println!("There already is an entry for {}", day);
+                        }
+                        ExistingEntry::AddMissingSections => {
+                            let (out, _) =
+                                render_entry(config, &journal, clock, &title, None).await?;
+                            let markdown = std::fs::read_to_string(&existing)?;
+                            let updated = template::add_missing_sections(&markdown, &out);
+                            if updated != markdown {
+                                storage::write_atomically(&existing, updated.as_bytes())?;
+                                git::commit(config, "Add missing sections", clock.today());
+                                println!("Added the missing sections to the entry for {}", day);
+                            }
+                        }
+                    }
+                    (existing, None, false)
+                }
+                None => {
+                    let (stored, line) = create_entry(
+                        config,
+                        &journal,
+                        clock,
+                        &title,
+                        template.as_deref(),
+                        backfilling,
+                    )
+                    .await?;
+                    (stored, line, true)
+                }
+            };
+
+            if config.open_on_create && !no_open {
+                match config.editor {
+                    Some(ref editor) => edit::open_at_line(editor, &stored, line)?,
+                    None => open(&stored)?,
+                }
+            } else if created {
+                println!("Created {}", stored.to_string_lossy());
+            } else {
+                println!("The entry is in {}", stored.to_string_lossy());
+            }
+        }
+        Cmd::Today {
+            title,
+            template,
+            pretty,
+        } => {
+            let (out, _) =
+                render_entry(config, &journal, clock, &title, template.as_deref()).await?;
+            print!("{}", for_terminal(out, pretty, config.ascii));
+        }
+        Cmd::Append {
+            note,
+            title,
+            dry_run,
+        } => {
             let today = clock.today();
+            let entry = match journal.entry_for(today)? {
+                Some(entry) => entry,
+                None if dry_run.is_dry_run() => {
+                    bail!(
+                        "There is no entry for today yet, `journal append` would create one first"
+                    )
+                }
+                None => {
+                    let (path, _) =
+                        create_entry(config, &journal, clock, &title, None, false).await?;
+                    journal.read_entry(path)?
+                }
+            };
 
-            let template = Template {
-                title: title.clone(),
-                today,
-                sections,
+            let note = match clock.local_offset() {
+                Some(offset) => {
+                    let now = OffsetDateTime::now_utc().to_offset(offset);
+                    format!("{:02}:{:02} {}", now.hour(), now.minute(), note)
+                }
+                None => {
+                    eprintln!("Warning: could not determine the local time zone, adding the note without a time");
+                    note
+                }
             };
+            let updated = notes::append_note(&entry.markdown, &note, &config.notes_heading());
+            let changed = dry_run.apply(&entry.path, &entry.markdown, &updated)?;
+            if !dry_run.is_dry_run() {
+                git::commit(config, "Add a note", today);
+                println!("Added the note to {}", entry.path.to_string_lossy());
+            }
+            dry_run.finish(changed as usize)?;
+        }
+    }
 
-            let out = template.render(config.sections.clone())?;
+    Ok(())
+}
 
-            if write_to_stdout {
-                print!("{}", out);
-            } else {
-                let file_title = normalize_filename(&title);
-                let new_filename = format!("{}-{}.md", today, file_title);
+/// Renders a new entry with all enabled sections, or the ones of the named `template`, along with the line to open it at
+async fn render_entry(
+    config: &Config,
+    journal: &Journal,
+    clock: &impl Clock,
+    title: &str,
+    template: Option<&str>,
+) -> Result<(String, Option<usize>)> {
+    let kind = match template {
+        Some(name) => Some(config.template(name)?),
+        None => None,
+    };
+    let mut sections = HashMap::new();
+    let handlebars = config.handlebars(clock.today())?;
 
-                let stored = journal.add_entry(&new_filename, &out)?;
+    for (name, section) in &config.enabled_sections() {
+        if kind.is_some_and(|kind| !kind.has_section(name)) {
+            continue;
+        }
+        let mut rendered = section
+            .render(journal, clock, &handlebars)
+            .instrument(tracing::info_span!("Rendering section", section = ?name))
+            .await?;
+        if let Some(heading) = config.headings.get(name) {
+            rendered = heading.retitle(&rendered);
+        }
+        sections.insert(name.clone(), rendered);
+    }
 
-                open(&stored)?;
-            }
+    let entry = Template {
+        title: title.to_string(),
+        today: clock.today(),
+        sections,
+    };
+
+    let mut rendered = entry.render_with_lines(
+        kind.and_then(EntryTemplate::sections)
+            .unwrap_or(&config.sections)
+            .to_vec(),
+        &handlebars,
+        &Layout {
+            entry_template: kind
+                .and_then(EntryTemplate::entry_template)
+                .or(config.entry_template.as_deref())
+                .unwrap_or(template::DEFAULT_ENTRY_TEMPLATE),
+            date_format: &config.heading_date_format,
+            locale: config.locale,
+        },
+    )?;
+    if let Some(content) = kind.and_then(EntryTemplate::content) {
+        rendered.content = format!(
+            "{}\n\n{}\n",
+            rendered.content.trim_end(),
+            content.trim_end()
+        );
+    }
+    let heading = rendered
+        .line_of(&config.open_at)
+        .and_then(|line| rendered.content.lines().nth(line - 1))
+        .map(str::to_string);
+
+    let mut out = post_process::apply(&config.post_processors, rendered.content)?;
+    // Post-processors may have moved the section, so look for its heading again.
+    // The cursor goes right below it, ready to type.
+    let line = heading
+        .and_then(|heading| out.lines().position(|line| line == heading))
+        .map(|index| index + 2);
+
+    if config.ascii {
+        out = accessibility::plain(&out);
+    }
+
+    if let Some(scanning) = config.secret_scanning() {
+        scanning.check(&out)?;
+    }
+
+    // Added last so post-processors like `wrap` don't mistake it for markdown
+    if let Some(front_matter) = config.front_matter() {
+        let front_matter = FrontMatter {
+            title: Some(title.to_string()),
+            date: Some(clock.today()),
+            tags: front_matter.tags.clone(),
+            template: template.map(str::to_string),
         }
+        .render()?;
+        let line = line.map(|line| line + front_matter.lines().count());
+        return Ok((front_matter + &out, line));
     }
 
-    Ok(())
+    Ok((out, line))
+}
+
+/// Renders a new entry and stores it in the journal, closing the previous one
+async fn create_entry(
+    config: &Config,
+    journal: &Journal,
+    clock: &impl Clock,
+    title: &str,
+    template: Option<&str>,
+    backfilling: bool,
+) -> Result<(PathBuf, Option<usize>)> {
+    // An entry for an earlier day shouldn't change the ones that came after it
+    if !backfilling && config.reminders.is_enabled() {
+        remind_me::take_from_latest(config, journal, clock.today())?;
+    }
+    if let Some(todos) = config.todos().filter(|_| !backfilling) {
+        todo::archive_completed(todos, journal)?;
+    }
+
+    let (out, line) = render_entry(config, journal, clock, title, template).await?;
+
+    // Creating a new entry seals the previous ones
+    if let Some(signing) = config.signing() {
+        signing.seal(journal)?;
+    }
+
+    let file_title = normalize_filename(title);
+    let new_filename =
+        config
+            .date_stamp
+            .path(config.filename.as_deref(), clock.today(), &file_title)?;
+
+    let stored = journal.add_entry(&new_filename, &out)?;
+    git::commit(config, &format!("Add {}", new_filename), clock.today());
+    Ok((stored, line))
 }
 
 #[cfg(test)]
@@ -111,13 +604,13 @@ mod controlled_clock;
 
 #[cfg(test)]
 mod test {
-    use indoc::indoc;
+    use indoc::{formatdoc, indoc};
     use std::sync::{Arc, Mutex};
 
     use super::controlled_clock::ControlledClock;
     use super::*;
     use assert_fs::{prelude::*, TempDir};
-    use predicates::{path::exists, str::diff};
+    use predicates::{path::exists, prelude::*, str::diff};
     use time::ext::NumericalDuration;
     use time::Month::April;
 
@@ -130,9 +623,37 @@ mod test {
             pull_requests: None,
             reminders: Default::default(),
             jira: None,
+            jira_worklog: None,
             todos: Default::default(),
             sections: Vec::new(),
             notes: Default::default(),
+            scratchpad: None,
+            countdowns: None,
+            datafile: None,
+            secret_scanning: None,
+            signing: None,
+            statuses: Default::default(),
+            ascii: false,
+            open_on_create: true,
+            editor: None,
+            open_at: config::SectionName::Notes,
+            post_processors: Vec::new(),
+            locale: Default::default(),
+            date_stamp: Default::default(),
+            filename: None,
+            existing_entry: Default::default(),
+            ignore: Vec::new(),
+            cache: None,
+            git: None,
+            front_matter: None,
+            index: None,
+            include: Vec::new(),
+            profiles: Default::default(),
+            entry_template: None,
+            heading_date_format: Default::default(),
+            headings: Default::default(),
+            templates: Default::default(),
+            partials: Default::default(),
         };
         let open_was_called = Arc::new(Mutex::new(false));
         let open = |_: &Path| {
@@ -142,7 +663,7 @@ mod test {
         };
         let mut clock = ControlledClock::new(2020, April, 22)?;
 
-        let cli = Cli::parse_from(&["journal", "new", "This is great"]);
+        let cli = Cli::parse_from(["journal", "new", "This is great"]);
         run(cli, &config, &clock, open).await?;
         assert!(*open_was_called.lock().unwrap());
         journal_home
@@ -150,7 +671,7 @@ mod test {
             .assert(exists());
 
         clock.advance_by(1.days());
-        let cli = Cli::parse_from(&["journal", "new", "The Next One"]);
+        let cli = Cli::parse_from(["journal", "new", "The Next One"]);
         run(cli, &config, &clock, open).await?;
         journal_home
             .child("2020-04-23-the-next-one.md")
@@ -169,6 +690,333 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn does_not_open_entries_when_asked_not_to() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        journal_home
+            .child("reminders.json")
+            .write_str(r#"{"stored": [] }"#)?;
+        let mut config = Config::from_reader(
+            format!("dir: {}", journal_home.path().to_string_lossy()).as_bytes(),
+        )?;
+        let clock = ControlledClock::new(2020, April, 22)?;
+        let open = |_: &Path| -> Result<()> { panic!("The entry should not have been opened") };
+
+        let cli = Cli::parse_from(["journal", "new", "From cron", "--no-open"]);
+        run(cli, &config, &clock, open).await?;
+        journal_home
+            .child("2020-04-22-from-cron.md")
+            .assert(exists());
+
+        config.open_on_create = false;
+        let cli = Cli::parse_from(["journal", "new", "From the server", "--force"]);
+        run(cli, &config, &clock, open).await?;
+        journal_home
+            .child("2020-04-22-from-the-server.md")
+            .assert(exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn places_the_worklog_without_configured_sections() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        journal_home
+            .child("reminders.json")
+            .write_str(r#"{"stored": [] }"#)?;
+        let config = Config::from_reader(
+            format!(
+                indoc::indoc! {r#"
+                dir: {}
+                jira_worklog:
+                  enabled: true
+                  base_url: http://127.0.0.1:9
+                  auth:
+                    user: me
+                    personal_access_token: abc
+                  on_error: placeholder
+                "#},
+                journal_home.path().to_string_lossy()
+            )
+            .as_bytes(),
+        )?;
+        let clock = ControlledClock::new(2020, April, 22)?;
+        let journal = Journal::new_at(journal_home.path());
+
+        let (entry, _) = render_entry(&config, &journal, &clock, "Planning", None).await?;
+
+        assert!(entry.contains("> Skipped"), "{}", entry);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn imports_calendars_with_import_ics() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        let reminders = journal_home.child("reminders.json");
+        reminders.write_str(r#"{"stored": [] }"#)?;
+        let calendar = journal_home.child("calendar.ics");
+        calendar.write_str(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Dentist
+            DTSTART;VALUE=DATE:20200501
+            END:VEVENT
+            END:VCALENDAR
+        "})?;
+        let config = Config::from_reader(
+            format!("dir: {}", journal_home.path().to_string_lossy()).as_bytes(),
+        )?;
+        let clock = ControlledClock::new(2020, April, 22)?;
+
+        let cli = Cli::parse_from([
+            "journal",
+            "reminders",
+            "import-ics",
+            &calendar.path().to_string_lossy(),
+        ]);
+        run(cli, &config, &clock, |_: &Path| Ok(())).await?;
+
+        assert!(std::fs::read_to_string(reminders.path())?.contains("Dentist"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn appends_notes_only_when_not_a_dry_run() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        let entry = journal_home.child("2020-04-22-notes.md");
+        entry.write_str("# Notes on 2020-04-22\n\n## Notes\n")?;
+        let config = Config::from_reader(
+            format!("dir: {}", journal_home.path().to_string_lossy()).as_bytes(),
+        )?;
+        let clock = ControlledClock::new(2020, April, 22)?;
+
+        let cli = Cli::parse_from(["journal", "append", "Talked to Sam", "--check"]);
+        assert!(run(cli, &config, &clock, |_: &Path| Ok(())).await.is_err());
+        entry.assert(diff("# Notes on 2020-04-22\n\n## Notes\n"));
+
+        let cli = Cli::parse_from(["journal", "append", "Talked to Sam"]);
+        run(cli, &config, &clock, |_: &Path| Ok(())).await?;
+        entry.assert(predicate::str::contains("Talked to Sam"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_the_todo_when_the_reminder_cannot_be_saved() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        journal_home
+            .child("reminders.json")
+            .write_str(r#"{"stored": [] }"#)?;
+        // Backing up the reminders fails, so they can be read but not saved
+        journal_home.child("reminders.json.bak").create_dir_all()?;
+        let entry = journal_home.child("2020-04-22-planning.md");
+        entry.write_str("# Planning on 2020-04-22\n\n## TODOs\n\n* [ ] Call Sam\n")?;
+        let config = Config::from_reader(
+            format!("dir: {}", journal_home.path().to_string_lossy()).as_bytes(),
+        )?;
+        let clock = ControlledClock::new(2020, April, 22)?;
+
+        let cli = Cli::parse_from([
+            "journal",
+            "todo",
+            "promote",
+            "1",
+            "--reminder",
+            "--on",
+            "Monday",
+        ]);
+        assert!(run(cli, &config, &clock, |_: &Path| Ok(())).await.is_err());
+        entry.assert(predicate::str::contains("* [ ] Call Sam"));
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_to_match_reminders_by_blank_text() {
+        for args in [
+            ["journal", "reminders", "delete", "--match", " "],
+            ["journal", "reminders", "list", "--matching", ""],
+        ] {
+            assert!(Cli::try_parse_from(args).is_err());
+        }
+        assert!(
+            Cli::try_parse_from(["journal", "reminders", "delete", "--match", "dentist"]).is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn shows_today_without_creating_an_entry() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        journal_home
+            .child("reminders.json")
+            .write_str(r#"{"stored": [] }"#)?;
+        let config = Config::from_reader(
+            format!("dir: {}", journal_home.path().to_string_lossy()).as_bytes(),
+        )?;
+        let clock = ControlledClock::new(2020, April, 22)?;
+
+        let cli = Cli::parse_from(["journal", "today"]);
+        run(cli, &config, &clock, |_: &Path| Ok(())).await?;
+
+        assert!(Journal::new_at(journal_home.path()).entries()?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn does_not_clobber_the_entry_for_today() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        journal_home
+            .child("reminders.json")
+            .write_str(r#"{"stored": [] }"#)?;
+        let mut config = Config::from_reader(
+            format!("dir: {}", journal_home.path().to_string_lossy()).as_bytes(),
+        )?;
+        let clock = ControlledClock::new(2020, April, 22)?;
+        let entry = journal_home.child("2020-04-22-planning.md");
+
+        let cli = Cli::parse_from(["journal", "new", "Planning", "--no-open"]);
+        run(cli, &config, &clock, |_: &Path| Ok(())).await?;
+        entry.write_str("# Planning on 2020-04-22\n\nWritten by hand\n")?;
+
+        let cli = Cli::parse_from(["journal", "new", "Planning"]);
+        let opened = Mutex::new(None);
+        run(cli, &config, &clock, |path: &Path| {
+            *opened.lock().unwrap() = Some(path.to_path_buf());
+            Ok(())
+        })
+        .await?;
+        assert_eq!(*opened.lock().unwrap(), Some(entry.to_path_buf()));
+        entry.assert(predicates::str::contains("Written by hand"));
+
+        config.existing_entry = ExistingEntry::Fail;
+        let cli = Cli::parse_from(["journal", "new", "Planning", "--no-open"]);
+        assert!(run(cli, &config, &clock, |_: &Path| Ok(())).await.is_err());
+
+        let cli = Cli::parse_from(["journal", "new", "Planning", "--no-open", "--force"]);
+        run(cli, &config, &clock, |_: &Path| Ok(())).await?;
+        entry.assert(predicates::str::contains("Written by hand").not());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn backfills_entries_for_earlier_days() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        journal_home
+            .child("reminders.json")
+            .write_str(r#"{"stored": [] }"#)?;
+        journal_home
+            .child("2020-04-08-earlier.md")
+            .write_str("# Earlier on 2020-04-08\n\n## TODOs\n\n* [ ] From before\n")?;
+        journal_home.child("2020-04-21-later.md").write_str(
+            "# Later on 2020-04-21\n\n## TODOs\n\n* [ ] From after\n* [x] Done after\n",
+        )?;
+        journal_home.child("archive.md").write_str("")?;
+        let config = Config::from_reader(
+            formatdoc! {r#"
+                dir: {}
+                todos:
+                  enabled: true
+                  archive: archive.md
+                "#,
+                journal_home.path().to_string_lossy()
+            }
+            .as_bytes(),
+        )?;
+        let clock = ControlledClock::new(2020, April, 22)?;
+
+        let cli = Cli::parse_from([
+            "journal",
+            "new",
+            "Catch up",
+            "--date",
+            "2020-04-10",
+            "--no-open",
+        ]);
+        run(cli, &config, &clock, |_: &Path| Ok(())).await?;
+
+        let entry = journal_home.child("2020-04-10-catch-up.md");
+        entry.assert(exists());
+        entry.assert(predicates::str::starts_with("# Catch up on 2020-04-10"));
+        entry.assert(predicates::str::contains("From before"));
+        entry.assert(predicates::str::contains("From after").not());
+        journal_home.child("archive.md").assert("");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn creates_entries_from_a_named_template() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        journal_home
+            .child("reminders.json")
+            .write_str(r#"{"stored": [] }"#)?;
+        let config = Config::from_reader(
+            formatdoc! {r#"
+                dir: {}
+                templates:
+                  one-on-one:
+                    sections: [notes]
+                    content: '## Feedback'
+                "#,
+                journal_home.path().to_string_lossy()
+            }
+            .as_bytes(),
+        )?;
+        let clock = ControlledClock::new(2020, April, 22)?;
+
+        let cli = Cli::parse_from(["journal", "new", "Standup", "--no-open"]);
+        run(cli, &config, &clock, |_: &Path| Ok(())).await?;
+        let cli = Cli::parse_from([
+            "journal",
+            "new",
+            "Sam",
+            "--template",
+            "one-on-one",
+            "--no-open",
+        ]);
+        run(cli, &config, &clock, |_: &Path| Ok(())).await?;
+
+        let entry = journal_home.child("2020-04-22-sam.md");
+        entry.assert(predicates::str::starts_with(
+            "# Sam on 2020-04-22\n\n## Notes\n",
+        ));
+        entry.assert(predicates::str::ends_with("\n\n## Feedback\n"));
+        entry.assert(predicates::str::contains("## TODOs").not());
+
+        let cli = Cli::parse_from(["journal", "new", "Sam", "--template", "retro"]);
+        let err = run(cli, &config, &clock, |_: &Path| Ok(())).await.err();
+        assert_eq!(
+            err.map(|e| e.to_string()),
+            Some("There is no template 'retro', only: one-on-one".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn finds_the_entry_to_open() -> Result<()> {
+        let journal_home = TempDir::new()?;
+        let journal = Journal::new_at(journal_home.path());
+        let today = ControlledClock::new(2020, April, 22)?.today();
+
+        assert!(entry_to_open(&journal, "latest", today).is_err());
+
+        journal.add_entry("2020-04-21-planning.md", "# Planning")?;
+        journal.add_entry("2020-04-22-standup.md", "# Standup")?;
+
+        assert_eq!(
+            entry_to_open(&journal, "latest", today)?,
+            journal_home.child("2020-04-22-standup.md").path()
+        );
+        assert_eq!(
+            entry_to_open(&journal, "yesterday", today)?,
+            journal_home.child("2020-04-21-planning.md").path()
+        );
+        assert_eq!(
+            entry_to_open(&journal, "2020-04-21", today)?,
+            journal_home.child("2020-04-21-planning.md").path()
+        );
+        assert!(entry_to_open(&journal, "2020-04-20", today).is_err());
+        assert!(entry_to_open(&journal, "last week", today).is_err());
+        Ok(())
+    }
+
     mod title {
         use data_test::data_test;
 
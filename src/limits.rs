@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+/// Caps on how much a section may contribute to an entry,
+/// so a runaway query can't produce thousands of lines
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Limits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_chars: Option<usize>,
+}
+
+impl Limits {
+    /// Keeps at most `max_items` and returns how many were dropped
+    pub fn truncate_items<T>(&self, mut items: Vec<T>) -> (Vec<T>, usize) {
+        match self.max_items {
+            Some(max) if items.len() > max => {
+                let more = items.len() - max;
+                items.truncate(max);
+                (items, more)
+            }
+            _ => (items, 0),
+        }
+    }
+
+    /// Cuts the rendered section at the last full line that fits into `max_chars`
+    pub fn truncate_text(&self, text: String) -> String {
+        let max = match self.max_chars {
+            Some(max) if text.chars().count() > max => max,
+            _ => return text,
+        };
+
+        let mut kept = String::new();
+        let mut lines = text.lines();
+        for line in lines.by_ref() {
+            if kept.chars().count() + line.chars().count() + 1 > max {
+                let more = 1 + lines.filter(|line| !line.trim().is_empty()).count();
+                kept.push_str(&format!("…and {} more lines\n", more));
+                return kept;
+            }
+            kept.push_str(line);
+            kept.push('\n');
+        }
+        kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn keeps_everything_without_limits() {
+        let limits = Limits::default();
+
+        assert_eq!(limits.truncate_items(vec![1, 2, 3]), (vec![1, 2, 3], 0));
+        assert_eq!(limits.truncate_text("abc".to_string()), "abc");
+    }
+
+    #[test]
+    fn drops_items_beyond_the_limit() {
+        let limits = Limits {
+            max_items: Some(2),
+            max_chars: None,
+        };
+
+        assert_eq!(limits.truncate_items(vec![1, 2, 3, 4]), (vec![1, 2], 2));
+    }
+
+    #[test]
+    fn cuts_text_at_line_boundaries() {
+        let limits = Limits {
+            max_items: None,
+            max_chars: Some(30),
+        };
+
+        let text = indoc! {r#"
+        ## PRs
+        * [ ] first
+        * [ ] second
+        * [ ] third
+        "#};
+
+        assert_eq!(
+            limits.truncate_text(text.to_string()),
+            indoc! {r#"
+            ## PRs
+            * [ ] first
+            …and 2 more lines
+            "#}
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Result};
+use clap::StructOpt;
+use serde::Serialize;
+use tabled::{Table, Tabled};
+use time::Date;
+
+use crate::accessibility;
+use crate::reminders::ListFormat;
+use crate::storage::{Journal, YEAR_MONTH_DAY};
+
+#[derive(Debug, StructOpt)]
+pub struct ListCmd {
+    /// Only show entries from this day on, e.g. `--since 2022-03-01`
+    #[clap(long)]
+    since: Option<String>,
+    /// Only show this many of the most recent entries
+    #[clap(long)]
+    limit: Option<usize>,
+    /// Either `table` or `json`
+    #[clap(long, default_value = "table")]
+    format: ListFormat,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+struct Row {
+    date: String,
+    title: String,
+    #[tabled(display_with = "comma_separated")]
+    tags: Vec<String>,
+    file: String,
+    #[tabled(skip)]
+    path: String,
+}
+
+fn comma_separated(tags: &[String]) -> String {
+    tags.join(", ")
+}
+
+impl ListCmd {
+    pub fn execute(&self, journal: &Journal, ascii: bool) -> Result<()> {
+        let since = match self.since {
+            Some(ref since) => Some(
+                Date::parse(since, YEAR_MONTH_DAY)
+                    .map_err(|_| anyhow!("'{}' should be a date like 2022-03-11", since))?,
+            ),
+            None => None,
+        };
+
+        let rows = rows(journal, since, self.limit)?;
+        match self.format {
+            ListFormat::Table => println!("{}", accessibility::styled(Table::new(&rows), ascii)),
+            ListFormat::Json => println!("{}", serde_json::to_string_pretty(&rows)?),
+        }
+        Ok(())
+    }
+}
+
+/// The entries written on or after `since`, oldest first, keeping only the `limit` most recent ones
+fn rows(journal: &Journal, since: Option<Date>, limit: Option<usize>) -> Result<Vec<Row>> {
+    let mut rows = Vec::new();
+    for path in journal.entries()? {
+        let entry = journal.read_entry(path)?;
+        match (since, entry.date()) {
+            (Some(since), Some(date)) if date < since => continue,
+            (Some(_), None) => continue,
+            _ => {}
+        }
+
+        rows.push(Row {
+            date: entry.describe(),
+            title: entry.title(),
+            tags: entry.tags(),
+            file: entry
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: entry.path.to_string_lossy().to_string(),
+        });
+    }
+
+    if let Some(limit) = limit {
+        rows.drain(..rows.len().saturating_sub(limit));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use time::macros::date;
+
+    #[test]
+    fn only_lists_recent_entries() -> Result<()> {
+        let dir = TempDir::new()?;
+        let journal = Journal::new_at(dir.path());
+        journal.add_entry("2022-02-28-planning.md", "# Planning on 2022-02-28")?;
+        journal.add_entry("2022-03-01-standup.md", "# Standup on 2022-03-01")?;
+        journal.add_entry("2022-03-02-retro.md", "# Retro on 2022-03-02")?;
+
+        let titles = |rows: Vec<Row>| rows.into_iter().map(|row| row.title).collect::<Vec<_>>();
+
+        assert_eq!(
+            titles(rows(&journal, Some(date!(2022 - 03 - 01)), None)?),
+            vec!["Standup", "Retro"]
+        );
+        assert_eq!(titles(rows(&journal, None, Some(1))?), vec!["Retro"]);
+        assert_eq!(titles(rows(&journal, None, None)?).len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use time::{Month, Weekday};
+
+/// The language month and weekday names are typed in, e.g. in `--on 15.März`.
+/// English names and numeric months always work.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+    Weekday::Saturday,
+    Weekday::Sunday,
+];
+
+struct Names {
+    months: [&'static str; 12],
+    short_months: [&'static str; 12],
+    /// Starting with Monday
+    weekdays: [&'static str; 7],
+    short_weekdays: [&'static str; 7],
+}
+
+#[rustfmt::skip]
+const ENGLISH: Names = Names {
+    months: ["january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december"],
+    short_months: ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"],
+    weekdays: ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"],
+    short_weekdays: ["mon", "tue", "wed", "thu", "fri", "sat", "sun"],
+};
+
+#[rustfmt::skip]
+const GERMAN: Names = Names {
+    months: ["januar", "februar", "märz", "april", "mai", "juni", "juli", "august", "september", "oktober", "november", "dezember"],
+    short_months: ["jan", "feb", "mär", "apr", "mai", "jun", "jul", "aug", "sep", "okt", "nov", "dez"],
+    weekdays: ["montag", "dienstag", "mittwoch", "donnerstag", "freitag", "samstag", "sonntag"],
+    short_weekdays: ["mo", "di", "mi", "do", "fr", "sa", "so"],
+};
+
+#[rustfmt::skip]
+const FRENCH: Names = Names {
+    months: ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"],
+    short_months: ["janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct", "nov", "déc"],
+    weekdays: ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+    short_weekdays: ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"],
+};
+
+#[rustfmt::skip]
+const SPANISH: Names = Names {
+    months: ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre"],
+    short_months: ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic"],
+    weekdays: ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+    short_weekdays: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+};
+
+impl Locale {
+    fn names(&self) -> &'static Names {
+        match self {
+            Locale::En => &ENGLISH,
+            Locale::De => &GERMAN,
+            Locale::Fr => &FRENCH,
+            Locale::Es => &SPANISH,
+        }
+    }
+
+    /// The names to try, in order. The locale's own come first as they can clash with English ones.
+    fn candidates(&self) -> Vec<&'static Names> {
+        match self {
+            Locale::En => vec![&ENGLISH],
+            other => vec![other.names(), &ENGLISH],
+        }
+    }
+
+    /// A month by its name, abbreviation, or number
+    pub fn parse_month(&self, month: &str) -> Result<Month, String> {
+        if let Ok(number) = month.parse::<u8>() {
+            return Month::try_from(number).map_err(|_| format!("No month {}", number));
+        }
+
+        let lower = month.to_lowercase();
+        self.candidates()
+            .iter()
+            .find_map(|names| {
+                names
+                    .months
+                    .iter()
+                    .zip(names.short_months.iter())
+                    .position(|(full, short)| lower == *full || lower == *short)
+            })
+            .map(|index| Month::try_from(index as u8 + 1).unwrap())
+            .ok_or_else(|| format!("No matching month name: {}", month))
+    }
+
+    /// A day of the week by its name or abbreviation
+    pub fn parse_weekday(&self, weekday: &str) -> Result<Weekday, String> {
+        let lower = weekday.to_lowercase();
+        // A typo that made it into the README and therefore into people's habits
+        if *self == Locale::En && lower == "wedneday" {
+            return Ok(Weekday::Wednesday);
+        }
+
+        self.candidates()
+            .iter()
+            .find_map(|names| {
+                names
+                    .weekdays
+                    .iter()
+                    .zip(names.short_weekdays.iter())
+                    .position(|(full, short)| lower == *full || lower == *short)
+            })
+            .map(|index| WEEKDAYS[index])
+            .ok_or_else(|| format!("No matching day of the week: {}", weekday))
+    }
+
+    /// Replaces the English month and weekday names in an already formatted date with the locale's own
+    pub fn translate_date(&self, formatted: &str) -> String {
+        if *self == Locale::En {
+            return formatted.to_string();
+        }
+
+        let mut translated = String::new();
+        let mut word = String::new();
+        for c in formatted.chars().map(Some).chain([None]) {
+            match c {
+                Some(c) if c.is_alphabetic() => word.push(c),
+                _ => {
+                    match self.translate_name(&word) {
+                        Some(name) => translated.push_str(&name),
+                        None => translated.push_str(&word),
+                    }
+                    word.clear();
+                    translated.extend(c);
+                }
+            }
+        }
+        translated
+    }
+
+    fn translate_name(&self, english: &str) -> Option<String> {
+        let lower = english.to_lowercase();
+        let own = self.names();
+        let name = [
+            (&ENGLISH.months[..], &own.months[..]),
+            (&ENGLISH.short_months, &own.short_months),
+            (&ENGLISH.weekdays, &own.weekdays),
+            (&ENGLISH.short_weekdays, &own.short_weekdays),
+        ]
+        .iter()
+        .find_map(|(english, own)| {
+            english
+                .iter()
+                .position(|name| *name == lower)
+                .map(|index| own[index])
+        })?;
+
+        // German capitalizes its nouns, French and Spanish write them in lower case
+        Some(match self {
+            Locale::De => {
+                let mut chars = name.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            _ => name.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_numeric_and_english_months_in_any_locale() {
+        assert_eq!(Locale::En.parse_month("01"), Ok(Month::January));
+        assert_eq!(Locale::En.parse_month("Sep"), Ok(Month::September));
+        assert_eq!(Locale::De.parse_month("December"), Ok(Month::December));
+        assert!(Locale::En.parse_month("13").is_err());
+        assert!(Locale::En.parse_month("März").is_err());
+    }
+
+    #[test]
+    fn prefers_the_names_of_the_locale() {
+        assert_eq!(Locale::De.parse_month("März"), Ok(Month::March));
+        assert_eq!(Locale::Fr.parse_month("août"), Ok(Month::August));
+        assert_eq!(Locale::De.parse_weekday("Do"), Ok(Weekday::Thursday));
+        assert_eq!(Locale::Es.parse_weekday("dom"), Ok(Weekday::Sunday));
+        // `mar` is Tuesday in French, but March in English
+        assert_eq!(Locale::Fr.parse_weekday("mar"), Ok(Weekday::Tuesday));
+        assert_eq!(Locale::Fr.parse_weekday("Friday"), Ok(Weekday::Friday));
+    }
+
+    #[test]
+    fn writes_dates_in_the_language_of_the_locale() {
+        assert_eq!(
+            Locale::En.translate_date("Monday, 3 June 2024"),
+            "Monday, 3 June 2024"
+        );
+        assert_eq!(
+            Locale::De.translate_date("Monday, 3 June 2024"),
+            "Montag, 3 Juni 2024"
+        );
+        assert_eq!(Locale::Fr.translate_date("Mon 3 Jun"), "lun 3 juin");
+        assert_eq!(Locale::Es.translate_date("2024-06-03"), "2024-06-03");
+    }
+}
@@ -1,38 +1,65 @@
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Mutex;
 
 use anyhow::{Context, Result};
 use clap::StructOpt;
 use journal::{run, Cli, Config, WallClock};
 use tracing::Level;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::prelude::*;
 
 fn to_level<S: AsRef<str>>(level: S) -> Result<Level, ()> {
     Level::from_str(level.as_ref()).map_err(|_| ())
 }
 
-fn init_logs() {
+fn init_logs(trace_file: Option<&Path>) -> Result<()> {
     let level = std::env::var("JOURNAL__LOG_LEVEL")
         .map_err(|_| ())
         .and_then(to_level)
         .unwrap_or(Level::ERROR);
 
-    let subscriber = tracing_subscriber::FmtSubscriber::builder()
-        .with_max_level(level)
-        .finish();
+    let console = tracing_subscriber::fmt::layer().with_filter(LevelFilter::from_level(level));
 
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+    // The trace file gets everything, including how long each span took
+    let trace = match trace_file {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Could not create trace file {:?}", path))?;
+            let layer = tracing_subscriber::fmt::layer()
+                .with_writer(Mutex::new(file))
+                .with_ansi(false)
+                .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+                .with_filter(LevelFilter::TRACE);
+            Some(layer)
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(console)
+        .with(trace)
+        .try_init()
+        .context("setting default subscriber failed")
 }
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
-    init_logs();
-
+    // Before anything gets to start another thread
+    let clock = WallClock::new();
     let cli = Cli::parse();
-    let config_path: PathBuf = Config::config_path()?;
-    let config_file = std::fs::File::open(config_path)?;
-    let config = Config::from_reader(config_file).context("Failed to load configuration")?;
+    init_logs(cli.trace_file.as_deref())?;
+
+    if let Some(result) = cli.execute_without_config() {
+        return result;
+    }
+
+    let config = tracing::info_span!("Loading configuration").in_scope(|| -> Result<Config> {
+        let config_path: PathBuf = Config::config_path()?;
+        Config::load(&config_path, cli.profile().as_deref()).context("Failed to load configuration")
+    })?;
 
-    let clock = WallClock;
     let open = |path: &Path| open::that(path).map_err(|e| anyhow::anyhow!(e));
 
     run(cli, &config, &clock, open).await
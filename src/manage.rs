@@ -0,0 +1,354 @@
+use std::io::Stdout;
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::{Frame, Terminal};
+
+use crate::locale::Locale;
+use crate::reminders::{self, Reminder, RepeatingDate, SpecificDate};
+use crate::{Clock, Config, Reminders};
+
+const HELP: &str = "j/k: move  d: delete  e: edit  n: new  q: save and quit";
+
+/// Lets the user browse, delete, edit, and add reminders until they quit.
+/// Changes are made to `reminders`, saving them is up to the caller.
+pub(crate) fn manage(reminders: &mut Reminders, clock: &dyn Clock, config: &Config) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let app = App::new(reminders, clock, config.locale);
+    let result = run(&mut terminal, app, config.ascii);
+
+    // Always give the terminal back, even if something went wrong
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>, mut app: App, ascii: bool) -> Result<()> {
+    loop {
+        terminal.draw(|f| draw(f, &mut app, ascii))?;
+
+        if let Event::Key(key) = event::read()? {
+            // Windows also reports releasing a key
+            if key.kind == KeyEventKind::Press && app.handle(key) == Flow::Quit {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Flow {
+    Continue,
+    Quit,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Mode {
+    Browse,
+    Edit { nr: usize, input: String },
+    NewWhen { input: String },
+    NewText { when: String, input: String },
+}
+
+struct App<'a> {
+    reminders: &'a mut Reminders,
+    clock: &'a dyn Clock,
+    locale: Locale,
+    rows: Vec<Reminder>,
+    table: TableState,
+    mode: Mode,
+    message: Option<String>,
+}
+
+impl<'a> App<'a> {
+    fn new(reminders: &'a mut Reminders, clock: &'a dyn Clock, locale: Locale) -> Self {
+        let mut app = App {
+            reminders,
+            clock,
+            locale,
+            rows: Vec::new(),
+            table: TableState::default(),
+            mode: Mode::Browse,
+            message: None,
+        };
+        app.refresh();
+        app
+    }
+
+    /// Re-reads the reminders after they changed, keeping the cursor in range
+    fn refresh(&mut self) {
+        self.rows = self.reminders.all(self.clock);
+        reminders::by_next_occurrence(&mut self.rows);
+
+        let selected = match self.rows.len() {
+            0 => None,
+            len => Some(self.table.selected().unwrap_or(0).min(len - 1)),
+        };
+        self.table.select(selected);
+    }
+
+    fn selected(&self) -> Option<&Reminder> {
+        self.table.selected().and_then(|i| self.rows.get(i))
+    }
+
+    fn handle(&mut self, key: KeyEvent) -> Flow {
+        let mode = std::mem::replace(&mut self.mode, Mode::Browse);
+        self.mode = match mode {
+            Mode::Browse => return self.browse(key.code),
+            Mode::Edit { nr, input } => match edit_line(input, key.code) {
+                Input::Typing(input) => Mode::Edit { nr, input },
+                Input::Cancelled => Mode::Browse,
+                Input::Done(text) => {
+                    self.message = Some(match self.reminders.edit(nr as u32, &text) {
+                        Ok(()) => format!("Changed {} to '{}'", nr, text),
+                        Err(e) => e.to_string(),
+                    });
+                    self.refresh();
+                    Mode::Browse
+                }
+            },
+            Mode::NewWhen { input } => match edit_line(input, key.code) {
+                Input::Typing(input) => Mode::NewWhen { input },
+                Input::Cancelled => Mode::Browse,
+                Input::Done(when) => Mode::NewText {
+                    when,
+                    input: String::new(),
+                },
+            },
+            Mode::NewText { when, input } => match edit_line(input, key.code) {
+                Input::Typing(input) => Mode::NewText { when, input },
+                Input::Cancelled => Mode::Browse,
+                Input::Done(text) => {
+                    self.message = Some(match self.add(&when, &text) {
+                        Ok(()) => format!("Added '{}'", text),
+                        Err(e) => e,
+                    });
+                    self.refresh();
+                    Mode::Browse
+                }
+            },
+        };
+        Flow::Continue
+    }
+
+    fn browse(&mut self, key: KeyCode) -> Flow {
+        self.message = None;
+        let selected = self.table.selected().unwrap_or(0);
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Flow::Quit,
+            KeyCode::Char('j') | KeyCode::Down if selected + 1 < self.rows.len() => {
+                self.table.select(Some(selected + 1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.table.select(Some(selected.saturating_sub(1)));
+            }
+            KeyCode::Char('d') => {
+                if let Some((nr, text)) = self.selected().map(|r| (r.nr, r.reminder.clone())) {
+                    self.message = Some(match self.reminders.delete(nr as u32) {
+                        Ok(()) => format!("Deleted '{}'", text),
+                        Err(e) => e.to_string(),
+                    });
+                    self.refresh();
+                }
+            }
+            KeyCode::Char('e') => {
+                if let Some(reminder) = self.selected() {
+                    self.mode = Mode::Edit {
+                        nr: reminder.nr,
+                        input: reminder.reminder.clone(),
+                    };
+                }
+            }
+            KeyCode::Char('n') => {
+                self.mode = Mode::NewWhen {
+                    input: String::new(),
+                }
+            }
+            _ => {}
+        }
+        Flow::Continue
+    }
+
+    /// Adds a reminder, where `when` is like `every 3.days` or `on 14.Feb` just like the flags of `new`
+    fn add(&mut self, when: &str, text: &str) -> Result<(), String> {
+        if text.is_empty() {
+            return Err("A reminder needs some text".to_string());
+        }
+
+        let today = self.clock.today();
+        match when.split_once(' ') {
+            Some(("every", interval)) => {
+                let interval = RepeatingDate::parse(interval, self.locale)?;
+                self.reminders.every_since(today, &interval, text);
+            }
+            Some(("on", date)) => {
+                let date = SpecificDate::parse(date, self.locale)?.next_date(today);
+                self.reminders.on_date(date, text);
+            }
+            _ => {
+                return Err(format!(
+                    "'{}' should start with 'every' or 'on', e.g. 'every Monday'",
+                    when
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    fn prompt(&self) -> String {
+        match &self.mode {
+            Mode::Browse => self.message.clone().unwrap_or_else(|| HELP.to_string()),
+            Mode::Edit { input, .. } => format!("Reminder: {}", input),
+            Mode::NewWhen { input } => {
+                format!("When (e.g. 'every Monday', 'on 14.Feb'): {}", input)
+            }
+            Mode::NewText { input, .. } => format!("Reminder: {}", input),
+        }
+    }
+}
+
+enum Input {
+    Typing(String),
+    Cancelled,
+    Done(String),
+}
+
+/// A single line of text input, finished with Enter and cancelled with Esc
+fn edit_line(mut input: String, key: KeyCode) -> Input {
+    match key {
+        KeyCode::Enter => return Input::Done(input.trim().to_string()),
+        KeyCode::Esc => return Input::Cancelled,
+        KeyCode::Backspace => {
+            input.pop();
+        }
+        KeyCode::Char(c) => input.push(c),
+        _ => {}
+    }
+    Input::Typing(input)
+}
+
+fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App, ascii: bool) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(f.size());
+
+    let rows = app.rows.iter().map(|reminder| {
+        Row::new(vec![
+            Cell::from(reminder.nr.to_string()),
+            Cell::from(reminder.date.clone()),
+            Cell::from(reminder.next.clone()),
+            Cell::from(reminder.priority.to_string()),
+            Cell::from(reminder.reminder.clone()),
+        ])
+    });
+    let header = Row::new(vec!["nr", "date", "next", "priority", "reminder"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let borders = if ascii { Borders::NONE } else { Borders::ALL };
+    let widths = [
+        Constraint::Length(4),
+        Constraint::Length(24),
+        Constraint::Length(24),
+        Constraint::Length(8),
+        Constraint::Min(10),
+    ];
+
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().borders(borders).title("Reminders"))
+        .widths(&widths)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(if ascii { "> " } else { "▶ " });
+
+    f.render_stateful_widget(table, chunks[0], &mut app.table);
+    f.render_widget(Paragraph::new(app.prompt()), chunks[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controlled_clock::ControlledClock;
+    use crossterm::event::KeyModifiers;
+    use time::Month::July;
+
+    fn type_in(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            app.handle(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> Flow {
+        app.handle(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn texts(app: &App) -> Vec<String> {
+        app.rows.iter().map(|r| r.reminder.clone()).collect()
+    }
+
+    #[test]
+    fn adds_edits_and_deletes_reminders() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let mut reminders: Reminders = serde_json::from_str(r#"{"stored": []}"#)?;
+        let mut app = App::new(&mut reminders, &clock, Locale::default());
+
+        type_in(&mut app, "n");
+        type_in(&mut app, "every 3.days");
+        press(&mut app, KeyCode::Enter);
+        type_in(&mut app, "Water the plantz");
+        press(&mut app, KeyCode::Enter);
+
+        type_in(&mut app, "n");
+        type_in(&mut app, "on 20.Jul");
+        press(&mut app, KeyCode::Enter);
+        type_in(&mut app, "Dentist");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(texts(&app), vec!["Water the plantz", "Dentist"]);
+
+        type_in(&mut app, "e");
+        press(&mut app, KeyCode::Backspace);
+        type_in(&mut app, "s");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(texts(&app), vec!["Water the plants", "Dentist"]);
+
+        type_in(&mut app, "jd");
+        assert_eq!(texts(&app), vec!["Water the plants"]);
+        assert_eq!(app.message, Some("Deleted 'Dentist'".to_string()));
+        assert_eq!(app.table.selected(), Some(0));
+
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Flow::Quit);
+        Ok(())
+    }
+
+    #[test]
+    fn explains_what_went_wrong_when_adding_a_reminder() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let mut reminders: Reminders = serde_json::from_str(r#"{"stored": []}"#)?;
+        let mut app = App::new(&mut reminders, &clock, Locale::default());
+
+        type_in(&mut app, "ntomorrow");
+        press(&mut app, KeyCode::Enter);
+        type_in(&mut app, "Something");
+        press(&mut app, KeyCode::Enter);
+
+        assert!(texts(&app).is_empty());
+        assert_eq!(
+            app.message,
+            Some("'tomorrow' should start with 'every' or 'on', e.g. 'every Monday'".to_string())
+        );
+        Ok(())
+    }
+}
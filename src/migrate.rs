@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use clap::StructOpt;
+use regex::{Captures, Regex};
+
+use crate::edit::DryRun;
+use crate::project::PROJECTS_DIR;
+use crate::signing::signature_of;
+use crate::storage::{link, normalized, restamped, Journal};
+use crate::Config;
+
+#[derive(Debug, StructOpt)]
+pub enum MigrateCmd {
+    /// Rename all entries to use the configured `date_stamp`, along with the links to them
+    Filenames {
+        #[clap(flatten)]
+        dry_run: DryRun,
+    },
+}
+
+impl MigrateCmd {
+    pub(crate) fn execute(self, config: &Config, journal: &Journal) -> Result<()> {
+        match self {
+            MigrateCmd::Filenames { dry_run } => {
+                let renames = journal
+                    .entries()?
+                    .into_iter()
+                    .filter_map(|path| restamped(&path, config.date_stamp).map(|new| (path, new)))
+                    .collect::<Vec<_>>();
+
+                // Nothing is changed unless every entry can be renamed
+                let mut targets = HashSet::new();
+                for (from, to) in &renames {
+                    if to.exists() || !targets.insert(to) {
+                        bail!(
+                            "Not renaming {} as {} is already taken",
+                            from.to_string_lossy(),
+                            to.to_string_lossy()
+                        );
+                    }
+                }
+
+                // Links in entries and project pages would point to the old names otherwise
+                let mut relinked = 0;
+                for path in files_with_links(journal)? {
+                    let original = std::fs::read_to_string(&path)?;
+                    let dir = path.parent().unwrap_or(&path);
+                    let updated = relink(&original, dir, &renames);
+                    if updated == original {
+                        continue;
+                    }
+                    if signature_of(&path).exists() {
+                        println!(
+                            "Left the links in {} as they are, changing it would break its signature",
+                            path.to_string_lossy()
+                        );
+                        continue;
+                    }
+                    if dry_run.apply(&path, &original, &updated)? {
+                        relinked += 1;
+                    }
+                }
+
+                for (path, new_path) in &renames {
+                    dry_run.rename(path, new_path)?;
+                    // Signatures are of the content, so they stay valid under the new name
+                    let signature = signature_of(path);
+                    if signature.exists() {
+                        dry_run.rename(&signature, &signature_of(new_path))?;
+                    }
+                }
+
+                if !dry_run.is_dry_run() {
+                    println!(
+                        "Renamed {} entries and updated the links in {} files",
+                        renames.len(),
+                        relinked
+                    );
+                }
+                dry_run.finish(renames.len() + relinked)
+            }
+        }
+    }
+}
+
+/// The entries and project pages, which can link to entries
+fn files_with_links(journal: &Journal) -> Result<Vec<PathBuf>> {
+    let mut files = journal.entries()?;
+
+    let projects = journal.child_file(PROJECTS_DIR);
+    if projects.is_dir() {
+        for page in std::fs::read_dir(&projects)? {
+            let page = page?.path();
+            if page.extension().is_some_and(|ext| ext == "md") {
+                files.push(page);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// `markdown` in `dir` with its links to renamed entries changed to their new name
+fn relink(markdown: &str, dir: &Path, renames: &[(PathBuf, PathBuf)]) -> String {
+    let target = Regex::new(r"\]\(([^)\s]+)\)").unwrap();
+
+    target
+        .replace_all(markdown, |captures: &Captures| {
+            let linked = dir.join(&captures[1]);
+            match renames
+                .iter()
+                .find(|(from, _)| normalized(from) == normalized(&linked))
+            {
+                Some((_, to)) => format!("]({})", link(dir, to)),
+                None => captures[0].to_string(),
+            }
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::DateStamp;
+    use assert_fs::{prelude::*, TempDir};
+
+    #[test]
+    fn renames_entries_and_their_signatures() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("2022-03-10-standup.md").write_str("first")?;
+        dir.child("2022-03-10-standup.md.sig").write_str("signed")?;
+        dir.child("11-03-2022-retro.md").write_str("second")?;
+
+        let mut config: Config = serde_yaml::from_str("dir: /tmp")?;
+        config.date_stamp = DateStamp::DayMonthYear;
+
+        let journal = Journal::new_at(dir.path());
+        MigrateCmd::Filenames {
+            dry_run: DryRun::default(),
+        }
+        .execute(&config, &journal)?;
+
+        dir.child("10-03-2022-standup.md").assert("first");
+        dir.child("10-03-2022-standup.md.sig").assert("signed");
+        dir.child("11-03-2022-retro.md").assert("second");
+        dir.child("2022-03-10-standup.md")
+            .assert(predicates::path::missing());
+        Ok(())
+    }
+
+    #[test]
+    fn updates_links_to_renamed_entries() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("2022/2022-03-10-standup.md")
+            .write_str("# Standup")?;
+        dir.child("2022/2022-03-11-retro.md").write_str(
+            "* [ ] Send the figures ([from 2022-03-10](2022-03-10-standup.md))\n[Notes](notes.md)\n",
+        )?;
+        dir.child("projects/apollo.md")
+            .write_str("## 2022-03-10: [Standup](../2022/2022-03-10-standup.md)\n")?;
+
+        let mut config: Config = serde_yaml::from_str("dir: /tmp")?;
+        config.date_stamp = DateStamp::Compact;
+
+        let journal = Journal::new_at(dir.path());
+        MigrateCmd::Filenames {
+            dry_run: DryRun::default(),
+        }
+        .execute(&config, &journal)?;
+
+        dir.child("2022/20220311-retro.md").assert(
+            "* [ ] Send the figures ([from 2022-03-10](20220310-standup.md))\n[Notes](notes.md)\n",
+        );
+        dir.child("projects/apollo.md")
+            .assert("## 2022-03-10: [Standup](../2022/20220310-standup.md)\n");
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_to_rename_over_other_files() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("2022-03-10-standup.md").write_str("first")?;
+        dir.child("10-03-2022-standup.md")
+            .write_str("already here")?;
+
+        let mut config: Config = serde_yaml::from_str("dir: /tmp")?;
+        config.date_stamp = DateStamp::DayMonthYear;
+
+        let journal = Journal::new_at(dir.path());
+        let result = MigrateCmd::Filenames {
+            dry_run: DryRun::default(),
+        }
+        .execute(&config, &journal);
+
+        assert!(result.is_err());
+        dir.child("2022-03-10-standup.md").assert("first");
+        dir.child("10-03-2022-standup.md").assert("already here");
+        Ok(())
+    }
+}
@@ -1,12 +1,15 @@
 use anyhow::Result;
+use handlebars::Handlebars;
 use indoc::indoc;
 use serde::{Deserialize, Serialize};
 
 use crate::config::Section;
 use crate::storage::Journal;
+use crate::template::Heading;
 use crate::Clock;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct NotesConfig {
     #[serde(default = "default_note_template")]
     pub template: String,
@@ -32,7 +35,122 @@ fn default_note_template() -> String {
 
 #[async_trait::async_trait]
 impl Section for NotesConfig {
-    async fn render(&self, _: &Journal, _: &dyn Clock) -> Result<String> {
+    async fn render(&self, _: &Journal, _: &dyn Clock, _: &Handlebars<'static>) -> Result<String> {
         Ok(self.template.clone())
     }
 }
+
+/// Adds `note` as a bullet at the end of the section under `heading`, which is added if the entry doesn't have one
+pub(crate) fn append_note(markdown: &str, note: &str, heading: &Heading) -> String {
+    let bullet = format!("* {}", note);
+    let title = heading.to_string();
+    let mut lines: Vec<&str> = markdown.lines().collect();
+
+    let mut in_code = false;
+    let mut section = None;
+    let mut end = lines.len();
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        } else if in_code {
+            continue;
+        } else if section.is_none() {
+            if line.trim_end() == title {
+                section = Some(index);
+            }
+        } else if line
+            .parse::<Heading>()
+            .map_or(line.starts_with("# "), |other| {
+                other.level() <= heading.level()
+            })
+        {
+            end = index;
+            break;
+        }
+    }
+
+    let heading = match section {
+        Some(heading) => heading,
+        None => return format!("{}\n\n{}\n\n{}\n", markdown.trim_end(), title, bullet),
+    };
+
+    let mut added = Vec::new();
+    let after = match (heading + 1..end)
+        .rev()
+        .find(|index| !lines[*index].trim().is_empty())
+    {
+        Some(last) if lines[last].starts_with("* ") => last + 1,
+        Some(last) => {
+            added.push("");
+            last + 1
+        }
+        None => {
+            added.push("");
+            heading + 1
+        }
+    };
+    added.push(&bullet);
+    if lines.get(after).is_some_and(|line| !line.trim().is_empty()) {
+        added.push("");
+    }
+
+    lines.splice(after..after, added);
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn appends_notes_to_their_section() {
+        let entry = indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## Notes
+
+            > This is where your notes will go!
+
+            ## TODOs
+
+            * [ ] Call the bank
+            "#};
+
+        let notes = Heading::notes();
+        let once = append_note(entry, "09:12 Talked to Sam about the rollout", &notes);
+        let twice = append_note(&once, "11:30 Rollout is delayed", &notes);
+        assert_eq!(
+            twice,
+            indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## Notes
+
+            > This is where your notes will go!
+
+            * 09:12 Talked to Sam about the rollout
+            * 11:30 Rollout is delayed
+
+            ## TODOs
+
+            * [ ] Call the bank
+            "#}
+        );
+
+        assert_eq!(
+            append_note("# Planning on 2022-03-11\n", "09:12 Standup", &notes),
+            "# Planning on 2022-03-11\n\n## Notes\n\n* 09:12 Standup\n"
+        );
+
+        let renamed: Heading = "### Notizen".parse().unwrap();
+        assert_eq!(
+            append_note(
+                "# Planung\n\n### Notizen\n\n#### Details\n\nText\n\n## TODOs\n",
+                "09:12 Standup",
+                &renamed
+            ),
+            "# Planung\n\n### Notizen\n\n#### Details\n\nText\n\n* 09:12 Standup\n\n## TODOs\n"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A transformation applied to every new entry after it was rendered and before it is written
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+#[serde(untagged)]
+pub enum PostProcessor {
+    Builtin(Builtin),
+    /// Any command that reads the entry on stdin and prints the new one on stdout
+    Command {
+        command: String,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub enum Builtin {
+    /// `wrap:100` re-flows lines longer than the given width
+    Wrap(usize),
+    /// `smart-quotes` turns "straight" quotes into “curly” ones
+    SmartQuotes,
+    /// `toc` adds links to each section below the title
+    Toc,
+}
+
+impl TryFrom<String> for Builtin {
+    type Error = String;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        match raw.split_once(':') {
+            Some(("wrap", width)) => width
+                .parse()
+                .map(Builtin::Wrap)
+                .map_err(|_| format!("invalid width for wrap: {}", width)),
+            None if raw == "smart-quotes" => Ok(Builtin::SmartQuotes),
+            None if raw == "toc" => Ok(Builtin::Toc),
+            _ => Err(format!("unknown post-processor: {}", raw)),
+        }
+    }
+}
+
+impl From<Builtin> for String {
+    fn from(builtin: Builtin) -> Self {
+        match builtin {
+            Builtin::Wrap(width) => format!("wrap:{}", width),
+            Builtin::SmartQuotes => "smart-quotes".to_string(),
+            Builtin::Toc => "toc".to_string(),
+        }
+    }
+}
+
+/// Runs each of the `processors` over the entry, in order
+pub fn apply(processors: &[PostProcessor], entry: String) -> Result<String> {
+    let mut entry = entry;
+    for processor in processors {
+        tracing::info!("Applying post-processor {:?}", processor);
+        entry = match processor {
+            PostProcessor::Builtin(Builtin::Wrap(width)) => wrap(&entry, *width),
+            PostProcessor::Builtin(Builtin::SmartQuotes) => smart_quotes(&entry),
+            PostProcessor::Builtin(Builtin::Toc) => toc(&entry),
+            PostProcessor::Command { command } => pipe_through(command, &entry)?,
+        };
+    }
+    Ok(entry)
+}
+
+/// Applies `f` to every line outside of fenced code blocks
+pub(crate) fn outside_of_code(markdown: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut in_code_block = false;
+    let mut result = Vec::new();
+
+    for line in markdown.split('\n') {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            result.push(line.to_string());
+        } else if in_code_block {
+            result.push(line.to_string());
+        } else {
+            result.push(f(line));
+        }
+    }
+
+    result.join("\n")
+}
+
+fn wrap(markdown: &str, width: usize) -> String {
+    outside_of_code(markdown, |line| {
+        let trimmed = line.trim_start();
+        if line.chars().count() <= width || trimmed.starts_with('#') || trimmed.starts_with('|') {
+            return line.to_string();
+        }
+
+        // Continuation lines line up with the text of list items and stay inside quotes
+        let marker_end = line.len() - text_of(trimmed).len();
+        let (marker, text) = line.split_at(marker_end);
+        let indent = if trimmed.starts_with('>') {
+            marker.to_string()
+        } else {
+            " ".repeat(marker.chars().count())
+        };
+
+        let mut lines = vec![marker.to_string()];
+        for word in text.split_whitespace() {
+            let current = lines.last_mut().unwrap();
+            let is_empty = current.chars().count() == marker.chars().count();
+            if !is_empty && current.chars().count() + 1 + word.chars().count() > width {
+                lines.push(format!("{}{}", indent, word));
+            } else {
+                if !is_empty {
+                    current.push(' ');
+                }
+                current.push_str(word);
+            }
+        }
+        lines.join("\n")
+    })
+}
+
+/// The text of a line without its quote, bullet, number, or checkbox
+fn text_of(line: &str) -> &str {
+    let mut text = line;
+    loop {
+        let before = text;
+        for marker in ["> ", "* ", "- ", "+ ", "[ ] ", "[x] ", "[X] "] {
+            if let Some(rest) = text.strip_prefix(marker) {
+                text = rest;
+            }
+        }
+        if let Some((number, rest)) = text.split_once(". ") {
+            if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+                text = rest;
+            }
+        }
+        if text == before {
+            return text;
+        }
+    }
+}
+
+fn smart_quotes(markdown: &str) -> String {
+    outside_of_code(markdown, |line| {
+        let mut result = String::with_capacity(line.len());
+        let mut in_code_span = false;
+        let mut previous: Option<char> = None;
+
+        for c in line.chars() {
+            let opens = previous
+                .map(|p| p.is_whitespace() || "([{".contains(p))
+                .unwrap_or(true);
+            match c {
+                '`' => {
+                    in_code_span = !in_code_span;
+                    result.push(c);
+                }
+                '"' if !in_code_span => result.push(if opens { '“' } else { '”' }),
+                '\'' if !in_code_span => result.push(if opens { '‘' } else { '’' }),
+                _ => result.push(c),
+            }
+            previous = Some(c);
+        }
+
+        result
+    })
+}
+
+/// Adds links to each section below the title, replacing the ones that are already there
+fn toc(markdown: &str) -> String {
+    let (title, rest) = markdown.split_once('\n').unwrap_or((markdown, ""));
+    let rest = without_toc(rest).unwrap_or(rest);
+
+    let mut in_code_block = false;
+    let mut sections = Vec::new();
+    for line in rest.lines() {
+        if line.starts_with("```") {
+            in_code_block = !in_code_block;
+        } else if let Some(heading) = line.strip_prefix("## ").filter(|_| !in_code_block) {
+            let heading = heading.trim();
+            sections.push(format!("* [{}](#{})", heading, anchor(heading)));
+        }
+    }
+
+    if sections.is_empty() {
+        return format!("{}\n{}", title, rest);
+    }
+
+    format!("{}\n\n{}\n{}", title, sections.join("\n"), rest)
+}
+
+/// Brings the table of contents of an entry up to date, e.g. after sections were added during the day.
+/// Entries without one are left alone.
+pub fn update_toc(markdown: &str) -> String {
+    let rest = markdown.split_once('\n').map_or("", |(_, rest)| rest);
+    match without_toc(rest) {
+        Some(_) => toc(markdown),
+        None => markdown.to_string(),
+    }
+}
+
+/// What follows the table of contents that `toc` put below the title, if there is one
+fn without_toc(below_title: &str) -> Option<&str> {
+    let mut remaining = below_title.strip_prefix('\n')?;
+    let mut found = false;
+    while let Some((line, next)) = remaining.split_once('\n') {
+        if !(line.starts_with("* [") && line.contains("](#") && line.ends_with(')')) {
+            break;
+        }
+        remaining = next;
+        found = true;
+    }
+    found.then_some(remaining)
+}
+
+/// The anchor GitHub and most editors generate for a heading
+fn anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-' || *c == '_')
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+fn pipe_through(command: &str, entry: &str) -> Result<String> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Could not run post-processor `{}`", command))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(entry.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("Post-processor `{}` failed with {}", command, output.status);
+    }
+
+    String::from_utf8(output.stdout)
+        .with_context(|| format!("Post-processor `{}` did not print valid UTF-8", command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_builtins_and_commands() -> Result<()> {
+        let processors: Vec<PostProcessor> = serde_yaml::from_str(indoc! {r#"
+            - wrap:100
+            - smart-quotes
+            - toc
+            - command: "prettier --parser markdown"
+            "#})?;
+
+        assert_eq!(
+            processors,
+            vec![
+                PostProcessor::Builtin(Builtin::Wrap(100)),
+                PostProcessor::Builtin(Builtin::SmartQuotes),
+                PostProcessor::Builtin(Builtin::Toc),
+                PostProcessor::Command {
+                    command: "prettier --parser markdown".to_string()
+                },
+            ]
+        );
+
+        assert!(serde_yaml::from_str::<Vec<PostProcessor>>("- wrap:wide").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn wraps_long_lines_but_not_headings_or_code() {
+        let entry = indoc! {r#"
+            # A title that is much longer than twenty characters on 2022-03-11
+
+            * [ ] Send the marketing figures to Jessica
+            > Quotes stay quotes when they are wrapped
+
+            ```
+            let code = "is left exactly as it is";
+            ```"#};
+
+        assert_eq!(
+            wrap(entry, 20),
+            indoc! {r#"
+            # A title that is much longer than twenty characters on 2022-03-11
+
+            * [ ] Send the
+                  marketing
+                  figures to
+                  Jessica
+            > Quotes stay quotes
+            > when they are
+            > wrapped
+
+            ```
+            let code = "is left exactly as it is";
+            ```"#}
+        );
+    }
+
+    #[test]
+    fn curls_quotes_outside_of_code() {
+        assert_eq!(
+            smart_quotes(r#"She said "it's fine" but `"code"` isn't"#),
+            "She said “it’s fine” but `\"code\"` isn’t"
+        );
+    }
+
+    #[test]
+    fn links_to_every_section_below_the_title() {
+        let entry = indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## Notes
+
+            ## Pull Requests:
+            "#};
+
+        assert_eq!(
+            toc(entry),
+            indoc! {r#"
+            # Planning on 2022-03-11
+
+            * [Notes](#notes)
+            * [Pull Requests:](#pull-requests)
+
+            ## Notes
+
+            ## Pull Requests:
+            "#}
+        );
+    }
+
+    #[test]
+    fn updates_an_existing_table_of_contents() {
+        let entry = indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## Notes
+            "#};
+        let with_toc = toc(entry);
+        assert_eq!(toc(&with_toc), with_toc);
+
+        let edited = format!("{}\n## Highlights\n", with_toc);
+        assert_eq!(
+            update_toc(&edited),
+            indoc! {r#"
+            # Planning on 2022-03-11
+
+            * [Notes](#notes)
+            * [Highlights](#highlights)
+
+            ## Notes
+
+            ## Highlights
+            "#}
+        );
+
+        assert_eq!(update_toc(entry), entry);
+    }
+
+    #[test]
+    fn pipes_the_entry_through_commands() -> Result<()> {
+        let processors = vec![PostProcessor::Command {
+            command: "tr a-z A-Z".to_string(),
+        }];
+
+        assert_eq!(apply(&processors, "# title".to_string())?, "# TITLE");
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use crossterm::style::{ContentStyle, Stylize};
+use pulldown_cmark::{Event, HeadingLevel, LinkType, Options, Parser, Tag};
+
+/// Renders `markdown` for reading in the terminal, with bold headings, colors, and checkboxes
+/// instead of the markdown syntax. Only uses ASCII characters for bullets and checkboxes if asked to.
+pub fn render(markdown: &str, ascii: bool) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    let mut out = Output {
+        text: String::new(),
+        line_start: true,
+        quotes: 0,
+        indent: 0,
+        ascii,
+    };
+    let mut styles = vec![ContentStyle::new()];
+    // The next number of each list we're in, or `None` for bullet points
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    // Written before the first text of an item, unless it turns out to be a TODO
+    let mut marker: Option<String> = None;
+    let mut links: Vec<(LinkType, String)> = Vec::new();
+
+    for event in Parser::new_ext(markdown, options) {
+        let style = *styles.last().unwrap();
+        match event {
+            Event::Start(tag) => {
+                let styled = match tag {
+                    Tag::Heading(HeadingLevel::H1, _, _) => {
+                        out.start_block();
+                        style.bold().underlined().magenta()
+                    }
+                    Tag::Heading(_, _, _) => {
+                        out.start_block();
+                        style.bold().cyan()
+                    }
+                    Tag::Paragraph if marker.is_none() => {
+                        out.start_block();
+                        style
+                    }
+                    Tag::BlockQuote => {
+                        out.start_block();
+                        out.quotes += 1;
+                        style.italic()
+                    }
+                    Tag::CodeBlock(_) => {
+                        out.start_block();
+                        style.yellow()
+                    }
+                    Tag::List(start) => {
+                        if lists.is_empty() {
+                            out.start_block();
+                        }
+                        lists.push(start);
+                        style
+                    }
+                    Tag::Item => {
+                        out.end_line();
+                        out.indent = lists.len().saturating_sub(1) * 2;
+                        let bullet = match lists.last_mut() {
+                            Some(Some(number)) => {
+                                *number += 1;
+                                format!("{}.", *number - 1)
+                            }
+                            _ if ascii => "*".to_string(),
+                            _ => "•".to_string(),
+                        };
+                        marker = Some(format!("{} ", bullet));
+                        style
+                    }
+                    Tag::Emphasis => style.italic(),
+                    Tag::Strong => style.bold(),
+                    Tag::Strikethrough => style.crossed_out(),
+                    Tag::Link(kind, url, _) => {
+                        links.push((kind, url.to_string()));
+                        style.underlined().blue()
+                    }
+                    _ => style,
+                };
+                styles.push(styled);
+            }
+            Event::End(tag) => {
+                styles.pop();
+                match tag {
+                    Tag::Heading(_, _, _) | Tag::Paragraph | Tag::CodeBlock(_) => out.end_line(),
+                    Tag::BlockQuote => {
+                        out.end_line();
+                        out.quotes -= 1;
+                    }
+                    Tag::List(_) => {
+                        out.end_line();
+                        lists.pop();
+                        out.indent = lists.len().saturating_sub(1) * 2;
+                    }
+                    Tag::Item => {
+                        if let Some(marker) = marker.take() {
+                            out.write(&marker, style);
+                        }
+                        out.end_line();
+                    }
+                    Tag::Link(_, _, _) => match links.pop() {
+                        Some((LinkType::Autolink | LinkType::Email, _)) | None => {}
+                        Some((_, url)) => out.write(&format!(" ({})", url), style.dim()),
+                    },
+                    _ => {}
+                }
+            }
+            Event::TaskListMarker(done) => {
+                let checkbox = match (done, ascii) {
+                    (true, true) => "[x] ",
+                    (false, true) => "[ ] ",
+                    (true, false) => "☑ ",
+                    (false, false) => "☐ ",
+                };
+                marker = Some(checkbox.to_string());
+                // What's already done fades into the background
+                if done {
+                    *styles.last_mut().unwrap() = style.dim();
+                }
+            }
+            Event::Text(text) => {
+                if let Some(marker) = marker.take() {
+                    out.write(&marker, style);
+                }
+                out.write(&text, style);
+            }
+            Event::Code(code) => {
+                if let Some(marker) = marker.take() {
+                    out.write(&marker, style);
+                }
+                out.write(&code, style.yellow());
+            }
+            Event::Html(html) => out.write(&html, style.dim()),
+            Event::SoftBreak | Event::HardBreak => out.write("\n", style),
+            Event::Rule => {
+                out.start_block();
+                let rule = if ascii { "-" } else { "─" };
+                out.write(&rule.repeat(40), style.dim());
+                out.end_line();
+            }
+            Event::FootnoteReference(name) => out.write(&format!("[{}]", name), style),
+        }
+    }
+
+    let mut text = out.text.trim_end().to_string();
+    text.push('\n');
+    text
+}
+
+/// The rendered text so far, along with what goes at the start of each line
+struct Output {
+    text: String,
+    line_start: bool,
+    quotes: usize,
+    indent: usize,
+    ascii: bool,
+}
+
+impl Output {
+    /// Writes `text` in `style`, starting each new line with the quote markers and indentation
+    fn write(&mut self, text: &str, style: ContentStyle) {
+        for line in text.split_inclusive('\n') {
+            if self.line_start && self.quotes + self.indent > 0 {
+                let quote = if self.ascii { "> " } else { "│ " };
+                let prefix = format!("{}{}", quote.repeat(self.quotes), " ".repeat(self.indent));
+                self.text
+                    .push_str(&format!("{}", ContentStyle::new().dim().apply(prefix)));
+            }
+            let content = line.trim_end_matches('\n');
+            if !content.is_empty() {
+                self.text.push_str(&style.apply(content).to_string());
+            }
+            self.line_start = line.ends_with('\n');
+            if self.line_start {
+                self.text.push('\n');
+            }
+        }
+    }
+
+    fn end_line(&mut self) {
+        if !self.line_start {
+            self.text.push('\n');
+            self.line_start = true;
+        }
+    }
+
+    /// Separates a new heading, paragraph, or list from whatever came before it with an empty line
+    fn start_block(&mut self) {
+        self.end_line();
+        if !self.text.is_empty() && !self.text.ends_with("\n\n") {
+            self.text.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use regex::Regex;
+
+    fn without_colors(text: &str) -> String {
+        Regex::new("\x1b\\[[0-9;]*m")
+            .unwrap()
+            .replace_all(text, "")
+            .to_string()
+    }
+
+    #[test]
+    fn renders_markdown_without_its_syntax() {
+        let markdown = indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## TODOs
+
+            * [ ] Call **the bank**
+            * [x] Book the flights
+              * Window seat
+
+            ## Notes
+
+            > This is where your notes will go!
+
+            Read [the docs](https://example.com) and run `journal new`.
+            "#};
+
+        let rendered = render(markdown, false);
+        assert!(rendered.contains('\x1b'));
+        assert_eq!(
+            without_colors(&rendered),
+            indoc! {r#"
+            Planning on 2022-03-11
+
+            TODOs
+
+            ☐ Call the bank
+            ☑ Book the flights
+              • Window seat
+
+            Notes
+
+            │ This is where your notes will go!
+
+            Read the docs (https://example.com) and run journal new.
+            "#}
+        );
+
+        let ascii = without_colors(&render("* [ ] Call the bank\n* Eat\n", true));
+        assert_eq!(ascii, "[ ] Call the bank\n* Eat\n");
+    }
+}
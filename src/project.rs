@@ -0,0 +1,166 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use clap::StructOpt;
+use regex::Regex;
+
+use crate::storage::{link, write_atomically, Entry, Journal};
+
+/// Project pages live in their own directory so they aren't mistaken for entries
+pub(crate) const PROJECTS_DIR: &str = "projects";
+
+#[derive(Debug, StructOpt)]
+pub struct ProjectCmd {
+    /// The tag of the project, with or without the leading `#`
+    tag: String,
+}
+
+impl ProjectCmd {
+    pub(crate) fn execute(&self, journal: &Journal) -> Result<()> {
+        let tag = self.tag.trim_start_matches('#');
+        if tag.is_empty() {
+            bail!("A project needs a tag, e.g. `journal project apollo`");
+        }
+
+        let mut mentions = Vec::new();
+        for path in journal.entries()? {
+            let entry = journal.read_entry(path)?;
+            let excerpts = excerpts(&entry.markdown, tag);
+            if !excerpts.is_empty() {
+                mentions.push((entry, excerpts));
+            }
+        }
+
+        if mentions.is_empty() {
+            bail!("No entries are tagged with #{}", tag);
+        }
+
+        let dir = journal.child_file(PROJECTS_DIR);
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.md", tag));
+        write_atomically(&path, project_page(tag, &dir, &mentions).as_bytes())?;
+
+        println!(
+            "Collected {} entries into {}",
+            mentions.len(),
+            path.to_string_lossy()
+        );
+        Ok(())
+    }
+}
+
+/// The lines of `markdown` that mention `#tag`, skipping headings and code blocks
+fn excerpts(markdown: &str, tag: &str) -> Vec<String> {
+    let mention = Regex::new(&format!(r"(?i)(^|\s)#{}\b", regex::escape(tag))).unwrap();
+
+    let mut in_code_block = false;
+    let mut excerpts = Vec::new();
+    for line in markdown.lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || line.starts_with("# ") || line.starts_with("##") {
+            continue;
+        }
+
+        if mention.is_match(line) {
+            excerpts.push(line.to_string());
+        }
+    }
+    excerpts
+}
+
+/// A chronological log of the project, kept in `dir` and linking back to each entry
+fn project_page(tag: &str, dir: &Path, mentions: &[(Entry, Vec<String>)]) -> String {
+    let mut page = format!("# Project #{}\n", tag);
+
+    for (entry, excerpts) in mentions {
+        page.push_str(&format!(
+            "\n## {}: [{}]({})\n\n",
+            entry.describe(),
+            entry.title(),
+            link(dir, &entry.path)
+        ));
+        for excerpt in excerpts {
+            page.push_str(excerpt);
+            page.push('\n');
+        }
+    }
+
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn only_whole_tags_outside_of_code_are_excerpts() {
+        let markdown = indoc! {r##"
+            # Planning on 2022-03-11
+
+            ## Notes
+
+            * Kicked off the migration #apollo
+            * Nothing to do with #apollonia
+            * #Apollo needs a new database
+
+            ```sh
+            grep "#apollo" notes.md
+            ```
+            "##};
+
+        assert_eq!(
+            excerpts(markdown, "apollo"),
+            vec![
+                "* Kicked off the migration #apollo",
+                "* #Apollo needs a new database"
+            ]
+        );
+    }
+
+    #[test]
+    fn collects_tagged_entries_into_a_project_page() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("2022-03-09-planning.md").write_str(indoc! {r#"
+            # Planning on 2022-03-09
+
+            * Kicked off the migration #apollo
+            "#})?;
+        dir.child("2022-03-10-retro.md").write_str(indoc! {r#"
+            # Retro on 2022-03-10
+
+            * Nothing about the project
+            "#})?;
+        dir.child("2022/03/11.md").write_str(indoc! {r#"
+            # Standup on 2022-03-11
+
+            * Staging is ready #apollo
+            "#})?;
+
+        let journal = Journal::new_at(dir.path());
+        ProjectCmd {
+            tag: "#apollo".to_string(),
+        }
+        .execute(&journal)?;
+
+        dir.child("projects/apollo.md").assert(indoc! {r#"
+            # Project #apollo
+
+            ## 2022-03-09: [Planning](../2022-03-09-planning.md)
+
+            * Kicked off the migration #apollo
+
+            ## 2022-03-11: [Standup](../2022/03/11.md)
+
+            * Staging is ready #apollo
+            "#});
+
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Context, Result};
+use regex::{Captures, Regex};
+use time::Date;
+
+use crate::ics::Schedule;
+use crate::locale::Locale;
+use crate::post_process::outside_of_code;
+use crate::reminders::{RepeatingDate, SpecificDate};
+use crate::storage::{write_atomically, Journal, YEAR_MONTH_DAY};
+use crate::{Config, Reminders};
+
+const SECTION: &str = "## Remind me";
+
+/// A reminder that was written down in an entry
+#[derive(Debug, PartialEq)]
+struct Found {
+    schedule: Schedule,
+    text: String,
+}
+
+/// Turns the reminders written down in the latest entry into stored ones, marking them in the entry
+/// so they aren't added again. They can be items in a `## Remind me` section like `* 12.Feb: text`
+/// or anywhere in the entry like `@remind(every Monday): text`.
+pub(crate) fn take_from_latest(config: &Config, journal: &Journal, today: Date) -> Result<()> {
+    let entry = match journal.latest_entry()? {
+        Some(entry) => entry,
+        None => return Ok(()),
+    };
+
+    let (updated, found) = extract(&entry.markdown, today, config.locale);
+    if found.is_empty() {
+        return Ok(());
+    }
+
+    let location = config.reminders_file();
+    let mut reminders = Reminders::load(&location)?;
+    for Found { schedule, text } in found {
+        match schedule {
+            Schedule::Once(date) => {
+                reminders.on_date(date, text.as_str());
+                println!(
+                    "Added a reminder for '{}' on '{}'",
+                    text,
+                    date.format(YEAR_MONTH_DAY)?
+                );
+            }
+            Schedule::Every(interval, _) => {
+                reminders.every_since(today, &interval, &text);
+                println!("Added a reminder for '{}' every '{}'", text, interval);
+            }
+        }
+    }
+
+    // Only mark them once they are saved, so nothing that was written down gets lost
+    reminders
+        .save(&location)
+        .context("Failed to save reminders")?;
+    write_atomically(&entry.path, updated.as_bytes())
+        .with_context(|| format!("Could not mark the reminders in {:?}", entry.path))
+}
+
+/// The reminders in `markdown`, along with the markdown in which each of them is marked as taken care of
+fn extract(markdown: &str, today: Date, locale: Locale) -> (String, Vec<Found>) {
+    let inline = Regex::new(r"@remind\(([^)]+)\):\s*(.+)$").unwrap();
+    let item = Regex::new(r"^(\s*[*+-]\s+)([^:\[]+):\s*(.+)$").unwrap();
+
+    let mut found = Vec::new();
+    let mut in_section = false;
+    let updated = outside_of_code(markdown, |line| {
+        if line.starts_with('#') {
+            in_section = line.trim_end() == SECTION;
+            return line.to_string();
+        }
+
+        let mut take = |when: &str, text: &str| match schedule(when, today, locale) {
+            Ok(schedule) => {
+                let described = describe(&schedule);
+                found.push(Found {
+                    schedule,
+                    text: text.trim().to_string(),
+                });
+                Some(described)
+            }
+            Err(e) => {
+                eprintln!("Warning: could not add the reminder '{}': {}", text, e);
+                None
+            }
+        };
+
+        if in_section {
+            if let Some(captures) = item.captures(line) {
+                return match take(&captures[2], &captures[3]) {
+                    Some(when) => {
+                        format!("{}[>] {} (reminder {})", &captures[1], &captures[3], when)
+                    }
+                    None => line.to_string(),
+                };
+            }
+        }
+
+        inline
+            .replace(line, |captures: &Captures| {
+                match take(&captures[1], &captures[2]) {
+                    Some(when) => format!("@reminded({}): {}", when, &captures[2]),
+                    None => captures[0].to_string(),
+                }
+            })
+            .to_string()
+    });
+
+    (updated, found)
+}
+
+/// Reads `when` like the `--on` and `--every` flags of `reminders new`, e.g. `12.Feb` or `every Monday`
+fn schedule(when: &str, today: Date, locale: Locale) -> Result<Schedule> {
+    let when = when.trim();
+    match when.strip_prefix("every ") {
+        Some(interval) => RepeatingDate::parse(interval.trim(), locale)
+            .map(|interval| Schedule::Every(interval, None))
+            .map_err(|e| anyhow!(e)),
+        None => SpecificDate::parse(when, locale)
+            .map(|date| Schedule::Once(date.next_date(today)))
+            .map_err(|e| anyhow!(e)),
+    }
+}
+
+fn describe(schedule: &Schedule) -> String {
+    match schedule {
+        Schedule::Once(date) => format!("on {}", date.format(YEAR_MONTH_DAY).unwrap()),
+        Schedule::Every(interval, _) => format!("every {}", interval),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use time::macros::date;
+    use time::Weekday;
+
+    #[test]
+    fn finds_reminders_in_their_section_and_inline() {
+        let entry = indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## Notes
+
+            Talked to Anna @remind(every Monday): ask Anna about the offsite
+
+            ```
+            @remind(12.Feb): not in code
+            ```
+
+            ## Remind me
+
+            * 12.Apr: Renew the passport
+            * [>] Already taken care of (reminder on 2022-02-01)
+            * whenever: Something vague
+            "#};
+
+        let (updated, found) = extract(entry, date!(2022 - 03 - 11), Locale::En);
+
+        assert_eq!(
+            found,
+            vec![
+                Found {
+                    schedule: Schedule::Every(RepeatingDate::Weekday(Weekday::Monday), None),
+                    text: "ask Anna about the offsite".to_string(),
+                },
+                Found {
+                    schedule: Schedule::Once(date!(2022 - 04 - 12)),
+                    text: "Renew the passport".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            updated,
+            indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## Notes
+
+            Talked to Anna @reminded(every Monday): ask Anna about the offsite
+
+            ```
+            @remind(12.Feb): not in code
+            ```
+
+            ## Remind me
+
+            * [>] Renew the passport (reminder on 2022-04-12)
+            * [>] Already taken care of (reminder on 2022-02-01)
+            * whenever: Something vague
+            "#}
+        );
+
+        let (again, found) = extract(&updated, date!(2022 - 03 - 11), Locale::En);
+        assert!(found.is_empty());
+        assert_eq!(again, updated);
+    }
+}
@@ -1,23 +1,28 @@
 use std::fmt::Display;
+use std::io::{Read, Write};
 use std::num::ParseIntError;
-use std::ops::Mul;
-use std::path::Path;
+use std::ops::{Mul, Range};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::{anyhow, bail, Context, Result};
 use clap::StructOpt;
 use serde::{Deserialize, Serialize};
-use tabled::object::Segment;
-use time::format_description::FormatItem;
-use time::{format_description, Date, Month, OffsetDateTime, Weekday};
+use time::{format_description, Date, Duration, Month, OffsetDateTime, UtcOffset, Weekday};
 
 use handlebars::Handlebars;
-use tabled::{Alignment, Modify, Style, Table, Tabled};
+use tabled::{Table, Tabled};
 
+use crate::accessibility;
 use crate::config::Section;
-use crate::{storage::Journal, Config};
-
-const YEAR_MONTH_DAY: &[FormatItem] = time::macros::format_description!("[year]-[month]-[day]");
+use crate::daemon;
+use crate::edit;
+use crate::git;
+use crate::ics::{self, Schedule};
+use crate::locale::Locale;
+use crate::manage;
+use crate::storage::{write_atomically, Journal, YEAR_MONTH_DAY};
+use crate::Config;
 
 trait WeekdayExt {
     fn next(&self, weekday: Weekday) -> Date;
@@ -39,14 +44,40 @@ impl WeekdayExt for Date {
 
 pub trait Clock: Sync {
     fn today(&self) -> Date;
+
+    /// The offset of the local time zone, if it is known
+    fn local_offset(&self) -> Option<UtcOffset> {
+        None
+    }
+}
+
+pub struct WallClock {
+    local_offset: Option<UtcOffset>,
+}
+
+impl WallClock {
+    /// Determines the local time zone right away, as that fails once the process has more than one thread
+    pub fn new() -> WallClock {
+        WallClock {
+            local_offset: UtcOffset::current_local_offset().ok(),
+        }
+    }
 }
 
-pub struct WallClock;
+impl Default for WallClock {
+    fn default() -> Self {
+        WallClock::new()
+    }
+}
 
 impl Clock for WallClock {
     fn today(&self) -> Date {
         OffsetDateTime::now_utc().date()
     }
+
+    fn local_offset(&self) -> Option<UtcOffset> {
+        self.local_offset
+    }
 }
 
 const REMIDNERS: &str = r#"
@@ -57,47 +88,109 @@ const REMIDNERS: &str = r#"
 
 "#;
 
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct ReminderConfig {
     #[serde(default = "default_reminders_template")]
     pub template: String,
+    /// Add one-off reminders to the TODOs instead, so they are carried over until they are done
+    #[serde(default)]
+    pub one_offs_as_todos: bool,
+    /// Where the reminders are kept, relative to the journal unless it is absolute like in a synced folder
+    #[serde(default = "default_reminders_file")]
+    pub file: PathBuf,
+    /// More reminders files to show in entries but never change, like a team's rituals.
+    /// Either paths like `file` or `http(s)://` URLs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared: Vec<String>,
 }
 
 fn default_reminders_template() -> String {
     REMIDNERS.to_string()
 }
 
+fn default_reminders_file() -> PathBuf {
+    PathBuf::from("reminders.json")
+}
+
 impl Default for ReminderConfig {
     fn default() -> Self {
         Self {
             template: default_reminders_template(),
+            one_offs_as_todos: false,
+            file: default_reminders_file(),
+            shared: Vec::new(),
         }
     }
 }
 
+impl ReminderConfig {
+    /// Our own reminders followed by the shared ones, to see which are due.
+    /// Saving them would copy the shared ones over, so these are only for showing them.
+    pub(crate) async fn load_with_shared(&self, journal: &Journal) -> Result<Reminders> {
+        let location = journal.child_file(&self.file);
+        let mut reminders = Reminders::load(&location)?;
+        for source in &self.shared {
+            // Someone else's file being unavailable shouldn't keep anyone from seeing their own reminders
+            match load_shared(journal, source).await {
+                Ok(shared) => reminders.extend(shared),
+                Err(e) => {
+                    tracing::warn!("Could not load shared reminders from {}: {:#}", source, e);
+                    eprintln!(
+                        "Warning: skipped the shared reminders from {}: {:#}",
+                        source, e
+                    );
+                }
+            }
+        }
+        Ok(reminders)
+    }
+}
+
 #[async_trait::async_trait]
 impl Section for ReminderConfig {
-    async fn render(&self, journal: &Journal, clock: &dyn Clock) -> Result<String> {
-        let location = journal.child_file("reminders.json");
-        let reminders = Reminders::load(&location)?;
+    async fn render(
+        &self,
+        journal: &Journal,
+        clock: &dyn Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String> {
+        let reminders = self.load_with_shared(journal).await?;
+
+        // One-off reminders show up in the TODOs instead
+        let due = reminders.due_refs(clock, |reminder| {
+            !self.one_offs_as_todos || reminder.interval().is_some()
+        });
 
-        let todays_reminders = reminders.for_today(clock);
+        #[derive(Serialize)]
+        struct Due {
+            text: String,
+            date: String,
+            at: Option<String>,
+        }
 
         #[derive(Serialize)]
         struct C {
             reminders: Vec<String>,
+            due: Vec<Due>,
         }
 
-        let mut tt = Handlebars::new();
-        tt.register_template_string("reminders", self.template.to_string())?;
-        tt.register_escape_fn(handlebars::no_escape);
-        tt.render(
-            "reminders",
-            &C {
-                reminders: todays_reminders,
-            },
-        )
-        .map_err(|e| e.into())
+        let date = clock.today().format(YEAR_MONTH_DAY)?;
+        let context = C {
+            reminders: due.iter().map(|r| r.stored.describe()).collect(),
+            due: due
+                .iter()
+                .map(|r| Due {
+                    text: r.stored.describe(),
+                    date: date.clone(),
+                    at: r.at().map(|at| at.to_string()),
+                })
+                .collect(),
+        };
+
+        let mut tt = handlebars.clone();
+        tt.register_template_string("reminders", &self.template)?;
+        tt.render("reminders", &context).map_err(|e| e.into())
     }
 }
 
@@ -106,58 +199,461 @@ impl Section for ReminderConfig {
 pub enum ReminderCmd {
     /// Add a new reminder, either on a specific date or recurring.
     New {
+        /// A weekday like `Monday` or a date like `15.Jan`, `15.01.2023`
         #[clap(long = "on", group = "date_selection")]
-        on_date: Option<SpecificDate>,
+        on_date: Option<String>,
 
+        /// Like `Monday`, `Mon,Wed`, `3.days`, `12.Apr`, `year`, or `1st.Monday`
         #[clap(long = "every", group = "date_selection")]
-        every: Option<RepeatingDate>,
+        every: Option<String>,
+
+        /// Either `high`, `normal`, or `low`. Higher priority reminders are listed first.
+        #[clap(long, default_value = "normal")]
+        priority: Priority,
+
+        /// The time of day, like `14:30`. Reminders with a time are listed first, in order.
+        #[clap(long)]
+        at: Option<TimeOfDay>,
+
+        /// Move occurrences of a recurring reminder that land on a weekend to the Monday after
+        #[clap(long, conflicts_with = "on-date")]
+        weekdays_only: bool,
 
         #[clap(takes_value(true))]
         reminder: String,
     },
-    /// List all existing reminders
-    List,
-    /// Delete a reminder
+    /// List all existing reminders, or only the ones matching the filters
+    List {
+        /// Only show reminders that repeat
+        #[clap(long, conflicts_with = "one-off")]
+        recurring: bool,
+        /// Only show reminders that fire once
+        #[clap(long)]
+        one_off: bool,
+        /// Only show reminders that fire next before this day, e.g. `--before 1.Jun`
+        #[clap(long)]
+        before: Option<String>,
+        /// Only show reminders loosely matching this text, like `delete --match`
+        #[clap(long, parse(try_from_str = search_text))]
+        matching: Option<String>,
+        /// Either `table` or `json`
+        #[clap(long, default_value = "table")]
+        format: ListFormat,
+    },
+    /// Browse, delete, edit, and add reminders in an interactive list
+    Manage,
+    /// Show a desktop notification for each of today's reminders, e.g. from cron or launchd
+    Notify,
+    /// Show every day a reminder fires on over the next few days, e.g. to check recurring ones
+    Upcoming {
+        /// How many days to look ahead, including today
+        #[clap(long, default_value = "14")]
+        days: u32,
+    },
+    /// Delete one or more reminders, either by number or by their text
     Delete {
-        /// The number to delete
+        /// The numbers to delete, e.g. `delete 2 5 7`
+        #[clap(required_unless_present = "matching")]
+        nrs: Vec<u32>,
+        /// Delete all reminders loosely matching this text, e.g. `--match dentst` finds "Call the dentist".
+        /// Each word has to appear in the reminder, but letters may be left out.
+        #[clap(long, alias = "match", conflicts_with = "nrs", parse(try_from_str = search_text))]
+        matching: Option<String>,
+        /// Show what would be deleted and ask first, even for a single reminder
+        #[clap(long, short, conflicts_with = "yes")]
+        interactive: bool,
+        /// Don't ask before deleting several reminders
+        #[clap(long, short)]
+        yes: bool,
+    },
+    /// Mark today's occurrence of a reminder as done, so it doesn't show up again today
+    Done {
+        /// The number of the reminder
+        nr: u32,
+    },
+    /// Fix the text of a reminder, either directly or in your $EDITOR
+    Edit {
+        /// The number of the reminder
+        nr: u32,
+        /// The new text. Opens your $EDITOR with the current text if left out
+        reminder: Option<String>,
+    },
+    /// Push a reminder back, e.g. `snooze 2 --by 3.days`
+    Snooze {
+        /// The number of the reminder
+        nr: u32,
+        /// How long to snooze for, in days or weeks
+        #[clap(long)]
+        by: Delay,
+    },
+    /// Move a reminder to a different date, e.g. `move 2 --to 14.Feb`
+    Move {
+        /// The number of the reminder
         nr: u32,
+        #[clap(long)]
+        to: String,
+    },
+    /// Import the events of an .ics calendar as reminders
+    Import {
+        /// The .ics file or the URL of the calendar to import
+        #[clap(long)]
+        ics: String,
     },
+    /// Import the events of an .ics file as reminders, like `import --ics <file>`
+    ImportIcs {
+        /// The .ics file to import
+        file: String,
+    },
+    /// Write all reminders as a calendar other tools can subscribe to, or as CSV or JSON
+    Export {
+        /// Either `ics`, `csv`, or `json`
+        #[clap(long, default_value = "ics")]
+        format: ExportFormat,
+        /// Where to write the reminders to instead of printing them
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug)]
+pub enum ExportFormat {
+    Ics,
+    Csv,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ics" => Ok(ExportFormat::Ics),
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ListFormat {
+    Table,
+    Json,
+}
+
+impl FromStr for ListFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(ListFormat::Table),
+            "json" => Ok(ListFormat::Json),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+/// Text to look for in the reminders, which can't be blank as that would match every one of them
+fn search_text(raw: &str) -> Result<String, String> {
+    if raw.trim().is_empty() {
+        return Err("needs some text to look for in the reminders".to_string());
+    }
+    Ok(raw.to_string())
+}
+
+/// Which reminders `list` shows, everything if left empty
+#[derive(Debug, Default)]
+struct ListFilter {
+    recurring: bool,
+    one_off: bool,
+    before: Option<Date>,
+    matching: Option<String>,
+}
+
+impl ListFilter {
+    fn keeps(&self, reminder: &Reminder) -> bool {
+        (!self.recurring || reminder.recurring)
+            && (!self.one_off || !reminder.recurring)
+            && self
+                .before
+                .is_none_or(|before| reminder.next_date.is_some_and(|next| next < before))
+            && self
+                .matching
+                .as_ref()
+                .is_none_or(|pattern| fuzzy_matches(pattern, &reminder.reminder))
+    }
 }
 
 impl ReminderCmd {
-    pub(crate) fn execute(self, config: &Config, clock: &impl Clock) -> Result<()> {
-        let location = config.dir.join("reminders.json");
+    pub(crate) async fn execute(
+        self,
+        config: &Config,
+        journal: &Journal,
+        clock: &impl Clock,
+    ) -> Result<()> {
+        let location = config.reminders_file();
         let mut reminders_storage = Reminders::load(&location)?;
+        // Only what changed the reminders is saved, so looking at them keeps the backup as it is
+        let before = serde_json::to_string(&reminders_storage)?;
 
         match self {
-            ReminderCmd::Delete { nr } => {
-                tracing::info!("intention to delete reminder");
+            ReminderCmd::Delete {
+                nrs,
+                matching,
+                interactive,
+                yes,
+            } => {
+                tracing::info!("intention to delete reminders");
+
+                let ask = interactive || (!yes && (nrs.len() > 1 || matching.is_some()));
+                let nrs = match matching {
+                    Some(pattern) => {
+                        let nrs = reminders_storage.matching(&pattern);
+                        if nrs.is_empty() {
+                            bail!("No reminders contain '{}'", pattern);
+                        }
+                        nrs
+                    }
+                    None => nrs,
+                };
+
+                if ask {
+                    // Make sure they all exist before showing any of them
+                    for nr in &nrs {
+                        reminders_storage.get(*nr)?;
+                    }
+                    for reminder in reminders_storage.all(clock) {
+                        if nrs.contains(&(reminder.nr as u32)) {
+                            println!("{}: {} — {}", reminder.nr, reminder.date, reminder.reminder);
+                        }
+                    }
+                    let question = match nrs.len() {
+                        1 => "Delete this reminder?".to_string(),
+                        n => format!("Delete {} reminders?", n),
+                    };
+                    if !edit::confirm(&question)? {
+                        println!("Nothing was deleted");
+                        return Ok(());
+                    }
+                }
+
+                reminders_storage.delete_all(&nrs)?;
+
+                let nrs = nrs.iter().map(u32::to_string).collect::<Vec<_>>();
+                println!("Deleted {}", nrs.join(", "));
+            }
+            ReminderCmd::Done { nr } => {
+                tracing::info!("intention to complete reminder");
+
+                match reminders_storage.done(nr, clock.today())? {
+                    Done::Completed => println!("Done with {} for today", nr),
+                    Done::Deleted => println!("Done with {}, deleted it as it won't come back", nr),
+                }
+            }
+            ReminderCmd::Edit { nr, reminder } => {
+                tracing::info!("intention to edit reminder");
+
+                let reminder = match reminder {
+                    Some(reminder) => reminder,
+                    None => {
+                        let current = reminders_storage.get(nr)?.text();
+                        edit::in_editor("reminder.txt", current)?.trim().to_string()
+                    }
+                };
+
+                if reminder.is_empty() {
+                    bail!("A reminder needs some text, use `delete` to get rid of it");
+                }
+
+                reminders_storage.edit(nr, &reminder)?;
+
+                println!("Changed {} to '{}'", nr, reminder);
+            }
+            ReminderCmd::Snooze { nr, by } => {
+                tracing::info!("intention to snooze reminder");
+
+                let today = clock.today();
+                let date = reminders_storage.reschedule(nr, |date| date.max(today) + by.0)?;
+
+                println!("Snoozed {} until {}", nr, date.format(YEAR_MONTH_DAY)?);
+            }
+            ReminderCmd::Move { nr, to } => {
+                tracing::info!("intention to move reminder");
 
-                reminders_storage.delete(nr)?;
+                let to = SpecificDate::parse(&to, config.locale)
+                    .map_err(|e| anyhow!(e))?
+                    .next_date(clock.today());
+                let date = reminders_storage.reschedule(nr, |_| to)?;
 
-                println!("Deleted {}", nr,);
+                println!("Moved {} to {}", nr, date.format(YEAR_MONTH_DAY)?);
             }
-            ReminderCmd::List => {
+            ReminderCmd::Import { ics } | ReminderCmd::ImportIcs { file: ics } => {
+                tracing::info!("intention to import reminders from {}", ics);
+
+                let local = clock.local_offset().unwrap_or_else(|| {
+                    eprintln!("Warning: could not determine the local time zone, times are imported in UTC");
+                    UtcOffset::UTC
+                });
+                let content = read_source(&ics).await?;
+
+                let today = clock.today();
+                let mut imported = 0;
+                for event in ics::parse_events(&content, local) {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(e) => {
+                            eprintln!("Skipped an event: {:#}", e);
+                            continue;
+                        }
+                    };
+                    // Importing the same calendar again shouldn't remind us twice
+                    if reminders_storage.contains(&event.summary, event.start) {
+                        eprintln!("Skipped '{}': it was imported before", event.summary);
+                        continue;
+                    }
+                    match event.schedule() {
+                        Ok(Schedule::Once(date)) if date < today => {
+                            eprintln!("Skipped '{}': it was in the past", event.summary);
+                        }
+                        Ok(Schedule::Every(_, Some(until))) if until < today => {
+                            eprintln!("Skipped '{}': it ended before today", event.summary);
+                        }
+                        Ok(Schedule::Once(date)) => {
+                            reminders_storage.on_date(date, &event.summary).at(event.at);
+                            imported += 1;
+                        }
+                        Ok(Schedule::Every(interval, until)) => {
+                            reminders_storage
+                                .every_since(event.start, &interval, &event.summary)
+                                .at(event.at)
+                                .until(until)
+                                .skipping(&event.excluded);
+                            imported += 1;
+                        }
+                        Err(reason) => eprintln!("Skipped '{}': {}", event.summary, reason),
+                    }
+                }
+
+                println!("Imported {} reminders", imported);
+            }
+            ReminderCmd::Export { format, output } => {
+                tracing::info!("intention to export reminders as {:?}", format);
+
+                let reminders = reminders_storage.export();
+                let exported = match format {
+                    ExportFormat::Ics => ics::to_calendar(&reminders, clock.today()),
+                    ExportFormat::Json => serde_json::to_string_pretty(&reminders)? + "\n",
+                    ExportFormat::Csv => {
+                        let mut writer = csv::Writer::from_writer(Vec::new());
+                        for reminder in &reminders {
+                            writer.serialize(reminder)?;
+                        }
+                        String::from_utf8(writer.into_inner()?)?
+                    }
+                };
+
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, exported)
+                            .with_context(|| format!("Could not write to {:?}", path))?;
+                        println!(
+                            "Exported {} reminders to {}",
+                            reminders.len(),
+                            path.to_string_lossy()
+                        );
+                    }
+                    None => print!("{}", exported),
+                }
+            }
+            ReminderCmd::List {
+                recurring,
+                one_off,
+                before,
+                matching,
+                format,
+            } => {
                 tracing::info!("intention to list reminders");
 
-                let data = reminders_storage.all();
-                let table = Table::new(&data)
-                    .with(Style::modern())
-                    .with(Modify::new(Segment::all()).with(Alignment::left()));
+                let today = clock.today();
+                let before = match before {
+                    Some(before) => Some(
+                        SpecificDate::parse(&before, config.locale)
+                            .map_err(|e| anyhow!(e))?
+                            .next_date(today),
+                    ),
+                    None => None,
+                };
+                let filter = ListFilter {
+                    recurring,
+                    one_off,
+                    before,
+                    matching,
+                };
+
+                let mut data = reminders_storage.all(clock);
+                data.retain(|reminder| filter.keeps(reminder));
+                by_next_occurrence(&mut data);
+
+                match format {
+                    ListFormat::Table => {
+                        let table = accessibility::styled(Table::new(&data), config.ascii);
+                        println!("{}", table);
+                    }
+                    ListFormat::Json => println!("{}", serde_json::to_string_pretty(&data)?),
+                }
+            }
+            ReminderCmd::Manage => {
+                tracing::info!("intention to manage reminders interactively");
+
+                manage::manage(&mut reminders_storage, clock, config)?;
+            }
+            ReminderCmd::Notify => {
+                tracing::info!("intention to notify about today's reminders");
+
+                let due = match config.reminders() {
+                    Some(reminders) => reminders.load_with_shared(journal).await?.for_today(clock),
+                    None => reminders_storage.for_today(clock),
+                };
+                for reminder in &due {
+                    daemon::notify(reminder)?;
+                }
+                println!("Notified about {} reminder(s)", due.len());
+            }
+            ReminderCmd::Upcoming { days } => {
+                tracing::info!("intention to preview upcoming reminders");
+
+                let upcoming = reminders_storage.upcoming(clock.today(), days);
+
+                let table = accessibility::styled(Table::new(&upcoming), config.ascii);
 
                 println!("{}", table);
             }
             ReminderCmd::New {
                 on_date: specific_date_spec,
                 every: interval_spec,
+                priority,
+                at,
+                weekdays_only,
                 reminder,
             } => {
                 tracing::info!("intention to create a new reminder");
 
+                let specific_date_spec = specific_date_spec
+                    .map(|raw| SpecificDate::parse(&raw, config.locale))
+                    .transpose()
+                    .map_err(|e| anyhow!(e))?;
+                let interval_spec = interval_spec
+                    .map(|raw| RepeatingDate::parse(&raw, config.locale))
+                    .transpose()
+                    .map_err(|e| anyhow!(e))?;
+
                 if let Some(date_spec) = specific_date_spec {
                     let next = date_spec.next_date(clock.today());
 
-                    reminders_storage.on_date(next, reminder.clone());
+                    reminders_storage
+                        .on_date(next, reminder.clone())
+                        .with_priority(priority)
+                        .at(at);
 
                     println!(
                         "Added a reminder for '{}' on '{}'",
@@ -167,7 +663,11 @@ impl ReminderCmd {
                 }
 
                 if let Some(interval_spec) = interval_spec {
-                    reminders_storage.every(clock, &interval_spec, &reminder);
+                    reminders_storage
+                        .every(clock, &interval_spec, &reminder)
+                        .with_priority(priority)
+                        .at(at)
+                        .weekdays_only(weekdays_only);
 
                     println!(
                         "Added a reminder for '{}' every '{}'",
@@ -177,16 +677,61 @@ impl ReminderCmd {
             }
         }
 
+        if serde_json::to_string(&reminders_storage)? == before {
+            tracing::info!("Reminders are unchanged");
+            return Ok(());
+        }
+
         reminders_storage
             .save(&location)
             .context("Failed to save reminders")?;
 
         tracing::info!("Saved reminders");
+        git::commit(config, "Update reminders", clock.today());
 
         Ok(())
     }
 }
 
+/// Reads a calendar either from disk or, for `http(s)://` and `webcal://` URLs, from the web
+async fn read_source(source: &str) -> Result<String> {
+    match remote_url(source) {
+        Some(url) => {
+            tracing::info!("Fetching calendar from {}", url);
+            reqwest::get(&url)
+                .await
+                .and_then(|response| response.error_for_status())
+                .with_context(|| format!("Could not fetch {}", url))?
+                .text()
+                .await
+                .with_context(|| format!("Could not read {}", url))
+        }
+        None => {
+            std::fs::read_to_string(source).with_context(|| format!("Could not read {:?}", source))
+        }
+    }
+}
+
+/// Reminders from a file or URL that isn't ours to change
+async fn load_shared(journal: &Journal, source: &str) -> Result<Reminders> {
+    let content = match remote_url(source) {
+        Some(_) => read_source(source).await?,
+        None => read_source(&journal.child_file(source).to_string_lossy()).await?,
+    };
+    Reminders::from_reader(content.as_bytes())
+}
+
+/// The URL to fetch a calendar or reminders from, if it is one. Subscriptions are served over `https`.
+fn remote_url(source: &str) -> Option<String> {
+    if let Some(rest) = source.strip_prefix("webcal://") {
+        return Some(format!("https://{}", rest));
+    }
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return Some(source.to_string());
+    }
+    None
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 enum InnerReminder {
@@ -195,170 +740,837 @@ enum InnerReminder {
         start: Date,
         interval: RepeatingDate,
         reminder: String,
+        /// Occurrences that were already marked as done
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        completed: Vec<Date>,
+        /// Occurrences on a weekend are moved to the Monday after
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        weekdays_only: bool,
+        /// The last day it can fire on, like for a calendar event that ends
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        until: Option<Date>,
     },
 }
 
-#[derive(Deserialize, Serialize)]
-pub struct Reminders {
-    stored: Vec<InnerReminder>,
+/// How important a reminder is
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
 }
 
-impl Reminders {
-    #[tracing::instrument(err, name = "Loading reminders from disk")]
-    pub fn load(path: &Path) -> Result<Self> {
-        let content = std::fs::read(path)
-            .with_context(|| format!("Could not load reminders from {:?}", path))?;
+impl Priority {
+    fn is_normal(&self) -> bool {
+        *self == Priority::Normal
+    }
+}
 
-        let reminders = serde_json::from_slice(&content)
-            .map_err(|e| anyhow!(e))
-            .context("Could not read structure in file")?;
+impl Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::High => f.write_str("high"),
+            Priority::Normal => f.write_str("normal"),
+            Priority::Low => f.write_str("low"),
+        }
+    }
+}
 
-        tracing::info!("Loaded reminders");
-        Ok(reminders)
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "high" => Ok(Priority::High),
+            "normal" => Ok(Priority::Normal),
+            "low" => Ok(Priority::Low),
+            _ => Err(format!("unknown priority: {}", s)),
+        }
     }
+}
 
-    #[tracing::instrument(err, name = "Saving reminders to disk", skip(self))]
-    pub fn save(&self, path: &Path) -> Result<()> {
-        tracing::info!("Saving reminders to {}", path.to_string_lossy());
-        let mut reminders_file = std::fs::OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .open(path)
-            .context("Opening reminders file to write")?;
+/// The time of day a reminder is due, like `14:30`
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay {
+    pub hour: u8,
+    pub minute: u8,
+}
 
-        serde_json::to_writer_pretty(&mut reminders_file, &self).map_err(|e| anyhow!(e))?;
-        tracing::info!("Saved reminders");
-        Ok(())
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
     }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = String;
 
-    pub fn on_date<S: Into<String>>(&mut self, date: Date, reminder: S) {
-        self.stored
-            .push(InnerReminder::Concrete(date, reminder.into()));
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hour, minute) = match s.split_once(':') {
+            Some(parts) => parts,
+            None => return Err(format!("Unrecognized time, use hours:minutes: {}", s)),
+        };
+
+        let hour: u8 = str::parse(hour).map_err(|e: ParseIntError| e.to_string())?;
+        let minute: u8 = str::parse(minute).map_err(|e: ParseIntError| e.to_string())?;
+        if hour > 23 || minute > 59 {
+            return Err(format!("Not a time of day: {}", s));
+        }
+
+        Ok(TimeOfDay { hour, minute })
     }
+}
 
-    pub fn every(&mut self, clock: &impl Clock, interval: &RepeatingDate, reminder: &str) {
-        let start = clock.today();
-        self.stored.push(InnerReminder::Recurring {
-            start,
-            interval: interval.clone(),
-            reminder: reminder.to_string(),
-        });
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        raw.parse()
     }
+}
 
-    #[tracing::instrument(name = "Loading todays reminders", skip(self, clock))]
-    pub fn for_today(&self, clock: &dyn Clock) -> Vec<String> {
-        let today = clock.today();
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> Self {
+        time.to_string()
+    }
+}
 
-        let mut reminders = Vec::new();
+/// A reminder as it is stored, along with how important it is and at what time it is due.
+/// Reminders stored before priorities existed are `normal` and have no time.
+#[derive(Deserialize, Serialize)]
+pub struct StoredReminder {
+    #[serde(flatten)]
+    inner: InnerReminder,
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    priority: Priority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    at: Option<TimeOfDay>,
+}
 
-        for reminder in &self.stored {
-            match reminder {
-                InnerReminder::Concrete(date, reminder) => {
-                    if today == *date {
-                        reminders.push(reminder.clone());
-                    }
-                }
-                InnerReminder::Recurring {
-                    start,
-                    interval,
-                    reminder,
-                } => match interval {
-                    RepeatingDate::Weekday(weekday) => {
-                        if today.weekday() == *weekday {
-                            reminders.push(reminder.clone());
-                        }
-                    }
-                    RepeatingDate::Periodic { amount, period } => {
-                        let interval_in_days = amount * period;
-                        let difference = today.to_julian_day() - start.to_julian_day();
+impl StoredReminder {
+    pub fn with_priority(&mut self, priority: Priority) -> &mut Self {
+        self.priority = priority;
+        self
+    }
 
-                        if difference % interval_in_days == 0 {
-                            reminders.push(reminder.clone());
-                        }
-                    }
-                },
-            }
-        }
+    pub fn at(&mut self, at: Option<TimeOfDay>) -> &mut Self {
+        self.at = at;
+        self
+    }
 
-        reminders
+    /// Moves occurrences on a weekend to the Monday after. One-off reminders stay where they are.
+    pub fn weekdays_only(&mut self, only: bool) -> &mut Self {
+        if let InnerReminder::Recurring { weekdays_only, .. } = &mut self.inner {
+            *weekdays_only = only;
+        }
+        self
     }
 
-    pub fn all(&self) -> Vec<Reminder> {
-        let mut nr = 1;
-        let mut result = Vec::new();
-        for reminder in &self.stored {
-            match reminder {
-                InnerReminder::Concrete(date, reminder) => {
-                    let format = format_description::parse("[year]-[month]-[day]").unwrap();
-                    result.push(Reminder {
-                        nr,
-                        date: date.format(&format).unwrap(),
-                        reminder: reminder.to_string(),
-                    });
-                }
-                InnerReminder::Recurring {
-                    interval, reminder, ..
-                } => {
-                    result.push(Reminder {
-                        nr,
-                        date: interval.to_string(),
-                        reminder: reminder.to_string(),
-                    });
-                }
-            }
-            nr += 1;
+    /// Ends a recurring reminder after `until`. One-off reminders stay as they are.
+    pub fn until(&mut self, last: Option<Date>) -> &mut Self {
+        if let InnerReminder::Recurring { until, .. } = &mut self.inner {
+            *until = last;
         }
+        self
+    }
 
-        result
+    /// Leaves out these occurrences of a recurring reminder, as if they were already done
+    pub fn skipping(&mut self, dates: &[Date]) -> &mut Self {
+        if let InnerReminder::Recurring { completed, .. } = &mut self.inner {
+            completed.extend(dates);
+        }
+        self
     }
 
-    #[tracing::instrument(skip(self))]
-    pub fn delete(&mut self, nr: u32) -> Result<()> {
-        let nr = (nr - 1) as usize;
-        if nr < self.stored.len() {
-            self.stored.remove(nr);
-            Ok(())
-        } else {
-            bail!("There is no reminder '{}'", (nr + 1));
+    /// The text of the reminder, starting with its time if it has one
+    fn describe(&self) -> String {
+        match self.at {
+            Some(at) => format!("{} — {}", at, self.inner.text()),
+            None => self.inner.text().to_string(),
         }
     }
 }
 
-#[derive(Tabled)]
-pub struct Reminder {
-    pub nr: usize,
-    pub date: String,
-    pub reminder: String,
+impl From<InnerReminder> for StoredReminder {
+    fn from(inner: InnerReminder) -> Self {
+        StoredReminder {
+            inner,
+            priority: Priority::Normal,
+            at: None,
+        }
+    }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub enum SpecificDate {
-    Next(Weekday),
-    OnDate(Date),
-    OnDayMonth(u8, Month),
-}
+/// How far ahead we look for the next occurrence of a recurring reminder
+const LOOKAHEAD_IN_DAYS: i64 = 5 * 366;
 
-impl SpecificDate {
-    pub fn next_date(self, current: Date) -> Date {
+impl InnerReminder {
+    fn text(&self) -> &str {
         match self {
-            Self::OnDate(date) => date,
-            Self::OnDayMonth(day, month) => Date::from_calendar_date(current.year(), month, day)
-                .expect("Day should have existed"),
-            Self::Next(weekday) => current.next(weekday),
+            InnerReminder::Concrete(_, reminder) => reminder,
+            InnerReminder::Recurring { reminder, .. } => reminder,
         }
     }
-}
-
-impl FromStr for SpecificDate {
-    type Err = String;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let components: Vec<&str> = s.split('.').collect();
+    fn is_due_on(&self, date: Date) -> bool {
+        match self {
+            InnerReminder::Concrete(on, _) => *on == date,
+            InnerReminder::Recurring { completed, .. } => {
+                self.is_scheduled_on(date) && !completed.contains(&date)
+            }
+        }
+    }
+
+    /// Whether a recurring reminder fires on `date`, no matter if it was already done
+    fn is_scheduled_on(&self, date: Date) -> bool {
+        match self {
+            InnerReminder::Concrete(on, _) => *on == date,
+            InnerReminder::Recurring {
+                until: Some(until), ..
+            } if date > *until => false,
+            InnerReminder::Recurring {
+                start,
+                interval,
+                weekdays_only: false,
+                ..
+            } => interval.matches(*start, date),
+            InnerReminder::Recurring {
+                start,
+                interval,
+                weekdays_only: true,
+                ..
+            } => match date.weekday() {
+                Weekday::Saturday | Weekday::Sunday => false,
+                // Picks up whatever would have fired over the weekend
+                Weekday::Monday => (0..3)
+                    .map(|days| date - Duration::days(days))
+                    .any(|day| day >= *start && interval.matches(*start, day)),
+                _ => interval.matches(*start, date),
+            },
+        }
+    }
+
+    /// The first day, starting with `today`, on which the reminder is due.
+    /// `None` for reminders that won't fire anymore.
+    fn next_occurrence(&self, today: Date) -> Option<Date> {
+        match self {
+            InnerReminder::Concrete(date, _) => (*date >= today).then_some(*date),
+            InnerReminder::Recurring { .. } => (0..LOOKAHEAD_IN_DAYS)
+                .map(|days| today + Duration::days(days))
+                .find(|date| self.is_due_on(*date)),
+        }
+    }
+}
+
+/// A reminder as it is handed out by `Reminders::iter`, to be used outside of the `reminders` command
+#[derive(Clone, Copy)]
+pub struct ReminderRef<'a> {
+    /// The number to refer to it by in commands like `reminders delete`
+    pub nr: usize,
+    stored: &'a StoredReminder,
+}
+
+impl<'a> ReminderRef<'a> {
+    pub fn text(&self) -> &'a str {
+        self.stored.inner.text()
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.stored.priority
+    }
+
+    pub fn at(&self) -> Option<TimeOfDay> {
+        self.stored.at
+    }
+
+    /// How often the reminder repeats, `None` for one-off reminders
+    pub fn interval(&self) -> Option<&'a RepeatingDate> {
+        match &self.stored.inner {
+            InnerReminder::Concrete(..) => None,
+            InnerReminder::Recurring { interval, .. } => Some(interval),
+        }
+    }
+
+    pub fn is_due_on(&self, date: Date) -> bool {
+        self.stored.inner.is_due_on(date)
+    }
+
+    /// The first day, starting with `today`, on which the reminder is due.
+    /// `None` for reminders that won't fire anymore.
+    pub fn next_occurrence(&self, today: Date) -> Option<Date> {
+        self.stored.inner.next_occurrence(today)
+    }
+}
+
+/// A reminder on one of the days it is due on
+#[derive(Clone, Copy)]
+pub struct DueReminder<'a> {
+    pub date: Date,
+    pub reminder: ReminderRef<'a>,
+}
+
+/// Upgrades the raw contents of `reminders.json` by one version each, starting at version 0.
+/// Append to this list whenever the format changes so older files keep loading.
+const MIGRATIONS: &[fn(&mut serde_json::Value)] = &[
+    // 0 -> 1: files before versioning had no `version` field but the same structure
+    |_| {},
+];
+
+/// The version of the format written by `Reminders::save`
+const CURRENT_VERSION: u64 = MIGRATIONS.len() as u64;
+
+fn current_version() -> u64 {
+    CURRENT_VERSION
+}
+
+/// Brings the contents of a reminders file written by any older version up to the current format
+fn migrate(mut raw: serde_json::Value) -> Result<serde_json::Value> {
+    let version = raw.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version > CURRENT_VERSION {
+        bail!(
+            "The reminders were written by a newer version of journal (format {}, this one knows up to {}), please update",
+            version,
+            CURRENT_VERSION
+        );
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        tracing::info!("Migrating reminders from version {} to {}", from, from + 1);
+        migration(&mut raw);
+    }
+
+    if let Some(object) = raw.as_object_mut() {
+        object.insert("version".to_string(), CURRENT_VERSION.into());
+    }
+    Ok(raw)
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Reminders {
+    #[serde(default = "current_version")]
+    version: u64,
+    stored: Vec<StoredReminder>,
+}
+
+impl Default for Reminders {
+    fn default() -> Self {
+        Reminders {
+            version: current_version(),
+            stored: Vec::new(),
+        }
+    }
+}
+
+impl Reminders {
+    #[tracing::instrument(err, name = "Loading reminders from disk")]
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Could not load reminders from {:?}", path))?;
+
+        let reminders = Self::from_reader(std::io::BufReader::new(file))?;
+        tracing::info!("Loaded reminders");
+        Ok(reminders)
+    }
+
+    /// Reads reminders in the format of `reminders.json`, upgrading older versions of it
+    pub fn from_reader(reader: impl Read) -> Result<Self> {
+        let raw = serde_json::from_reader(reader)
+            .map_err(|e| anyhow!(e))
+            .context("Could not read structure in file")?;
+
+        serde_json::from_value(migrate(raw)?)
+            .map_err(|e| anyhow!(e))
+            .context("Could not read structure in file")
+    }
+
+    #[tracing::instrument(err, name = "Saving reminders to disk", skip(self))]
+    pub fn save(&self, path: &Path) -> Result<()> {
+        tracing::info!("Saving reminders to {}", path.to_string_lossy());
+        let mut json = Vec::new();
+        self.to_writer(&mut json)?;
+
+        // Writing the same again would replace the backup with what is already there
+        if std::fs::read(path).is_ok_and(|current| current == json) {
+            tracing::info!("Reminders are unchanged");
+            return Ok(());
+        }
+
+        // Keep the previous version around, in case a change to the reminders needs undoing
+        if let Some(name) = path.file_name().filter(|_| path.exists()) {
+            let backup = path.with_file_name(format!("{}.bak", name.to_string_lossy()));
+            std::fs::copy(path, &backup)
+                .with_context(|| format!("Could not back up the reminders to {:?}", backup))?;
+        }
+        write_atomically(path, &json).context("Could not write the reminders file")?;
+        tracing::info!("Saved reminders");
+        Ok(())
+    }
+
+    /// Writes the reminders in the current format of `reminders.json`
+    pub fn to_writer(&self, writer: impl Write) -> Result<()> {
+        serde_json::to_writer_pretty(writer, &self).map_err(|e| anyhow!(e))
+    }
+
+    /// Adds reminders from somewhere else after these ones, e.g. to show shared ones.
+    /// Saving the result would copy them over, so this is only meant for showing them.
+    pub fn extend(&mut self, other: Reminders) {
+        self.stored.extend(other.stored);
+    }
+
+    /// Every reminder along with its number, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = ReminderRef<'_>> {
+        (1..)
+            .zip(self.stored.iter())
+            .map(|(nr, stored)| ReminderRef { nr, stored })
+    }
+
+    /// Every time a reminder is due on one of the `dates`, day by day
+    pub fn occurrences(&self, dates: Range<Date>) -> impl Iterator<Item = DueReminder<'_>> {
+        let days = (dates.end - dates.start).whole_days().max(0);
+        (0..days)
+            .map(move |offset| dates.start + Duration::days(offset))
+            .flat_map(move |date| {
+                self.iter()
+                    .filter(move |reminder| reminder.is_due_on(date))
+                    .map(move |reminder| DueReminder { date, reminder })
+            })
+    }
+
+    /// Whether there already is a reminder with this text starting on `date`, e.g. from importing a calendar before
+    pub fn contains(&self, text: &str, date: Date) -> bool {
+        self.stored.iter().any(|stored| match &stored.inner {
+            InnerReminder::Concrete(on, reminder) => *on == date && reminder == text,
+            InnerReminder::Recurring {
+                start, reminder, ..
+            } => *start == date && reminder == text,
+        })
+    }
+
+    pub fn on_date<S: Into<String>>(&mut self, date: Date, reminder: S) -> &mut StoredReminder {
+        self.push(InnerReminder::Concrete(date, reminder.into()))
+    }
+
+    pub fn every(
+        &mut self,
+        clock: &impl Clock,
+        interval: &RepeatingDate,
+        reminder: &str,
+    ) -> &mut StoredReminder {
+        self.every_since(clock.today(), interval, reminder)
+    }
+
+    /// A recurring reminder whose interval is counted from `start`
+    pub fn every_since(
+        &mut self,
+        start: Date,
+        interval: &RepeatingDate,
+        reminder: &str,
+    ) -> &mut StoredReminder {
+        self.push(InnerReminder::Recurring {
+            start,
+            interval: interval.clone(),
+            reminder: reminder.to_string(),
+            completed: Vec::new(),
+            weekdays_only: false,
+            until: None,
+        })
+    }
+
+    fn push(&mut self, reminder: InnerReminder) -> &mut StoredReminder {
+        self.stored.push(reminder.into());
+        self.stored.last_mut().unwrap()
+    }
+
+    /// The reminders that are due today in the order of their time,
+    /// followed by the ones without a time, the most important ones first
+    fn due_today(&self, clock: &dyn Clock, which: impl Fn(&ReminderRef) -> bool) -> Vec<String> {
+        self.due_refs(clock, which)
+            .into_iter()
+            .map(|reminder| reminder.stored.describe())
+            .collect()
+    }
+
+    fn due_refs(
+        &self,
+        clock: &dyn Clock,
+        which: impl Fn(&ReminderRef) -> bool,
+    ) -> Vec<ReminderRef<'_>> {
+        let today = clock.today();
+
+        let mut due = self
+            .iter()
+            .filter(|reminder| which(reminder) && reminder.is_due_on(today))
+            .collect::<Vec<_>>();
+        due.sort_by_key(|reminder| (reminder.at().is_none(), reminder.at(), reminder.priority()));
+        due
+    }
+
+    #[tracing::instrument(name = "Loading todays reminders", skip(self, clock))]
+    pub fn for_today(&self, clock: &dyn Clock) -> Vec<String> {
+        self.due_today(clock, |_| true)
+    }
+
+    /// Today's reminders like `for_today`, along with the time they are due at if they have one
+    pub fn timed_for_today(&self, clock: &dyn Clock) -> Vec<(Option<TimeOfDay>, String)> {
+        self.due_refs(clock, |_| true)
+            .into_iter()
+            .map(|reminder| (reminder.at(), reminder.stored.describe()))
+            .collect()
+    }
+
+    /// Today's reminders that are only due this once
+    pub fn one_offs_for_today(&self, clock: &dyn Clock) -> Vec<String> {
+        self.due_today(clock, |reminder| reminder.interval().is_none())
+    }
+
+    /// Today's reminders that will come back again
+    pub fn recurring_for_today(&self, clock: &dyn Clock) -> Vec<String> {
+        self.due_today(clock, |reminder| reminder.interval().is_some())
+    }
+
+    pub fn all(&self, clock: &dyn Clock) -> Vec<Reminder> {
+        let today = clock.today();
+        let mut result = Vec::new();
+        for (nr, stored) in (1..).zip(self.stored.iter()) {
+            let next_date = stored.inner.next_occurrence(today);
+            let next = match next_date {
+                // "in 12 days" alone means counting on the calendar, so give the day too
+                Some(date) if date - today > Duration::days(1) => format!(
+                    "{} ({})",
+                    date.format(YEAR_MONTH_DAY).unwrap(),
+                    relative_to(today, next_date)
+                ),
+                _ => relative_to(today, next_date),
+            };
+            let priority = stored.priority;
+            let at = stored.at;
+            let with_time = |date: String| match at {
+                Some(at) => format!("{} at {}", date, at),
+                None => date,
+            };
+
+            match &stored.inner {
+                InnerReminder::Concrete(date, reminder) => {
+                    let format = format_description::parse("[year]-[month]-[day]").unwrap();
+                    result.push(Reminder {
+                        nr,
+                        date: with_time(date.format(&format).unwrap()),
+                        next,
+                        priority,
+                        reminder: reminder.to_string(),
+                        next_date,
+                        at,
+                        recurring: false,
+                    });
+                }
+                InnerReminder::Recurring {
+                    interval,
+                    reminder,
+                    weekdays_only,
+                    ..
+                } => {
+                    let date = match weekdays_only {
+                        true => format!("{} on weekdays", interval),
+                        false => interval.to_string(),
+                    };
+                    result.push(Reminder {
+                        nr,
+                        date: with_time(date),
+                        next,
+                        priority,
+                        reminder: reminder.to_string(),
+                        next_date,
+                        at,
+                        recurring: true,
+                    });
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Every occurrence of every reminder in the `days` days starting with `today`, by date
+    pub fn upcoming(&self, today: Date, days: u32) -> Vec<UpcomingReminder> {
+        self.occurrences(today..today + Duration::days(days as i64))
+            .map(|due| UpcomingReminder {
+                date: due.date.format(YEAR_MONTH_DAY).unwrap(),
+                day: due.date.weekday().to_string(),
+                when: relative_to(today, Some(due.date)),
+                nr: due.reminder.nr,
+                reminder: due.reminder.text().to_string(),
+            })
+            .collect()
+    }
+
+    /// All reminders with the first day they fire on, to be used by other tools
+    pub fn export(&self) -> Vec<ExportedReminder> {
+        (1..)
+            .zip(self.stored.iter())
+            .map(|(nr, stored)| match &stored.inner {
+                InnerReminder::Concrete(date, reminder) => ExportedReminder {
+                    nr,
+                    date: *date,
+                    at: stored.at,
+                    repeats: None,
+                    priority: stored.priority,
+                    reminder: reminder.clone(),
+                    interval: None,
+                    weekdays_only: false,
+                },
+                InnerReminder::Recurring {
+                    start,
+                    interval,
+                    reminder,
+                    weekdays_only,
+                    ..
+                } => ExportedReminder {
+                    nr,
+                    date: (0..LOOKAHEAD_IN_DAYS)
+                        .map(|days| *start + Duration::days(days))
+                        .find(|date| interval.matches(*start, *date))
+                        .unwrap_or(*start),
+                    at: stored.at,
+                    repeats: Some(interval.to_string()),
+                    priority: stored.priority,
+                    reminder: reminder.clone(),
+                    interval: Some(interval.clone()),
+                    weekdays_only: *weekdays_only,
+                },
+            })
+            .collect()
+    }
+
+    fn get(&self, nr: u32) -> Result<&InnerReminder> {
+        match (nr as usize)
+            .checked_sub(1)
+            .and_then(|index| self.stored.get(index))
+            .map(|stored| &stored.inner)
+        {
+            Some(reminder) => Ok(reminder),
+            None => bail!("There is no reminder '{}'", nr),
+        }
+    }
+
+    fn get_mut(&mut self, nr: u32) -> Result<&mut InnerReminder> {
+        match (nr as usize)
+            .checked_sub(1)
+            .and_then(|index| self.stored.get_mut(index))
+            .map(|stored| &mut stored.inner)
+        {
+            Some(reminder) => Ok(reminder),
+            None => bail!("There is no reminder '{}'", nr),
+        }
+    }
+
+    /// Marks today's occurrence of a reminder as done.
+    /// One-off reminders are deleted as they won't come back anyway.
+    pub fn done(&mut self, nr: u32, today: Date) -> Result<Done> {
+        if !self.get(nr)?.is_scheduled_on(today) {
+            bail!("Reminder '{}' is not due today", nr);
+        }
+
+        match self.get_mut(nr)? {
+            InnerReminder::Concrete(..) => {
+                self.delete(nr)?;
+                Ok(Done::Deleted)
+            }
+            InnerReminder::Recurring { completed, .. } => {
+                // Older completions don't matter anymore
+                completed.retain(|date| *date >= today);
+                if !completed.contains(&today) {
+                    completed.push(today);
+                }
+                Ok(Done::Completed)
+            }
+        }
+    }
+
+    /// Replaces the text of a reminder, keeping when it is due
+    pub fn edit(&mut self, nr: u32, text: &str) -> Result<()> {
+        match self.get_mut(nr)? {
+            InnerReminder::Concrete(_, reminder) | InnerReminder::Recurring { reminder, .. } => {
+                *reminder = text.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// Changes the date of a one-off reminder, or when a periodic reminder starts counting
+    /// so that it continues its rhythm from the new date
+    pub fn reschedule(&mut self, nr: u32, to: impl FnOnce(Date) -> Date) -> Result<Date> {
+        match self.get_mut(nr)? {
+            InnerReminder::Concrete(date, _) => {
+                *date = to(*date);
+                Ok(*date)
+            }
+            InnerReminder::Recurring {
+                start,
+                interval: RepeatingDate::Periodic { .. },
+                ..
+            } => {
+                *start = to(*start);
+                Ok(*start)
+            }
+            InnerReminder::Recurring { interval, .. } => bail!(
+                "Reminder '{}' is due {}, which doesn't depend on when it started",
+                nr,
+                interval
+            ),
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn delete(&mut self, nr: u32) -> Result<()> {
+        let nr = (nr - 1) as usize;
+        if nr < self.stored.len() {
+            self.stored.remove(nr);
+            Ok(())
+        } else {
+            bail!("There is no reminder '{}'", (nr + 1));
+        }
+    }
+
+    /// Deletes several reminders at once, or none of them if any doesn't exist
+    pub fn delete_all(&mut self, nrs: &[u32]) -> Result<()> {
+        for nr in nrs {
+            self.get(*nr)?;
+        }
+
+        // Deleting from the back keeps the numbers of the remaining ones intact
+        let mut nrs = nrs.to_vec();
+        nrs.sort_unstable_by(|a, b| b.cmp(a));
+        nrs.dedup();
+        for nr in nrs {
+            self.delete(nr)?;
+        }
+        Ok(())
+    }
+
+    /// The numbers of all reminders loosely matching `pattern`, ignoring case
+    pub fn matching(&self, pattern: &str) -> Vec<u32> {
+        (1..)
+            .zip(self.stored.iter())
+            .filter(|(_, stored)| fuzzy_matches(pattern, stored.inner.text()))
+            .map(|(nr, _)| nr)
+            .collect()
+    }
+}
+
+/// Whether every word of `pattern` is found in one of the words of `text`,
+/// allowing for letters that were left out like in "dentst" for "dentist"
+fn fuzzy_matches(pattern: &str, text: &str) -> bool {
+    let text = text.to_lowercase();
+    pattern.to_lowercase().split_whitespace().all(|wanted| {
+        text.split_whitespace().any(|word| {
+            let mut letters = word.chars();
+            wanted.chars().all(|c| letters.any(|l| l == c))
+        })
+    })
+}
+
+#[derive(Serialize, Tabled)]
+pub struct Reminder {
+    pub nr: usize,
+    pub date: String,
+    pub next: String,
+    pub priority: Priority,
+    pub reminder: String,
+    #[tabled(skip)]
+    pub next_date: Option<Date>,
+    #[tabled(skip)]
+    pub at: Option<TimeOfDay>,
+    #[tabled(skip)]
+    pub recurring: bool,
+}
+
+/// A reminder as it is exported as CSV or JSON
+#[derive(Debug, Serialize)]
+pub struct ExportedReminder {
+    pub nr: usize,
+    /// The day of a one-off reminder, or the first day a recurring one fires on
+    pub date: Date,
+    pub at: Option<TimeOfDay>,
+    pub repeats: Option<String>,
+    pub priority: Priority,
+    pub reminder: String,
+    #[serde(skip)]
+    pub interval: Option<RepeatingDate>,
+    /// Occurrences on a weekend move to the Monday after
+    #[serde(skip)]
+    pub weekdays_only: bool,
+}
+
+#[derive(Debug, Tabled)]
+pub struct UpcomingReminder {
+    pub date: String,
+    pub day: String,
+    pub when: String,
+    pub nr: usize,
+    pub reminder: String,
+}
+
+/// Puts the reminders that fire soonest first and the ones that won't fire again last.
+/// Reminders on the same day are ordered by their time and then by priority.
+pub(crate) fn by_next_occurrence(reminders: &mut [Reminder]) {
+    reminders.sort_by_key(|reminder| {
+        (
+            reminder.next_date.is_none(),
+            reminder.next_date,
+            reminder.at.is_none(),
+            reminder.at,
+            reminder.priority,
+        )
+    });
+}
+
+fn relative_to(today: Date, next: Option<Date>) -> String {
+    match next.map(|next| (next - today).whole_days()) {
+        None => "passed".to_string(),
+        Some(0) => "today".to_string(),
+        Some(1) => "tomorrow".to_string(),
+        Some(days) => format!("in {} days", days),
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum SpecificDate {
+    Next(Weekday),
+    OnDate(Date),
+    OnDayMonth(u8, Month),
+}
+
+impl SpecificDate {
+    pub fn next_date(self, current: Date) -> Date {
+        match self {
+            Self::OnDate(date) => date,
+            Self::OnDayMonth(day, month) => Date::from_calendar_date(current.year(), month, day)
+                .expect("Day should have existed"),
+            Self::Next(weekday) => current.next(weekday),
+        }
+    }
+}
+
+impl FromStr for SpecificDate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SpecificDate::parse(s, Locale::default())
+    }
+}
+
+impl SpecificDate {
+    /// Like `from_str`, but also with the month and weekday names of `locale`
+    pub fn parse(s: &str, locale: Locale) -> Result<Self, String> {
+        let components: Vec<&str> = s.split('.').collect();
 
         match &components[..] {
             [day, month, year] => {
                 let day: u8 = str::parse(day).map_err(|e: ParseIntError| e.to_string())?;
-                let month = parse_month(month)?;
+                let month = locale.parse_month(month)?;
                 let year: i32 = str::parse(year).map_err(|e: ParseIntError| e.to_string())?;
                 Ok(SpecificDate::OnDate(
                     Date::from_calendar_date(year, month, day).map_err(|e| e.to_string())?,
@@ -366,11 +1578,11 @@ impl FromStr for SpecificDate {
             }
             [day, month] => {
                 let day: u8 = str::parse(day).map_err(|e: ParseIntError| e.to_string())?;
-                let month = parse_month(month)?;
+                let month = locale.parse_month(month)?;
                 Ok(SpecificDate::OnDayMonth(day, month))
             }
             [weekday] => {
-                let weekday = parse_weekday(weekday)?;
+                let weekday = locale.parse_weekday(weekday)?;
                 Ok(SpecificDate::Next(weekday))
             }
             _ => Err(
@@ -381,169 +1593,697 @@ impl FromStr for SpecificDate {
     }
 }
 
-#[rustfmt::skip]
-fn parse_weekday(s: &str) -> Result<Weekday, String> {
-    match s {
-        "Monday"    | "Mon" | "monday"   | "mon" => Ok(Weekday::Monday),
-        "Tuesday"   | "Tue" | "tuesday"  | "tue" => Ok(Weekday::Tuesday),
-        "Wednesday" | "Wed" | "wedneday" | "wed" => Ok(Weekday::Wednesday),
-        "Thursday"  | "Thu" | "thursday" | "thu" => Ok(Weekday::Thursday),
-        "Friday"    | "Fri" | "friday"   | "fri" => Ok(Weekday::Friday),
-        "Saturday"  | "Sat" | "saturday" | "sat" => Ok(Weekday::Saturday),
-        "Sunday"    | "Sun" | "sunday"   | "sun" => Ok(Weekday::Sunday),
-        _ => Err(format!("No matching day of the week: {}", s)),
-    }
+/// What happened to a reminder that was marked as done
+#[derive(Debug, PartialEq, Eq)]
+pub enum Done {
+    Completed,
+    Deleted,
 }
 
-#[rustfmt::skip]
-fn parse_month(month: &str) -> Result<Month, String> {
-    match month {
-        "January"   | "Jan" | "january"   | "jan" => Ok(Month::January),
-        "February"  | "Feb" | "february"  | "feb" => Ok(Month::February),
-        "March"     | "Mar" | "march"     | "mar" => Ok(Month::March),
-        "April"     | "Apr" | "april"     | "apr" => Ok(Month::April),
-        "May"                             | "may" => Ok(Month::May),
-        "June"      | "Jun" | "june"      | "jun" => Ok(Month::June),
-        "July"      | "Jul" | "july"      | "jul" => Ok(Month::July),
-        "August"    | "Aug" | "august"    | "aug" => Ok(Month::August),
-        "September" | "Sep" | "september" | "sep" => Ok(Month::September),
-        "October"   | "Oct" | "october"   | "oct" => Ok(Month::October),
-        "November"  | "Nov" | "november"  | "nov" => Ok(Month::November),
-        "December"  | "Dec" | "december"  | "dec" => Ok(Month::December),
-        _ => Err(format!("No matching month name: {}", month)),
+/// How long to snooze a reminder for, like `3.days` or `1.week`
+#[derive(Debug, Eq, PartialEq)]
+pub struct Delay(Duration);
+
+impl FromStr for Delay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, period) = match s.split_once('.') {
+            Some(parts) => parts,
+            None => return Err(format!("Unrecognized format for a delay: {}", s)),
+        };
+
+        let amount: i64 = str::parse(digits).map_err(|e: ParseIntError| e.to_string())?;
+        match period {
+            "day" | "days" => Ok(Delay(Duration::days(amount))),
+            "week" | "weeks" => Ok(Delay(Duration::weeks(amount))),
+            _ => Err(format!("unknown period: {}", period)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatingDate {
+    Weekday(Weekday),
+    Weekdays(Vec<Weekday>),
+    Periodic {
+        amount: usize,
+        period: Period,
+    },
+    Yearly {
+        day: u8,
+        month: Month,
+    },
+    Monthly {
+        occurrence: Occurrence,
+        weekday: Weekday,
+    },
+    /// Every `amount` weeks on `weekday`, counting from the first `weekday` since the start
+    WeeklyOn {
+        amount: usize,
+        weekday: Weekday,
+    },
+}
+
+/// Which of the weekdays in a month, e.g. the 1st or the last Monday
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Occurrence {
+    Nth(u8),
+    Last,
+}
+
+impl Occurrence {
+    fn matches(&self, date: Date) -> bool {
+        match self {
+            Occurrence::Nth(n) => (date.day() - 1) / 7 + 1 == *n,
+            Occurrence::Last => {
+                date.day() + 7 > time::util::days_in_year_month(date.year(), date.month())
+            }
+        }
+    }
+}
+
+impl Display for Occurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Occurrence::Nth(n @ 1) => write!(f, "{}st", n),
+            Occurrence::Nth(n @ 2) => write!(f, "{}nd", n),
+            Occurrence::Nth(n @ 3) => write!(f, "{}rd", n),
+            Occurrence::Nth(n) => write!(f, "{}th", n),
+            Occurrence::Last => write!(f, "last"),
+        }
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(rename_all = "lowercase")]
-pub enum RepeatingDate {
-    Weekday(Weekday),
-    Periodic { amount: usize, period: Period },
-}
+impl FromStr for Occurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1st" | "first" => Ok(Occurrence::Nth(1)),
+            "2nd" | "second" => Ok(Occurrence::Nth(2)),
+            "3rd" | "third" => Ok(Occurrence::Nth(3)),
+            "4th" | "fourth" => Ok(Occurrence::Nth(4)),
+            "5th" | "fifth" => Ok(Occurrence::Nth(5)),
+            "last" => Ok(Occurrence::Last),
+            _ => Err(format!("unknown occurrence in the month: {}", s)),
+        }
+    }
+}
+
+impl RepeatingDate {
+    /// Whether a reminder that started on `start` is due on `date`
+    pub(crate) fn matches(&self, start: Date, date: Date) -> bool {
+        match self {
+            RepeatingDate::Weekday(weekday) => date.weekday() == *weekday,
+            RepeatingDate::Weekdays(weekdays) => weekdays.contains(&date.weekday()),
+            RepeatingDate::Yearly { day, month } => is_anniversary(*day, *month, date),
+            RepeatingDate::Monthly {
+                occurrence,
+                weekday,
+            } => date.weekday() == *weekday && occurrence.matches(date),
+            RepeatingDate::WeeklyOn { amount, weekday } => {
+                let difference = date.to_julian_day() - start.next(*weekday).to_julian_day();
+
+                date.weekday() == *weekday && difference % (amount * &Period::Weeks) == 0
+            }
+            RepeatingDate::Periodic {
+                amount,
+                period: Period::Years,
+            } => {
+                let years = date.year() - start.year();
+
+                is_anniversary(start.day(), start.month(), date) && years % (*amount as i32) == 0
+            }
+            RepeatingDate::Periodic { amount, period } => {
+                let interval_in_days = amount * period;
+                let difference = date.to_julian_day() - start.to_julian_day();
+
+                difference % interval_in_days == 0
+            }
+        }
+    }
+}
+
+/// Reminders on the 29th of February are due on the 28th in years without one
+fn is_anniversary(day: u8, month: Month, date: Date) -> bool {
+    if date.month() != month {
+        return false;
+    }
+
+    let last_day = time::util::days_in_year_month(date.year(), month);
+    date.day() == day.min(last_day)
+}
+
+impl Display for RepeatingDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepeatingDate::Weekday(weekday) => write!(f, "{}", weekday),
+            RepeatingDate::Weekdays(weekdays) => {
+                let names = weekdays.iter().map(Weekday::to_string).collect::<Vec<_>>();
+                write!(f, "{}", names.join(", "))
+            }
+            RepeatingDate::Yearly { day, month } => write!(f, "every year on {}. {}", day, month),
+            RepeatingDate::Monthly {
+                occurrence,
+                weekday,
+            } => write!(f, "every {} {} of the month", occurrence, weekday),
+            RepeatingDate::WeeklyOn { amount, weekday } => {
+                write!(f, "every {} weeks on {}", amount, weekday)
+            }
+            RepeatingDate::Periodic { amount, period } => {
+                write!(f, "every {} {:?}", amount, period)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Period {
+    Days,
+    Weeks,
+    Years,
+}
+
+impl Mul<&Period> for &usize {
+    type Output = i32;
+
+    fn mul(self, rhs: &Period) -> Self::Output {
+        let rhs = match rhs {
+            Period::Days => 1,
+            Period::Weeks => 7,
+            // Years differ in length, so yearly reminders are matched on the calendar instead
+            Period::Years => 365,
+        };
+
+        (*self as i32) * rhs
+    }
+}
+
+impl FromStr for RepeatingDate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RepeatingDate::parse(s, Locale::default())
+    }
+}
+
+impl RepeatingDate {
+    /// Like `from_str`, but also with the month and weekday names of `locale`
+    pub fn parse(s: &str, locale: Locale) -> Result<Self, String> {
+        let parsed = locale.parse_weekday(s).map(RepeatingDate::Weekday);
+        if parsed.is_ok() {
+            return parsed;
+        }
+
+        if s.contains(',') {
+            let weekdays = s
+                .split(',')
+                .map(|day| locale.parse_weekday(day.trim()))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(RepeatingDate::Weekdays(weekdays));
+        }
+
+        if s == "year" {
+            return Ok(RepeatingDate::Periodic {
+                amount: 1,
+                period: Period::Years,
+            });
+        }
+
+        if let Some((digits, period)) = s.split_once('.') {
+            if let Some(weekday) = period.strip_prefix("weeks.on.") {
+                let amount = str::parse(digits).map_err(|e: ParseIntError| e.to_string())?;
+                if amount == 0 {
+                    return Err("every 0 weeks would never come around".to_string());
+                }
+                let weekday = locale.parse_weekday(weekday)?;
+                return Ok(RepeatingDate::WeeklyOn { amount, weekday });
+            }
+
+            if let Ok(weekday) = locale.parse_weekday(period) {
+                let occurrence = digits.parse()?;
+                return Ok(RepeatingDate::Monthly {
+                    occurrence,
+                    weekday,
+                });
+            }
+
+            if let Ok(month) = locale.parse_month(period) {
+                let day = str::parse(digits).map_err(|e: ParseIntError| e.to_string())?;
+                if day == 0 || day > time::util::days_in_year_month(2020, month) {
+                    return Err(format!("{} has no day {}", month, day));
+                }
+
+                return Ok(RepeatingDate::Yearly { day, month });
+            }
+
+            let amount = str::parse(digits).map_err(|e: ParseIntError| e.to_string())?;
+            let period = match period {
+                "days" => Period::Days,
+                "weeks" => Period::Weeks,
+                "years" => Period::Years,
+                _ => return Err(format!("unknown period: {}", period)),
+            };
+
+            return Ok(RepeatingDate::Periodic { amount, period });
+        }
+
+        Err(format!("Unrecognized format for repeating date: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controlled_clock::ControlledClock;
+
+    use anyhow::Result;
+    use assert_fs::{prelude::*, TempDir};
+    use time::{ext::NumericalDuration, macros::date, Month, Month::*};
+
+    // the names had to be different to not clash with time-rs
+    trait PeriodicExt {
+        fn daily(self) -> RepeatingDate;
+        fn weekly(self) -> RepeatingDate;
+    }
+
+    impl PeriodicExt for usize {
+        fn daily(self) -> RepeatingDate {
+            RepeatingDate::Periodic {
+                amount: self,
+                period: Period::Days,
+            }
+        }
+
+        fn weekly(self) -> RepeatingDate {
+            RepeatingDate::Periodic {
+                amount: self,
+                period: Period::Weeks,
+            }
+        }
+    }
+
+    fn reminders() -> (TempDir, Reminders) {
+        let dir = TempDir::new().unwrap();
+        dir.child("reminders.json")
+            .write_str(r#"{"stored": [] }"#)
+            .unwrap();
+
+        let reminders = Reminders::load(&dir.path().join("reminders.json")).unwrap();
+
+        (dir, reminders)
+    }
+
+    #[test]
+    fn repeating_reminders() -> Result<()> {
+        use time::Weekday::*;
+        let mut clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+
+        clock.advance_to(Monday);
+        reminders.every(&clock, &RepeatingDate::Weekday(Wednesday), "Email someone");
+
+        clock.advance_to(Wednesday);
+        let todays_reminders = reminders.for_today(&clock);
+        assert_eq!(todays_reminders, vec!["Email someone".to_string()]);
+
+        clock.advance_by(1.days()); // Thursday
+        reminders.every(&clock, &2.weekly(), "Second task");
+
+        clock.advance_by(1.weeks()); // next Thursday
+        let todays_reminders = reminders.for_today(&clock);
+        assert!(todays_reminders.is_empty());
+
+        clock.advance_by(1.weeks()); // Thursday after that...
+        let todays_reminders = reminders.for_today(&clock);
+        assert_eq!(todays_reminders, vec!["Second task".to_string()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reminders_on_several_weekdays() -> Result<()> {
+        use time::Weekday::*;
+        let mut clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+
+        let gym = RepeatingDate::from_str("Mon,Wed,Fri").unwrap();
+        assert_eq!(gym.to_string(), "Monday, Wednesday, Friday");
+        reminders.every(&clock, &gym, "Go to the gym");
+
+        clock.advance_to(Friday);
+        assert_eq!(reminders.for_today(&clock), vec!["Go to the gym"]);
+
+        clock.advance_to(Tuesday);
+        assert!(reminders.for_today(&clock).is_empty());
+
+        clock.advance_to(Wednesday);
+        assert_eq!(reminders.for_today(&clock), vec!["Go to the gym"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn upcoming_reminders_are_expanded_into_dates() -> Result<()> {
+        use time::Weekday::*;
+        let clock = ControlledClock::new(2021, July, 15)?; // Thursday
+        let (_dir, mut reminders) = reminders();
+
+        reminders.every(
+            &clock,
+            &RepeatingDate::Weekdays(vec![Monday, Friday]),
+            "Gym",
+        );
+        reminders.on_date(date!(2021 - 07 - 17), "Buy flowers");
+        reminders.every(&clock, &3.daily(), "Water the plants");
+
+        let upcoming = reminders
+            .upcoming(clock.today(), 5)
+            .into_iter()
+            .map(|upcoming| (upcoming.date, upcoming.when, upcoming.nr, upcoming.reminder))
+            .collect::<Vec<_>>();
+
+        let row = |date: &str, when: &str, nr, reminder: &str| {
+            (date.to_string(), when.to_string(), nr, reminder.to_string())
+        };
+        assert_eq!(
+            upcoming,
+            vec![
+                row("2021-07-15", "today", 3, "Water the plants"),
+                row("2021-07-16", "tomorrow", 1, "Gym"),
+                row("2021-07-17", "in 2 days", 2, "Buy flowers"),
+                row("2021-07-18", "in 3 days", 3, "Water the plants"),
+                row("2021-07-19", "in 4 days", 1, "Gym"),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn shows_shared_reminders_next_to_ones_own() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("reminders.json")
+            .write_str(r#"{"stored": [{"concrete": ["2021-07-15", "Send the figures"]}]}"#)?;
+        dir.child("team/rituals.json").write_str(
+            r#"{"stored": [{"recurring": {"start": "2021-07-01", "interval": {"weekday": "Thursday"}, "reminder": "Retro"}}]}"#,
+        )?;
+
+        let config = ReminderConfig {
+            template: "{{#each reminders}}{{this}};{{/each}}".to_string(),
+            shared: vec!["team/rituals.json".to_string(), "gone.json".to_string()],
+            ..Default::default()
+        };
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let rendered = config
+            .render(
+                &Journal::new_at(dir.path()),
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await?;
+
+        assert_eq!(rendered, "Send the figures;Retro;");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn shared_reminders_are_due_outside_of_entries_too() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("reminders.json")
+            .write_str(r#"{"stored": [{"concrete": ["2021-07-15", "Send the figures"]}]}"#)?;
+        dir.child("team.json")
+            .write_str(r#"{"stored": [{"concrete": ["2021-07-15", "Retro"]}]}"#)?;
+
+        let config = ReminderConfig {
+            shared: vec!["team.json".to_string()],
+            ..Default::default()
+        };
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let reminders = config
+            .load_with_shared(&Journal::new_at(dir.path()))
+            .await?;
+
+        assert_eq!(
+            reminders.for_today(&clock),
+            vec!["Send the figures", "Retro"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_be_used_as_a_library() -> Result<()> {
+        let raw = r#"{"stored": [
+            {"concrete": ["2021-07-17", "Buy flowers"], "at": "09:30"},
+            {"recurring": {"start": "2021-07-15", "interval": {"weekday": "Monday"}, "reminder": "Plan the week"}}
+        ]}"#;
+        let reminders = Reminders::from_reader(raw.as_bytes())?;
+
+        let recurring = reminders
+            .iter()
+            .filter(|reminder| reminder.interval().is_some())
+            .map(|reminder| (reminder.nr, reminder.text()))
+            .collect::<Vec<_>>();
+        assert_eq!(recurring, vec![(2, "Plan the week")]);
+
+        let due = reminders
+            .occurrences(date!(2021 - 07 - 15)..date!(2021 - 07 - 20))
+            .map(|due| (due.date, due.reminder.text(), due.reminder.at()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            due,
+            vec![
+                (
+                    date!(2021 - 07 - 17),
+                    "Buy flowers",
+                    TimeOfDay::from_str("09:30").ok()
+                ),
+                (date!(2021 - 07 - 19), "Plan the week", None),
+            ]
+        );
+
+        let mut written = Vec::new();
+        reminders.to_writer(&mut written)?;
+        let read_back = Reminders::from_reader(written.as_slice())?;
+        assert_eq!(read_back.iter().count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn recurring_reminders_can_end_and_skip_occurrences() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+        reminders
+            .every(&clock, &2.daily(), "Water the plants")
+            .until(Some(date!(2021 - 07 - 23)))
+            .skipping(&[date!(2021 - 07 - 19)]);
+
+        let dates = reminders
+            .upcoming(clock.today(), 14)
+            .into_iter()
+            .map(|upcoming| upcoming.date)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            dates,
+            vec!["2021-07-15", "2021-07-17", "2021-07-21", "2021-07-23"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn weekend_occurrences_can_move_to_monday() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?; // Thursday
+        let (_dir, mut reminders) = reminders();
+        reminders
+            .every(&clock, &2.daily(), "Water the plants")
+            .weekdays_only(true);
+
+        let dates = reminders
+            .upcoming(clock.today(), 14)
+            .into_iter()
+            .map(|upcoming| upcoming.date)
+            .collect::<Vec<_>>();
 
-impl Display for RepeatingDate {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            RepeatingDate::Weekday(weekday) => write!(f, "{}", weekday),
-            RepeatingDate::Periodic { amount, period } => {
-                write!(f, "every {} {:?}", amount, period)
-            }
-        }
+        assert_eq!(
+            dates,
+            vec![
+                "2021-07-15",
+                "2021-07-19", // from Saturday the 17th, and due on Monday anyway
+                "2021-07-21",
+                "2021-07-23",
+                "2021-07-26", // from Sunday the 25th
+                "2021-07-27",
+            ]
+        );
+        assert_eq!(
+            reminders.all(&clock)[0].date,
+            "every 2 Days on weekdays".to_string()
+        );
+        Ok(())
     }
-}
-
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
-pub enum Period {
-    Days,
-    Weeks,
-}
 
-impl Mul<&Period> for &usize {
-    type Output = i32;
+    #[test]
+    fn yearly_reminders() -> Result<()> {
+        let mut clock = ControlledClock::new(2021, April, 1)?;
+        let (_dir, mut reminders) = reminders();
 
-    fn mul(self, rhs: &Period) -> Self::Output {
-        let rhs = match rhs {
-            Period::Days => 1,
-            Period::Weeks => 7,
+        let birthday = RepeatingDate::Yearly {
+            day: 12,
+            month: April,
         };
+        reminders.every(&clock, &birthday, "Call mum");
+        reminders.every(
+            &clock,
+            &RepeatingDate::from_str("year").unwrap(),
+            "Renew passport",
+        );
 
-        (*self as i32) * rhs
-    }
-}
+        clock.advance_by(11.days());
+        assert_eq!(reminders.for_today(&clock), vec!["Call mum".to_string()]);
 
-impl FromStr for RepeatingDate {
-    type Err = String;
+        clock.advance_by(354.days()); // 2022-04-01
+        assert_eq!(
+            reminders.for_today(&clock),
+            vec!["Renew passport".to_string()]
+        );
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parsed = parse_weekday(s).map(RepeatingDate::Weekday);
-        if parsed.is_ok() {
-            return parsed;
-        }
+        clock.advance_by(11.days());
+        assert_eq!(reminders.for_today(&clock), vec!["Call mum".to_string()]);
 
-        if let Some((digits, period)) = s.split_once('.') {
-            let amount = str::parse(digits).map_err(|e: ParseIntError| e.to_string())?;
-            let period = match period {
-                "days" => Period::Days,
-                "weeks" => Period::Weeks,
-                _ => return Err(format!("unknown period: {}", period)),
-            };
+        Ok(())
+    }
 
-            return Ok(RepeatingDate::Periodic { amount, period });
-        }
+    #[test]
+    fn leap_day_reminders_fall_back_to_the_28th() {
+        let leap_day = RepeatingDate::Yearly {
+            day: 29,
+            month: February,
+        };
 
-        Err(format!("Unrecognized format for repeating date: {}", s))
+        assert!(leap_day.matches(date!(2020 - 02 - 29), date!(2021 - 02 - 28)));
+        assert!(!leap_day.matches(date!(2020 - 02 - 29), date!(2024 - 02 - 28)));
+        assert!(leap_day.matches(date!(2020 - 02 - 29), date!(2024 - 02 - 29)));
     }
-}
 
-#[cfg(test)]
-#[path = "controlled_clock.rs"]
-mod controlled_clock;
+    #[test]
+    fn nth_weekday_of_the_month() {
+        let first_monday = RepeatingDate::from_str("1st.Monday").unwrap();
+        let last_friday = RepeatingDate::from_str("last.Friday").unwrap();
+        let start = date!(2022 - 01 - 01);
+
+        let due = |interval: &RepeatingDate| {
+            (0..90)
+                .map(|days| start + days.days())
+                .filter(|date| interval.matches(start, *date))
+                .collect::<Vec<_>>()
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::controlled_clock::ControlledClock;
-    use super::*;
+        assert_eq!(
+            due(&first_monday),
+            vec![
+                date!(2022 - 01 - 03),
+                date!(2022 - 02 - 07),
+                date!(2022 - 03 - 07)
+            ]
+        );
+        assert_eq!(
+            due(&last_friday),
+            vec![
+                date!(2022 - 01 - 28),
+                date!(2022 - 02 - 25),
+                date!(2022 - 03 - 25)
+            ]
+        );
+        assert_eq!(last_friday.to_string(), "every last Friday of the month");
+    }
 
-    use anyhow::Result;
-    use assert_fs::{prelude::*, TempDir};
-    use time::{ext::NumericalDuration, macros::date, Month, Month::*};
+    #[test]
+    fn every_other_weekday_counts_from_the_first_one_since_the_start() {
+        let every_other_tuesday = RepeatingDate::from_str("2.weeks.on.Tue").unwrap();
+        // A Thursday, so the first Tuesday is the 2022-01-11
+        let start = date!(2022 - 01 - 06);
+
+        let due = (0..42)
+            .map(|days| start + days.days())
+            .filter(|date| every_other_tuesday.matches(start, *date))
+            .collect::<Vec<_>>();
 
-    // the names had to be different to not clash with time-rs
-    trait PeriodicExt {
-        fn daily(self) -> RepeatingDate;
-        fn weekly(self) -> RepeatingDate;
+        assert_eq!(
+            due,
+            vec![
+                date!(2022 - 01 - 11),
+                date!(2022 - 01 - 25),
+                date!(2022 - 02 - 08)
+            ]
+        );
+        assert_eq!(every_other_tuesday.to_string(), "every 2 weeks on Tuesday");
     }
 
-    impl PeriodicExt for usize {
-        fn daily(self) -> RepeatingDate {
-            RepeatingDate::Periodic {
-                amount: self,
-                period: Period::Days,
-            }
-        }
+    #[test]
+    fn rescheduling_reminders() -> Result<()> {
+        use time::Weekday::*;
+        let mut clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
 
-        fn weekly(self) -> RepeatingDate {
-            RepeatingDate::Periodic {
-                amount: self,
-                period: Period::Weeks,
-            }
-        }
-    }
+        reminders.on_date(clock.today(), "Call the bank");
+        reminders.every(&clock, &2.daily(), "Water the plants");
+        reminders.every(&clock, &RepeatingDate::Weekday(Monday), "Plan the week");
 
-    fn reminders() -> (TempDir, Reminders) {
-        let dir = TempDir::new().unwrap();
-        dir.child("reminders.json")
-            .write_str(r#"{"stored": [] }"#)
-            .unwrap();
+        let snooze = Delay::from_str("3.days").unwrap();
+        assert_eq!(
+            reminders.reschedule(1, |date| date + snooze.0)?,
+            date!(2021 - 07 - 18)
+        );
+        reminders.reschedule(2, |_| date!(2021 - 07 - 16))?;
+        assert!(reminders.reschedule(3, |date| date).is_err());
+        assert!(reminders.reschedule(4, |date| date).is_err());
 
-        let reminders = Reminders::load(&dir.path().join("reminders.json")).unwrap();
+        clock.advance_by(1.days());
+        assert_eq!(reminders.for_today(&clock), vec!["Water the plants"]);
 
-        (dir, reminders)
+        clock.advance_by(2.days());
+        assert_eq!(
+            reminders.for_today(&clock),
+            vec!["Call the bank", "Water the plants"]
+        );
+
+        Ok(())
     }
 
     #[test]
-    fn repeating_reminders() -> Result<()> {
-        use time::Weekday::*;
+    fn completed_occurrences_are_skipped() -> Result<()> {
         let mut clock = ControlledClock::new(2021, July, 15)?;
         let (_dir, mut reminders) = reminders();
 
-        clock.advance_to(Monday);
-        reminders.every(&clock, &RepeatingDate::Weekday(Wednesday), "Email someone");
+        reminders.every(&clock, &2.daily(), "Water the plants");
+        reminders.on_date(clock.today(), "Call the bank");
+        reminders.on_date(clock.after(1.days()), "Pay rent");
 
-        clock.advance_to(Wednesday);
-        let todays_reminders = reminders.for_today(&clock);
-        assert_eq!(todays_reminders, vec!["Email someone".to_string()]);
+        assert_eq!(reminders.done(1, clock.today())?, Done::Completed);
+        assert_eq!(reminders.done(2, clock.today())?, Done::Deleted);
+        assert!(reminders.done(2, clock.today()).is_err());
+        assert!(reminders.for_today(&clock).is_empty());
 
-        clock.advance_by(1.days()); // Thursday
-        reminders.every(&clock, &2.weekly(), "Second task");
+        clock.advance_by(2.days());
+        assert_eq!(reminders.for_today(&clock), vec!["Water the plants"]);
 
-        clock.advance_by(1.weeks()); // next Thursday
-        let todays_reminders = reminders.for_today(&clock);
-        assert!(todays_reminders.is_empty());
+        Ok(())
+    }
 
-        clock.advance_by(1.weeks()); // Thursday after that...
-        let todays_reminders = reminders.for_today(&clock);
-        assert_eq!(todays_reminders, vec!["Second task".to_string()]);
+    #[test]
+    fn editing_keeps_the_recurrence() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+
+        reminders.every(&clock, &2.daily(), "Wter the plants");
+        reminders.edit(1, "Water the plants")?;
+
+        assert_eq!(reminders.for_today(&clock), vec!["Water the plants"]);
+        assert!(reminders.edit(2, "Nothing there").is_err());
 
         Ok(())
     }
@@ -595,8 +2335,165 @@ mod tests {
         reminders.on_date(clock.after(4.days()), "Four");
         reminders.on_date(clock.after(4.days()), "Five");
 
-        assert_eq!(reminders.all().len(), 5);
+        assert_eq!(reminders.all(&clock).len(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn computes_the_next_occurrence_of_each_reminder() -> Result<()> {
+        use time::Weekday::*;
+        let mut clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+
+        clock.advance_to(Monday);
+        reminders.every(&clock, &RepeatingDate::Weekday(Wednesday), "One");
+        reminders.every(&clock, &3.daily(), "Two");
+        reminders.on_date(clock.after(1.days()), "Three");
+        reminders.on_date(clock.today() - 1.days(), "Four");
+        reminders.on_date(clock.today(), "Five");
+
+        let mut all = reminders.all(&clock);
+        let next = all.iter().map(|r| r.next.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            next,
+            &[
+                "2021-07-21 (in 2 days)",
+                "today",
+                "tomorrow",
+                "passed",
+                "today"
+            ]
+        );
+
+        by_next_occurrence(&mut all);
+        let order = all.iter().map(|r| r.nr).collect::<Vec<_>>();
+        assert_eq!(order, &[2, 5, 3, 1, 4]);
+
+        clock.advance_by(1.days());
+        let two = &reminders.all(&clock)[1];
+        assert_eq!(two.next, "2021-07-22 (in 2 days)");
+
+        Ok(())
+    }
+
+    #[test]
+    fn important_reminders_come_first() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+
+        reminders
+            .on_date(clock.today(), "Water the plants")
+            .with_priority(Priority::Low);
+        reminders.on_date(clock.today(), "Send the figures");
+        reminders
+            .every(&clock, &1.daily(), "Renew the certificate")
+            .with_priority(Priority::High);
+
+        assert_eq!(
+            reminders.for_today(&clock),
+            vec![
+                "Renew the certificate",
+                "Send the figures",
+                "Water the plants"
+            ]
+        );
+
+        let mut all = reminders.all(&clock);
+        by_next_occurrence(&mut all);
+        let order = all.iter().map(|r| r.nr).collect::<Vec<_>>();
+        assert_eq!(order, &[3, 2, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reminders_with_a_time_come_first_in_order() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+
+        reminders
+            .on_date(clock.today(), "Renew the certificate")
+            .with_priority(Priority::High);
+        reminders
+            .on_date(clock.today(), "Dentist")
+            .at(Some("14:30".parse().unwrap()));
+        reminders
+            .every(&clock, &1.daily(), "Stand-up")
+            .at(Some("09:15".parse().unwrap()));
+
+        assert_eq!(
+            reminders.for_today(&clock),
+            vec![
+                "09:15 — Stand-up",
+                "14:30 — Dentist",
+                "Renew the certificate"
+            ]
+        );
+
+        let saved = serde_json::to_string(&reminders)?;
+        assert!(saved.contains(r#""at":"14:30""#));
+
+        assert!(TimeOfDay::from_str("24:00").is_err());
+        assert!(TimeOfDay::from_str("noon").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reminders_without_a_priority_are_normal() -> Result<()> {
+        let raw = r#"{"stored": [
+            {"concrete": ["2021-07-15", "Send the figures"]},
+            {"concrete": ["2021-07-15", "Renew the certificate"], "priority": "high"}
+        ]}"#;
+
+        let reminders: Reminders = serde_json::from_str(raw)?;
+        assert_eq!(reminders.stored[0].priority, Priority::Normal);
+        assert_eq!(reminders.stored[1].priority, Priority::High);
 
+        let saved = serde_json::to_string(&reminders)?;
+        assert_eq!(
+            saved,
+            r#"{"version":1,"stored":[{"concrete":["2021-07-15","Send the figures"]},{"concrete":["2021-07-15","Renew the certificate"],"priority":"high"}]}"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn upgrades_files_from_before_versioning() -> Result<()> {
+        let dir = TempDir::new()?;
+        let file = dir.child("reminders.json");
+        file.write_str(r#"{"stored": [{"concrete": ["2021-07-15", "Send the figures"]}]}"#)?;
+
+        let reminders = Reminders::load(file.path())?;
+        assert_eq!(reminders.version, CURRENT_VERSION);
+        assert_eq!(reminders.stored.len(), 1);
+
+        reminders.save(file.path())?;
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(file.path())?)?;
+        assert_eq!(saved["version"], CURRENT_VERSION);
+
+        // The version from before saving is kept next to it, and nothing else is left behind
+        dir.child("reminders.json.bak")
+            .assert(r#"{"stored": [{"concrete": ["2021-07-15", "Send the figures"]}]}"#);
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 2);
+
+        // Saving what is already there keeps the backup of the version before it
+        reminders.save(file.path())?;
+        dir.child("reminders.json.bak")
+            .assert(r#"{"stored": [{"concrete": ["2021-07-15", "Send the figures"]}]}"#);
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_files_from_a_newer_version() -> Result<()> {
+        let dir = TempDir::new()?;
+        let file = dir.child("reminders.json");
+        file.write_str(r#"{"version": 99, "stored": []}"#)?;
+
+        let err = Reminders::load(file.path()).err().unwrap();
+        assert!(err.to_string().contains("newer version of journal"));
         Ok(())
     }
 
@@ -613,13 +2510,13 @@ mod tests {
         reminders.on_date(clock.after(4.days()), "Four");
         reminders.on_date(clock.after(4.days()), "Five");
 
-        assert_eq!(reminders.all().len(), 5);
+        assert_eq!(reminders.all(&clock).len(), 5);
 
         reminders.delete(3)?; // should be the "Three"
-        assert_eq!(reminders.all().len(), 4);
+        assert_eq!(reminders.all(&clock).len(), 4);
 
         let existing_reminders = reminders
-            .all()
+            .all(&clock)
             .into_iter()
             .map(|reminders| reminders.reminder)
             .collect::<Vec<_>>();
@@ -632,6 +2529,34 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn deletes_several_reminders_by_number_or_text() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+
+        for text in ["Dentist", "One", "Call the dentist back", "Two", "Three"] {
+            reminders.on_date(clock.today(), text);
+        }
+
+        assert_eq!(reminders.matching("DENTIST"), vec![1, 3]);
+        assert_eq!(reminders.matching("dentst"), vec![1, 3]);
+        assert_eq!(reminders.matching("call dentist"), vec![3]);
+        assert!(reminders.matching("dentists").is_empty());
+        assert!(reminders.delete_all(&[2, 9]).is_err());
+        assert_eq!(reminders.all(&clock).len(), 5);
+
+        reminders.delete_all(&[1, 5, 3, 3])?;
+
+        let remaining = reminders
+            .all(&clock)
+            .into_iter()
+            .map(|reminder| reminder.reminder)
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, &["One", "Two"]);
+
+        Ok(())
+    }
+
     #[test]
     fn reports_when_the_number_to_delete_is_out_of_range() -> Result<()> {
         let clock = ControlledClock::new(2021, July, 15)?;
@@ -645,6 +2570,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn filters_the_list_of_reminders() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+        reminders.on_date(date!(2021 - 07 - 20), "Dentist");
+        reminders.on_date(date!(2021 - 09 - 01), "Renew passport");
+        reminders.every(
+            &clock,
+            &RepeatingDate::Weekday(Weekday::Monday),
+            "Plan the week",
+        );
+
+        let shown = |filter: ListFilter| {
+            reminders
+                .all(&clock)
+                .into_iter()
+                .filter(|reminder| filter.keeps(reminder))
+                .map(|reminder| reminder.reminder)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(shown(ListFilter::default()).len(), 3);
+        assert_eq!(
+            shown(ListFilter {
+                recurring: true,
+                ..Default::default()
+            }),
+            vec!["Plan the week"]
+        );
+        assert_eq!(
+            shown(ListFilter {
+                one_off: true,
+                before: Some(date!(2021 - 08 - 01)),
+                ..Default::default()
+            }),
+            vec!["Dentist"]
+        );
+        assert_eq!(
+            shown(ListFilter {
+                matching: Some("pasport".to_string()),
+                ..Default::default()
+            }),
+            vec!["Renew passport"]
+        );
+        Ok(())
+    }
+
     mod parsing_specific_date {
         use super::*;
         use data_test::data_test;
@@ -663,6 +2635,20 @@ mod tests {
             - short_day_month ("2.Feb",      super::SpecificDate::OnDayMonth(2, time::Month::February))
             - day_month_year ("15.Jan.2022", super::SpecificDate::OnDate(super::date! (2022 - 01 - 15)))
             - weekday ("Wednesday",          super::SpecificDate::Next(super::Weekday::Wednesday))
+            - numeric_month ("15.01.2023",   super::SpecificDate::OnDate(super::date! (2023 - 01 - 15)))
+        }
+
+        #[test]
+        fn parses_names_of_the_configured_locale() {
+            assert_eq!(
+                SpecificDate::parse("3.März", Locale::De),
+                Ok(SpecificDate::OnDayMonth(3, Month::March))
+            );
+            assert_eq!(
+                SpecificDate::parse("mercredi", Locale::Fr),
+                Ok(SpecificDate::Next(Weekday::Wednesday))
+            );
+            assert!(SpecificDate::parse("3.März", Locale::En).is_err());
         }
     }
 
@@ -682,6 +2668,17 @@ mod tests {
             - weekday ("Wednesday", Ok(super::RepeatingDate::Weekday(super::Weekday::Wednesday)))
             - n_days ("2.days", Ok(super::RepeatingDate::Periodic{amount: 2, period: super::Period::Days}))
             - n_weeks ("7.weeks", Ok(super::RepeatingDate::Periodic{amount: 7, period: super::Period::Weeks}))
+            - n_years ("2.years", Ok(super::RepeatingDate::Periodic{amount: 2, period: super::Period::Years}))
+            - every_year ("year", Ok(super::RepeatingDate::Periodic{amount: 1, period: super::Period::Years}))
+            - several_weekdays ("Mon,Wed,Fri", Ok(super::RepeatingDate::Weekdays(vec![super::Weekday::Monday, super::Weekday::Wednesday, super::Weekday::Friday])))
+            - day_of_year ("12.Apr", Ok(super::RepeatingDate::Yearly{day: 12, month: super::Month::April}))
+            - leap_day ("29.Feb", Ok(super::RepeatingDate::Yearly{day: 29, month: super::Month::February}))
+            - impossible_day_of_year ("31.Apr", Err("April has no day 31".into()))
+            - first_weekday_of_month ("1st.Monday", Ok(super::RepeatingDate::Monthly{occurrence: super::Occurrence::Nth(1), weekday: super::Weekday::Monday}))
+            - last_weekday_of_month ("last.Fri", Ok(super::RepeatingDate::Monthly{occurrence: super::Occurrence::Last, weekday: super::Weekday::Friday}))
+            - every_other_weekday ("2.weeks.on.Tuesday", Ok(super::RepeatingDate::WeeklyOn{amount: 2, weekday: super::Weekday::Tuesday}))
+            - no_weeks_on_weekday ("0.weeks.on.Tuesday", Err("every 0 weeks would never come around".into()))
+            - unknown_occurrence ("6th.Monday", Err("unknown occurrence in the month: 6th".into()))
             - negative_amount ("-1.months", Err("invalid digit found in string".into()))
             - unknown_period ("1.fortnights", Err("unknown period: fortnights".into()))
             - missing_separator ("quaselgoop", Err("Unrecognized format for repeating date: quaselgoop".into()))
@@ -721,4 +2718,44 @@ mod tests {
             assert_eq!(date!(2021 - 12 - 08), next_date);
         }
     }
+
+    #[test]
+    fn calendar_subscriptions_are_fetched_over_https() {
+        assert_eq!(
+            remote_url("webcal://example.com/team.ics"),
+            Some("https://example.com/team.ics".to_string())
+        );
+        assert_eq!(
+            remote_url("http://example.com/team.ics"),
+            Some("http://example.com/team.ics".to_string())
+        );
+        assert_eq!(remote_url("/Users/me/Downloads/calendar.ics"), None);
+    }
+
+    #[test]
+    fn knows_reminders_it_already_has() -> Result<()> {
+        let clock = ControlledClock::new(2021, July, 15)?;
+        let (_dir, mut reminders) = reminders();
+        reminders.on_date(date!(2021 - 07 - 20), "Dentist");
+        reminders.every(&clock, &2.daily(), "Water the plants");
+
+        assert!(reminders.contains("Dentist", date!(2021 - 07 - 20)));
+        assert!(reminders.contains("Water the plants", date!(2021 - 07 - 15)));
+        assert!(!reminders.contains("Dentist", date!(2021 - 07 - 21)));
+        assert!(!reminders.contains("Water the flowers", date!(2021 - 07 - 15)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn calendars_on_disk_are_read_directly() -> Result<()> {
+        let dir = TempDir::new()?;
+        let calendar = dir.child("calendar.ics");
+        calendar.write_str("BEGIN:VCALENDAR\nEND:VCALENDAR\n")?;
+
+        let content = read_source(&calendar.path().to_string_lossy()).await?;
+        assert_eq!(content, "BEGIN:VCALENDAR\nEND:VCALENDAR\n");
+
+        assert!(read_source("/does/not/exist.ics").await.is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,189 @@
+use anyhow::Result;
+use handlebars::Handlebars;
+use indoc::indoc;
+use pulldown_cmark::{Event, Parser, Tag};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Section;
+use crate::storage::Journal;
+use crate::template::Heading;
+use crate::Clock;
+
+const SCRATCHPAD: &str = indoc! {r#"
+## Scratchpad
+
+{{scratchpad}}
+"#};
+
+/// A free-form section that is carried over verbatim from one entry to the next
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ScratchpadConfig {
+    template: Option<String>,
+    /// The heading of the scratchpad, set from the `headings` configuration
+    #[serde(skip, default = "Heading::scratchpad")]
+    pub(crate) heading: Heading,
+}
+
+#[async_trait::async_trait]
+impl Section for ScratchpadConfig {
+    async fn render(
+        &self,
+        journal: &Journal,
+        clock: &dyn Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String> {
+        let scratchpad = match journal.entries_until(clock.today())?.pop() {
+            Some(path) => Some(journal.read_entry(path)?),
+            None => None,
+        };
+        let scratchpad = scratchpad
+            .and_then(|entry| {
+                // The latest entry may have been written before the heading was renamed
+                find_section(&entry.markdown, &self.heading)
+                    .or_else(|| find_section(&entry.markdown, &Heading::scratchpad()))
+            })
+            .unwrap_or_default();
+
+        #[derive(Serialize)]
+        struct C {
+            scratchpad: String,
+        }
+
+        let template = self
+            .template
+            .clone()
+            .unwrap_or_else(|| SCRATCHPAD.to_string());
+
+        let mut tt = handlebars.clone();
+        tt.register_template_string("scratchpad", template)?;
+        tt.render("scratchpad", &C { scratchpad })
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+/// Everything between `heading` and the next heading of the same or a higher level
+pub(crate) fn find_section(markdown: &str, heading: &Heading) -> Option<String> {
+    let mut content_start = None;
+    let mut heading_text: Option<String> = None;
+
+    for (event, range) in Parser::new(markdown).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading(level, _, _)) => {
+                if let Some(start) = content_start {
+                    if level as usize <= heading.level() {
+                        return Some(markdown[start..range.start].trim().to_string());
+                    }
+                } else {
+                    heading_text = Some(String::new());
+                }
+            }
+            Event::Text(text) => {
+                if let Some(ref mut heading_text) = heading_text {
+                    heading_text.push_str(&text);
+                }
+            }
+            Event::End(Tag::Heading(level, _, _)) => {
+                let is_section = heading_text
+                    .take()
+                    .map(|text| text.trim() == heading.text())
+                    .unwrap_or(false);
+
+                if is_section && level as usize == heading.level() {
+                    content_start = Some(range.end);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    content_start.map(|start| markdown[start..].trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn finds_the_scratchpad_until_the_next_section() {
+        let markdown = indoc! {r#"
+        # Something on 2022-03-11
+
+        ## Scratchpad
+
+        * staging is at 10.0.0.3
+
+        ### Ideas
+
+        Rewrite it in Rust
+
+        ## TODOs
+
+        * [ ] first
+        "#};
+
+        let scratchpad = find_section(markdown, &Heading::scratchpad()).unwrap();
+
+        assert_eq!(
+            scratchpad,
+            indoc! {r#"
+            * staging is at 10.0.0.3
+
+            ### Ideas
+
+            Rewrite it in Rust"#}
+        );
+    }
+
+    #[test]
+    fn there_is_no_scratchpad() {
+        let markdown = indoc! {r#"
+        # Something on 2022-03-11
+
+        ## TODOs
+
+        * [ ] first
+        "#};
+
+        assert_eq!(find_section(markdown, &Heading::scratchpad()), None);
+    }
+
+    #[tokio::test]
+    async fn carries_the_scratchpad_into_the_next_entry() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("2022-03-11-something.md").write_str(indoc! {r#"
+            # Something on 2022-03-11
+
+            ## Scratchpad
+
+            Remember the milk
+            "#})?;
+
+        let journal = Journal::new_at(dir.path());
+        let clock = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 12)?;
+        let config = ScratchpadConfig {
+            template: None,
+            heading: Heading::scratchpad(),
+        };
+
+        let rendered = config
+            .render(
+                &journal,
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await?;
+
+        assert_eq!(
+            rendered,
+            indoc! {r#"
+            ## Scratchpad
+
+            Remember the milk"#}
+        );
+        Ok(())
+    }
+}
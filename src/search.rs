@@ -0,0 +1,31 @@
+use anyhow::Result;
+use clap::StructOpt;
+
+use crate::index::Scope;
+use crate::storage::{Journal, YEAR_MONTH_DAY};
+
+#[derive(Debug, StructOpt)]
+pub struct SearchCmd {
+    /// What to look for, ignoring case
+    text: String,
+    /// Where to look: `text`, `headings`, open `todos`, or `reminders`
+    #[clap(long = "in", default_value = "text")]
+    scope: Scope,
+}
+
+impl SearchCmd {
+    pub(crate) fn execute(&self, journal: &Journal) -> Result<()> {
+        let hits = journal.search(&self.text, self.scope)?;
+        if hits.is_empty() {
+            println!("Nothing mentions '{}'", self.text);
+        }
+        for hit in hits {
+            let date = match hit.date {
+                Some(date) => date.format(YEAR_MONTH_DAY)?,
+                None => hit.path.to_string_lossy().to_string(),
+            };
+            println!("{}  {}", date, hit.line);
+        }
+        Ok(())
+    }
+}
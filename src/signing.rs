@@ -0,0 +1,279 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use clap::StructOpt;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use tabled::{Table, Tabled};
+
+use crate::accessibility;
+use crate::auth::Token;
+use crate::storage::Journal;
+use crate::Config;
+
+/// Signs entries once they are sealed, i.e. once the next entry is created,
+/// so that later changes to them can be detected
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SigningConfig {
+    tool: Tool,
+    /// The GPG key id, or the path to the minisign secret key
+    key: Option<String>,
+    /// The minisign public key to verify signatures with
+    public_key: Option<PathBuf>,
+    #[serde(default)]
+    passphrase: Option<Token>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Tool {
+    Gpg,
+    Minisign,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct VerifyCmd {
+    /// Check that every sealed entry has a valid signature
+    #[clap(long)]
+    signatures: bool,
+}
+
+impl VerifyCmd {
+    pub fn execute(&self, config: &Config, journal: &Journal) -> Result<()> {
+        if !self.signatures {
+            bail!("Nothing to verify, pass --signatures to check the signatures of your entries");
+        }
+
+        let signing = match config.signing() {
+            Some(signing) => signing,
+            None => bail!("Signing is not configured"),
+        };
+
+        let results = verify_chain(&journal.entries()?, |entry| signing.verify(entry))?;
+
+        #[derive(Tabled)]
+        struct Row {
+            entry: String,
+            signature: String,
+        }
+        let rows = results
+            .iter()
+            .map(|(entry, status)| Row {
+                entry: entry.to_string_lossy().to_string(),
+                signature: status.to_string(),
+            })
+            .collect::<Vec<_>>();
+        println!("{}", accessibility::styled(Table::new(&rows), config.ascii));
+
+        let broken = results
+            .iter()
+            .filter(|(_, status)| matches!(status, Status::Invalid | Status::Missing))
+            .count();
+        if broken > 0 {
+            bail!("{} entries have missing or invalid signatures", broken);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Valid,
+    Invalid,
+    Missing,
+    /// The latest entry is still being written and isn't signed yet
+    Unsealed,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Valid => f.write_str("valid"),
+            Status::Invalid => f.write_str("INVALID"),
+            Status::Missing => f.write_str("MISSING"),
+            Status::Unsealed => f.write_str("not sealed yet"),
+        }
+    }
+}
+
+pub(crate) fn signature_of(entry: &Path) -> PathBuf {
+    let mut signature = entry.as_os_str().to_owned();
+    signature.push(".sig");
+    PathBuf::from(signature)
+}
+
+/// Checks every entry, oldest first. Only the latest entry may be unsigned.
+pub fn verify_chain(
+    entries: &[PathBuf],
+    verify: impl Fn(&Path) -> Result<bool>,
+) -> Result<Vec<(PathBuf, Status)>> {
+    let mut results = Vec::new();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let is_latest = index + 1 == entries.len();
+
+        let status = if !signature_of(entry).exists() {
+            if is_latest {
+                Status::Unsealed
+            } else {
+                Status::Missing
+            }
+        } else if verify(entry)? {
+            Status::Valid
+        } else {
+            Status::Invalid
+        };
+
+        results.push((entry.clone(), status));
+    }
+
+    Ok(results)
+}
+
+impl SigningConfig {
+    pub(crate) fn passphrase(&self) -> Option<&Token> {
+        self.passphrase.as_ref()
+    }
+
+    /// Signs all entries that don't have a signature yet
+    pub fn seal(&self, journal: &Journal) -> Result<usize> {
+        let mut sealed = 0;
+        for entry in journal.entries()? {
+            if !signature_of(&entry).exists() {
+                self.sign(&entry)?;
+                sealed += 1;
+            }
+        }
+        Ok(sealed)
+    }
+
+    fn sign(&self, entry: &Path) -> Result<()> {
+        let signature = signature_of(entry);
+
+        let mut command = match self.tool {
+            Tool::Gpg => {
+                let mut command = Command::new("gpg");
+                command.args(["--batch", "--yes", "--detach-sign"]);
+                if let Some(ref key) = self.key {
+                    command.args(["--local-user", key]);
+                }
+                if self.passphrase.is_some() {
+                    command.args(["--pinentry-mode", "loopback", "--passphrase-fd", "0"]);
+                }
+                command.arg("--output").arg(&signature).arg(entry);
+                command
+            }
+            Tool::Minisign => {
+                let mut command = Command::new("minisign");
+                command.arg("-S");
+                if let Some(ref key) = self.key {
+                    command.args(["-s", key]);
+                }
+                command.arg("-m").arg(entry).arg("-x").arg(&signature);
+                command
+            }
+        };
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Could not run {:?} to sign entries", self.tool))?;
+
+        if let (Some(passphrase), Some(mut stdin)) = (&self.passphrase, child.stdin.take()) {
+            writeln!(stdin, "{}", passphrase.resolve()?.expose_secret())?;
+        }
+
+        if !child.wait()?.success() {
+            bail!("Failed to sign {:?}", entry);
+        }
+
+        tracing::info!("Signed {:?}", entry);
+        Ok(())
+    }
+
+    fn verify(&self, entry: &Path) -> Result<bool> {
+        let signature = signature_of(entry);
+
+        let mut command = match self.tool {
+            Tool::Gpg => {
+                let mut command = Command::new("gpg");
+                command
+                    .args(["--batch", "--verify"])
+                    .arg(&signature)
+                    .arg(entry);
+                command
+            }
+            Tool::Minisign => {
+                let public_key = match self.public_key {
+                    Some(ref public_key) => public_key,
+                    None => bail!("Verifying minisign signatures needs a 'public_key'"),
+                };
+                let mut command = Command::new("minisign");
+                command
+                    .arg("-V")
+                    .arg("-p")
+                    .arg(public_key)
+                    .arg("-m")
+                    .arg(entry)
+                    .arg("-x")
+                    .arg(&signature);
+                command
+            }
+        };
+
+        let status = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .with_context(|| format!("Could not run {:?} to verify entries", self.tool))?;
+
+        Ok(status.success())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::{prelude::*, TempDir};
+
+    #[test]
+    fn only_the_latest_entry_may_be_unsigned() -> Result<()> {
+        let dir = TempDir::new()?;
+        for name in ["2022-03-09-a.md", "2022-03-10-b.md", "2022-03-11-c.md"] {
+            dir.child(name).write_str("content")?;
+        }
+        dir.child("2022-03-09-a.md.sig").write_str("good")?;
+
+        let journal = Journal::new_at(dir.path());
+        let results = verify_chain(&journal.entries()?, |_| Ok(true))?;
+
+        let statuses = results
+            .into_iter()
+            .map(|(_, status)| status)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![Status::Valid, Status::Missing, Status::Unsealed]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_entries_are_invalid() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("2022-03-09-a.md").write_str("content")?;
+        dir.child("2022-03-09-a.md.sig").write_str("good")?;
+
+        let journal = Journal::new_at(dir.path());
+        let results = verify_chain(&journal.entries()?, |_| Ok(false))?;
+
+        assert_eq!(results[0].1, Status::Invalid);
+        Ok(())
+    }
+}
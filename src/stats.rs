@@ -0,0 +1,181 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::Result;
+use clap::StructOpt;
+use tabled::{Table, Tabled};
+use time::Date;
+
+use crate::accessibility;
+use crate::post_process::outside_of_code;
+use crate::storage::{Entry, Journal};
+
+#[derive(Debug, StructOpt)]
+pub struct StatsCmd {}
+
+#[derive(Debug, PartialEq)]
+struct Stats {
+    entries: usize,
+    current_streak: usize,
+    longest_streak: usize,
+    average_words: usize,
+    /// How many entries have each `## Section`, the most common first
+    sections: Vec<(String, usize)>,
+}
+
+#[derive(Tabled)]
+struct Row {
+    statistic: &'static str,
+    value: String,
+}
+
+#[derive(Tabled)]
+struct SectionRow {
+    section: String,
+    entries: usize,
+    share: String,
+}
+
+impl StatsCmd {
+    pub fn execute(&self, journal: &Journal, today: Date, ascii: bool) -> Result<()> {
+        let stats = stats(&journal.read_entries()?, today);
+
+        let days = |n: usize| format!("{} day{}", n, if n == 1 { "" } else { "s" });
+        let rows = vec![
+            Row {
+                statistic: "entries",
+                value: stats.entries.to_string(),
+            },
+            Row {
+                statistic: "current streak",
+                value: days(stats.current_streak),
+            },
+            Row {
+                statistic: "longest streak",
+                value: days(stats.longest_streak),
+            },
+            Row {
+                statistic: "average length",
+                value: format!("{} words", stats.average_words),
+            },
+        ];
+        println!("{}", accessibility::styled(Table::new(&rows), ascii));
+
+        if !stats.sections.is_empty() {
+            let sections: Vec<_> = stats
+                .sections
+                .into_iter()
+                .map(|(section, entries)| SectionRow {
+                    share: format!("{}%", entries * 100 / stats.entries),
+                    section,
+                    entries,
+                })
+                .collect();
+            println!("{}", accessibility::styled(Table::new(&sections), ascii));
+        }
+        Ok(())
+    }
+}
+
+fn stats(entries: &[Entry], today: Date) -> Stats {
+    let dates: BTreeSet<Date> = entries.iter().filter_map(Entry::date).collect();
+
+    let mut longest_streak = 0;
+    let mut streak = 0;
+    let mut previous: Option<Date> = None;
+    for date in &dates {
+        streak = match previous {
+            Some(previous) if previous.next_day() == Some(*date) => streak + 1,
+            _ => 1,
+        };
+        longest_streak = longest_streak.max(streak);
+        previous = Some(*date);
+    }
+
+    // Today's entry may not be written yet, so the streak is only broken after a full day without one
+    let mut current_streak = 0;
+    let mut day = if dates.contains(&today) {
+        Some(today)
+    } else {
+        today.previous_day()
+    };
+    while let Some(date) = day.filter(|date| dates.contains(date)) {
+        current_streak += 1;
+        day = date.previous_day();
+    }
+
+    let words: usize = entries
+        .iter()
+        .map(|entry| {
+            entry
+                .markdown
+                .split_whitespace()
+                .filter(|word| word.chars().any(char::is_alphanumeric))
+                .count()
+        })
+        .sum();
+
+    let mut sections: HashMap<String, usize> = HashMap::new();
+    for entry in entries {
+        let mut headings = BTreeSet::new();
+        outside_of_code(&entry.markdown, |line| {
+            if let Some(heading) = line.strip_prefix("## ") {
+                headings.insert(heading.trim().to_string());
+            }
+            line.to_string()
+        });
+        for heading in headings {
+            *sections.entry(heading).or_default() += 1;
+        }
+    }
+    let mut sections: Vec<_> = sections.into_iter().collect();
+    sections.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+    Stats {
+        entries: entries.len(),
+        current_streak,
+        longest_streak,
+        average_words: words.checked_div(entries.len()).unwrap_or(0),
+        sections,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use time::macros::date;
+
+    fn entry(name: &str, markdown: &str) -> Entry {
+        Entry {
+            path: PathBuf::from(name),
+            markdown: markdown.to_string(),
+        }
+    }
+
+    #[test]
+    fn counts_streaks_and_sections() {
+        let entries = vec![
+            entry("2022-03-01-a.md", "## Notes\n\nOne two three\n"),
+            entry("2022-03-02-b.md", "## Notes\n\n## TODOs\n"),
+            entry(
+                "2022-03-03-c.md",
+                "## TODOs\n\n```\n## not a section\n```\n",
+            ),
+            entry("2022-03-09-d.md", "## Notes\n"),
+            entry("2022-03-10-e.md", "## Notes\n"),
+        ];
+
+        assert_eq!(
+            stats(&entries, date!(2022 - 03 - 11)),
+            Stats {
+                entries: 5,
+                current_streak: 2,
+                longest_streak: 3,
+                average_words: 2,
+                sections: vec![("Notes".to_string(), 4), ("TODOs".to_string(), 2)],
+            }
+        );
+        assert_eq!(stats(&entries, date!(2022 - 03 - 12)).current_streak, 0);
+        assert_eq!(stats(&[], date!(2022 - 03 - 12)).average_words, 0);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderError};
+use serde::{Deserialize, Serialize};
+use tabled::{Table, Tabled};
+
+use crate::accessibility;
+
+/// A status marker like 🔥 that can be used in any template via `{{status "urgent"}}`
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Status {
+    pub emoji: String,
+    /// Shown instead of the emoji in ASCII mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ascii: Option<String>,
+    pub meaning: String,
+}
+
+/// The configured status conventions, by name
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Statuses(BTreeMap<String, Status>);
+
+impl Default for Statuses {
+    fn default() -> Self {
+        let status = |emoji: &str, ascii: &str, meaning: &str| Status {
+            emoji: emoji.to_string(),
+            ascii: Some(ascii.to_string()),
+            meaning: meaning.to_string(),
+        };
+
+        Statuses(BTreeMap::from([
+            (
+                "urgent".to_string(),
+                status("🔥", "!!", "Urgent, needs attention today"),
+            ),
+            (
+                "blocked".to_string(),
+                status("🧊", "(blocked)", "Blocked on someone or something"),
+            ),
+            ("done".to_string(), status("✅", "(done)", "Done")),
+        ]))
+    }
+}
+
+impl Status {
+    /// The emoji, or a plain ASCII marker for screen readers and limited terminals
+    fn marker(&self, name: &str, ascii: bool) -> String {
+        match (ascii, &self.ascii) {
+            (false, _) => self.emoji.clone(),
+            (true, Some(marker)) => marker.clone(),
+            (true, None) => format!("({})", name),
+        }
+    }
+}
+
+impl Statuses {
+    pub fn get(&self, name: &str) -> Option<&Status> {
+        self.0.get(name)
+    }
+
+    pub fn legend(&self, ascii: bool) -> Table {
+        #[derive(Tabled)]
+        struct Row<'a> {
+            name: &'a str,
+            status: String,
+            meaning: &'a str,
+        }
+
+        let rows = self
+            .0
+            .iter()
+            .map(|(name, status)| Row {
+                name,
+                status: status.marker(name, ascii),
+                meaning: &status.meaning,
+            })
+            .collect::<Vec<_>>();
+
+        accessibility::styled(Table::new(&rows), ascii)
+    }
+
+    /// Registers the `status` helper, which turns a status name into its emoji
+    pub fn register_helper(&self, handlebars: &mut Handlebars<'static>, ascii: bool) {
+        let statuses = self.clone();
+
+        handlebars.register_helper(
+            "status",
+            Box::new(
+                move |h: &Helper,
+                      _: &Handlebars,
+                      _: &Context,
+                      _: &mut RenderContext,
+                      out: &mut dyn Output|
+                      -> HelperResult {
+                    let name = h
+                        .param(0)
+                        .and_then(|param| param.value().as_str())
+                        .ok_or_else(|| RenderError::new("`status` needs the name of a status"))?;
+
+                    match statuses.get(name) {
+                        Some(status) => out.write(&status.marker(name, ascii))?,
+                        None => return Err(RenderError::new(format!("Unknown status '{}'", name))),
+                    }
+                    Ok(())
+                },
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn templates_can_use_the_configured_statuses() {
+        let statuses: Statuses = serde_yaml::from_str(indoc! {r#"
+        waiting:
+          emoji: "⏳"
+          meaning: Waiting for a review
+        "#})
+        .unwrap();
+
+        let mut handlebars = Handlebars::new();
+        statuses.register_helper(&mut handlebars, false);
+
+        let rendered = handlebars
+            .render_template(r#"{{status "waiting"}} Merge the PR"#, &())
+            .unwrap();
+        assert_eq!(rendered, "⏳ Merge the PR");
+
+        assert!(handlebars
+            .render_template(r#"{{status "urgent"}}"#, &())
+            .is_err());
+    }
+
+    #[test]
+    fn the_legend_lists_all_statuses() {
+        let legend = Statuses::default().legend(false).to_string();
+
+        assert!(legend.contains("🔥"));
+        assert!(legend.contains("Blocked on someone or something"));
+    }
+
+    #[test]
+    fn ascii_mode_uses_plain_markers() {
+        let mut handlebars = Handlebars::new();
+        Statuses::default().register_helper(&mut handlebars, true);
+
+        let rendered = handlebars
+            .render_template(r#"{{status "urgent"}} Fix the build"#, &())
+            .unwrap();
+        assert_eq!(rendered, "!! Fix the build");
+
+        assert!(Statuses::default().legend(true).to_string().is_ascii());
+    }
+}
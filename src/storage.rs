@@ -1,43 +1,518 @@
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{bail, Context, Result};
+use glob::Pattern;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use time::format_description::FormatItem;
+use time::{Date, Month};
+
+use crate::index::{Hit, Index, Scope};
+use crate::init::expand_home;
+
+pub(crate) const YEAR_MONTH_DAY: &[FormatItem] =
+    time::macros::format_description!("[year]-[month]-[day]");
+
+/// Reads a date like 2022-03-11 given on the command line
+pub(crate) fn parse_date(raw: &str) -> Result<Date, String> {
+    Date::parse(raw, YEAR_MONTH_DAY).map_err(|e| e.to_string())
+}
+
+/// Writes `data` to a temporary file next to `path` first and then moves it into place,
+/// so a crash halfway through leaves the previous version instead of a truncated file
+pub(crate) fn write_atomically(path: &Path, data: &[u8]) -> Result<()> {
+    let name = path
+        .file_name()
+        .with_context(|| format!("{:?} is not a file", path))?;
+    let temporary = path.with_file_name(format!(".{}.tmp", name.to_string_lossy()));
+
+    let mut file = std::fs::File::create(&temporary)
+        .with_context(|| format!("Could not create {:?}", temporary))?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    std::fs::rename(&temporary, path)
+        .with_context(|| format!("Could not move {:?} to {:?}", temporary, path))
+}
+
+/// How the date at the beginning of an entry's filename is written.
+/// Entries with any of them can be read, whichever one is configured.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DateStamp {
+    #[default]
+    #[serde(rename = "YYYY-MM-DD")]
+    YearMonthDay,
+    #[serde(rename = "YYYYMMDD")]
+    Compact,
+    #[serde(rename = "DD-MM-YYYY")]
+    DayMonthYear,
+}
+
+impl DateStamp {
+    const ALL: [DateStamp; 3] = [
+        DateStamp::YearMonthDay,
+        DateStamp::Compact,
+        DateStamp::DayMonthYear,
+    ];
+
+    fn format(&self) -> &'static [FormatItem<'static>] {
+        match self {
+            DateStamp::YearMonthDay => YEAR_MONTH_DAY,
+            DateStamp::Compact => time::macros::format_description!("[year][month][day]"),
+            DateStamp::DayMonthYear => time::macros::format_description!("[day]-[month]-[year]"),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            DateStamp::YearMonthDay | DateStamp::DayMonthYear => 10,
+            DateStamp::Compact => 8,
+        }
+    }
+
+    /// The filename for an entry on `date` with an already normalized `title`
+    pub fn filename(&self, date: Date, title: &str) -> String {
+        format!("{}-{}.md", date.format(self.format()).unwrap(), title)
+    }
+
+    /// Where an entry on `date` with an already normalized `slug` goes, following a `pattern` like
+    /// `{year}/{month}/{date}-{slug}.md`. Entries go straight into the journal without one.
+    pub fn path(&self, pattern: Option<&str>, date: Date, slug: &str) -> Result<String> {
+        let pattern = match pattern {
+            Some(pattern) => pattern,
+            None => return Ok(self.filename(date, slug)),
+        };
+
+        let has_date = pattern.contains("{date}")
+            || ["{year}", "{month}", "{day}"]
+                .iter()
+                .all(|part| pattern.contains(part));
+        if !has_date || !pattern.ends_with(".md") {
+            bail!(
+                "The filename '{}' needs to end in '.md' and contain either {{date}} or {{year}}, {{month}}, and {{day}}",
+                pattern
+            );
+        }
+        if pattern.starts_with('/') || pattern.split('/').any(|part| part == "..") {
+            bail!("The filename '{}' has to stay within the journal", pattern);
+        }
+
+        let path = pattern
+            .replace("{date}", &date.format(self.format())?)
+            .replace("{year}", &date.year().to_string())
+            .replace("{month}", &format!("{:02}", date.month() as u8))
+            .replace("{day}", &format!("{:02}", date.day()))
+            .replace("{slug}", slug);
+        if let Some(unknown) = Regex::new(r"\{[^}]*\}").unwrap().find(&path) {
+            bail!(
+                "The filename '{}' contains {}, use {{date}}, {{year}}, {{month}}, {{day}}, or {{slug}}",
+                pattern,
+                unknown.as_str()
+            );
+        }
+        // Entries are found by the date they start with or the folders they are in
+        if date_from_filename(Path::new(&path)) != Some(date) {
+            bail!(
+                "The filename '{}' has to start with {{date}}, be in a {{date}} folder, or be in {{year}}/{{month}} folders \
+                 and start with {{day}}, otherwise the date of the entry can't be read back",
+                pattern
+            );
+        }
+        Ok(path)
+    }
+
+    /// The date a filename starts with and whatever follows it
+    fn parse<'a>(&self, name: &'a str) -> Option<(Date, &'a str)> {
+        let stamp = name.get(0..self.len())?;
+        let rest = &name[self.len()..];
+        if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let date = Date::parse(stamp, self.format()).ok()?;
+        Some((date, rest))
+    }
+
+    /// Reads the date with whichever stamp the filename uses
+    fn parse_any(name: &str) -> Option<(DateStamp, Date, &str)> {
+        DateStamp::ALL
+            .iter()
+            .find_map(|stamp| stamp.parse(name).map(|(date, rest)| (*stamp, date, rest)))
+    }
+}
+
+/// What an entry is about, kept as YAML between two `---` lines at its very top
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct FrontMatter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<Date>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+impl FrontMatter {
+    /// The front matter of `markdown` and the rest of it, if it starts with any
+    pub fn parse(markdown: &str) -> Option<(FrontMatter, &str)> {
+        let rest = markdown.strip_prefix("---\n")?;
+        let (yaml, body) = match rest.split_once("\n---\n") {
+            Some(split) => split,
+            None => (rest.strip_suffix("\n---")?, ""),
+        };
+        let front_matter = serde_yaml::from_str(yaml)
+            .map_err(|e| tracing::warn!("Could not read the front matter: {}", e))
+            .ok()?;
+        Some((front_matter, body.trim_start_matches('\n')))
+    }
+
+    /// The front matter to put at the top of an entry
+    pub fn render(&self) -> Result<String> {
+        let yaml = serde_yaml::to_string(self)?;
+        Ok(format!(
+            "---\n{}---\n\n",
+            yaml.strip_prefix("---\n").unwrap_or(&yaml)
+        ))
+    }
+}
 
 pub struct Entry {
+    pub path: PathBuf,
     pub markdown: String,
 }
 
+impl Entry {
+    /// The date in the entry's front matter, or at the beginning of its filename
+    pub fn date(&self) -> Option<Date> {
+        self.front_matter()
+            .and_then(|front_matter| front_matter.date)
+            .or_else(|| date_from_filename(&self.path))
+    }
+
+    /// The entry's front matter, if it has any
+    pub fn front_matter(&self) -> Option<FrontMatter> {
+        FrontMatter::parse(&self.markdown).map(|(front_matter, _)| front_matter)
+    }
+
+    /// The markdown after the front matter
+    pub fn body(&self) -> &str {
+        match FrontMatter::parse(&self.markdown) {
+            Some((_, body)) => body,
+            None => &self.markdown,
+        }
+    }
+
+    /// The tags in the entry's front matter
+    pub fn tags(&self) -> Vec<String> {
+        self.front_matter()
+            .map(|front_matter| front_matter.tags)
+            .unwrap_or_default()
+    }
+
+    /// The title the entry was created with, taken from its front matter or its `# Title on 2022-03-11` heading.
+    /// Entries without either fall back to the title in their filename.
+    pub fn title(&self) -> String {
+        if let Some(title) = self
+            .front_matter()
+            .and_then(|front_matter| front_matter.title)
+        {
+            return title;
+        }
+
+        let heading = self
+            .body()
+            .lines()
+            .find_map(|line| line.strip_prefix("# "))
+            .map(str::trim);
+
+        match heading {
+            Some(heading) => match heading.rsplit_once(" on ") {
+                // The date may be written with a `heading_date_format`, which usually includes the year
+                Some((title, date))
+                    if Date::parse(date, YEAR_MONTH_DAY).is_ok()
+                        || self
+                            .date()
+                            .is_some_and(|day| date.contains(&day.year().to_string())) =>
+                {
+                    title.to_string()
+                }
+                _ => heading.to_string(),
+            },
+            None => title_from_filename(&self.path),
+        }
+    }
+
+    /// A short human readable description of the entry, used in messages
+    pub fn describe(&self) -> String {
+        match self.date() {
+            Some(date) => date.format(YEAR_MONTH_DAY).unwrap(),
+            None => self.path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// The date of an entry, from the beginning of its filename like `2022-03-11-title.md`,
+/// or from the folders it was put in with a `filename` like `{date}/{slug}.md` or `{year}/{month}/{day}.md`
+pub(crate) fn date_from_filename(path: &Path) -> Option<Date> {
+    let name = path.file_name()?.to_str()?;
+    if let Some((_, date, _)) = DateStamp::parse_any(name) {
+        return Some(date);
+    }
+
+    let parent = path.parent()?;
+    let folder = parent.file_name()?.to_str()?;
+    if let Some((_, date, "")) = DateStamp::parse_any(folder) {
+        return Some(date);
+    }
+
+    let year = parent.parent()?.file_name()?.to_str()?;
+    let day = name.get(0..2)?;
+    if year.len() != 4 || folder.len() != 2 || name[2..].starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let month = Month::try_from(folder.parse::<u8>().ok()?).ok()?;
+    Date::from_calendar_date(year.parse().ok()?, month, day.parse().ok()?).ok()
+}
+
+/// How a markdown file in `dir` links to `target`, like `../2022/03/11.md`
+pub(crate) fn link(dir: &Path, target: &Path) -> String {
+    let from = normalized(dir);
+    let to = normalized(target);
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|part| part.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+/// The parts of `path` without any `.` and `..`, without looking at the file system
+pub(crate) fn normalized(path: &Path) -> Vec<Component<'_>> {
+    let mut parts = Vec::new();
+    for part in path.components() {
+        match part {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(parts.last(), Some(Component::Normal(_))) => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts
+}
+
+/// The filename `path` would have with a different date `stamp`, if it isn't already using it
+pub(crate) fn restamped(path: &Path, stamp: DateStamp) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    match DateStamp::parse_any(name)? {
+        (current, _, _) if current == stamp => None,
+        (_, date, rest) => {
+            let name = format!("{}{}", date.format(stamp.format()).ok()?, rest);
+            Some(path.with_file_name(name))
+        }
+    }
+}
+
+/// Best effort for older entries: `2022-03-11-through-the-looking-glass.md` becomes "through the looking glass"
+fn title_from_filename(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let slug = match DateStamp::parse_any(&stem) {
+        Some((_, _, rest)) => rest,
+        None => &stem,
+    };
+
+    slug.trim_start_matches('-').replace(['-', '_'], " ")
+}
+
+/// Where `journal archive` moves old entries to, in a folder per year
+const ARCHIVE: &str = "archive";
+
 pub struct Journal {
     location: PathBuf,
+    ignore: Vec<Pattern>,
+    with_archive: bool,
+    index: Option<Index>,
 }
 
 impl Journal {
     pub fn new_at<P: Into<PathBuf>>(location: P) -> Journal {
         Journal {
             location: location.into(),
+            ignore: Vec::new(),
+            with_archive: false,
+            index: None,
         }
     }
 
-    pub fn latest_entry(&self) -> Result<Option<Entry>> {
-        // Would still need a filter that matches naming convention
-        let mut entries = std::fs::read_dir(&self.location)?
-            .map(|res| res.map(|e| e.path()).unwrap())
-            .filter(|path| {
-                if let Some(ext) = path.extension() {
-                    ext == "md"
-                } else {
-                    false
+    /// Keep what the entries contain in the SQLite index in `file`, so they are only read again once they changed
+    pub fn indexed(mut self, file: Option<&Path>) -> Result<Journal> {
+        if let Some(file) = file {
+            self.index = Some(Index::open(file)?);
+        }
+        Ok(self)
+    }
+
+    /// Also look at the entries in the `archive/<year>/` folders, which are left out otherwise
+    pub fn including_archive(mut self, with_archive: bool) -> Journal {
+        self.with_archive = with_archive;
+        self
+    }
+
+    /// Skip files and folders whose path in the journal matches any of the glob `patterns`
+    pub fn ignoring(mut self, patterns: &[String]) -> Result<Journal> {
+        for pattern in patterns {
+            let pattern = Pattern::new(pattern)
+                .with_context(|| format!("Invalid pattern to ignore: {}", pattern))?;
+            self.ignore.push(pattern);
+        }
+        Ok(self)
+    }
+
+    /// All markdown entries in the journal, oldest first.
+    /// Only files named like `2022-03-11-title.md` are entries, so a `README.md` is left alone.
+    pub fn entries(&self) -> Result<Vec<PathBuf>> {
+        let mut entries = Vec::new();
+        self.entries_in(&self.location, &mut entries)?;
+
+        // The order in which `read_dir` returns entries is not guaranteed, and with stamps
+        // like `DD-MM-YYYY` the filenames don't sort by date either
+        entries.sort_by_key(|path| (date_from_filename(path), path.clone()));
+
+        Ok(entries)
+    }
+
+    /// Looks through `dir` and its folders, as entries can be kept in folders with a `filename` pattern
+    fn entries_in(&self, dir: &Path, entries: &mut Vec<PathBuf>) -> Result<()> {
+        for path in std::fs::read_dir(dir)? {
+            let path = path?.path();
+            if path.is_dir() {
+                let hidden = path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+                if !hidden
+                    && (self.with_archive || path != self.location.join(ARCHIVE))
+                    && !self.is_ignored(&path, true)
+                {
+                    self.entries_in(&path, entries)?;
                 }
-            })
-            .collect::<Vec<_>>();
+            } else if path.extension().is_some_and(|ext| ext == "md")
+                && date_from_filename(&path).is_some()
+                && !self.is_ignored(&path, false)
+            {
+                entries.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Where `entry` from `year` goes in the archive, keeping any folders it is in
+    pub(crate) fn archived(&self, entry: &Path, year: i32) -> PathBuf {
+        let relative = entry
+            .strip_prefix(&self.location)
+            .ok()
+            .or_else(|| entry.file_name().map(Path::new))
+            .unwrap_or(entry);
+        self.location
+            .join(ARCHIVE)
+            .join(year.to_string())
+            .join(relative)
+    }
+
+    pub(crate) fn is_archived(&self, path: &Path) -> bool {
+        path.starts_with(self.location.join(ARCHIVE))
+    }
+
+    /// The entries whose filename starts with the given date
+    pub fn entries_on(&self, date: Date) -> Result<Vec<PathBuf>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|path| date_from_filename(path) == Some(date))
+            .collect())
+    }
 
-        // The order in which `read_dir` returns entries is not guaranteed. If reproducible
-        // ordering is required the entries should be explicitly sorted.
-        entries.sort();
+    /// The entries written on `date` or before it, oldest first, e.g. to carry over from when backfilling an earlier day
+    pub fn entries_until(&self, date: Date) -> Result<Vec<PathBuf>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|path| date_from_filename(path).is_none_or(|day| day <= date))
+            .collect())
+    }
+
+    /// Whether one of the patterns matches `path` relative to the journal, like `drafts/2022-03-11-idea.md`.
+    /// A folder is also ignored by patterns for everything in it, like `drafts/*`.
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = path
+            .strip_prefix(&self.location)
+            .unwrap_or(path)
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.ignore.iter().any(|pattern| {
+            pattern.matches(&relative) || (is_dir && pattern.matches(&format!("{}/", relative)))
+        })
+    }
+
+    pub fn read_entry(&self, path: PathBuf) -> Result<Entry> {
+        if let Some(ref index) = self.index {
+            return index.read(path);
+        }
+        let markdown = std::fs::read_to_string(&path)?;
+        Ok(Entry { path, markdown })
+    }
+
+    /// Every entry, oldest first
+    pub(crate) fn read_entries(&self) -> Result<Vec<Entry>> {
+        let entries = self.entries()?;
+        if let Some(ref index) = self.index {
+            index.forget_all_but(&self.location, &entries)?;
+        }
+        entries
+            .into_iter()
+            .map(|path| self.read_entry(path))
+            .collect()
+    }
+
+    /// The lines in the `scope` of the entries that contain `text`, ignoring case.
+    /// Journals without an index get one just for the search.
+    pub(crate) fn search(&self, text: &str, scope: Scope) -> Result<Vec<Hit>> {
+        let temporary;
+        let index = match self.index {
+            Some(ref index) => index,
+            None => {
+                temporary = Index::in_memory()?;
+                &temporary
+            }
+        };
+        index.refresh(&self.location, &self.entries()?)?;
+        index.search(text, scope)
+    }
+
+    /// The entry written on `date`, the last one if there are several
+    pub fn entry_for(&self, date: Date) -> Result<Option<Entry>> {
+        match self.entries_on(date)?.pop() {
+            Some(path) => Ok(Some(self.read_entry(path)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The entry with the most recent date, regardless of how the files sort by name
+    pub fn latest_entry(&self) -> Result<Option<Entry>> {
+        let mut entries = self.entries()?;
 
         if let Some(path) = entries.pop() {
-            let markdown = std::fs::read_to_string(&path)?;
             tracing::info!("Lastest entry found at {:?}", path);
 
-            Ok(Some(Entry { markdown }))
+            Ok(Some(self.read_entry(path)?))
         } else {
             tracing::info!(
                 "No journal entries found in {}",
@@ -48,14 +523,25 @@ impl Journal {
         }
     }
 
+    #[tracing::instrument(err, name = "Writing entry", skip(self, data))]
     pub fn add_entry(&self, name: &str, data: &str) -> Result<PathBuf> {
         let path = self.location.join(name);
-        std::fs::write(&path, data)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Could not create {:?}", dir))?;
+        }
+        write_atomically(&path, data.as_bytes())?;
+        if let Some(ref index) = self.index {
+            // The index catches up the next time the entry is read
+            if let Err(e) = index.read(path.clone()) {
+                tracing::warn!("Could not index {:?}: {:#}", path, e);
+            }
+        }
         Ok(path)
     }
 
-    pub(crate) fn child_file(&self, child: &str) -> PathBuf {
-        self.location.join(child)
+    /// A file in the journal, or anywhere else when `child` is absolute or starts with `~/`
+    pub(crate) fn child_file<P: AsRef<Path>>(&self, child: P) -> PathBuf {
+        self.location.join(expand_home(child))
     }
 }
 
@@ -90,6 +576,8 @@ mod tests {
         assert!(entry.is_ok());
         let entry = entry.unwrap().unwrap();
         assert_eq!(entry.markdown, "first content");
+        assert_eq!(entry.date(), Some(time::macros::date!(2021 - 08 - 23)));
+        assert_eq!(entry.describe(), "2021-08-23");
     }
 
     #[test]
@@ -127,4 +615,251 @@ mod tests {
         let entry = entry.unwrap().unwrap();
         assert_eq!(entry.markdown, "real content");
     }
+
+    #[test]
+    fn ignores_files_that_are_not_entries() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("2021-07-03-older_entry.md")
+            .write_str("real content")?;
+        dir.child("README.md").write_str("About my journal")?;
+        dir.child("2021-08-23-draft-for-later.md")
+            .write_str("not yet")?;
+
+        let journal = Journal::new_at(dir.path()).ignoring(&["*-draft-*".to_string()])?;
+
+        assert_eq!(
+            journal.entries()?,
+            vec![dir.path().join("2021-07-03-older_entry.md")]
+        );
+        assert!(Journal::new_at(dir.path())
+            .ignoring(&["[".to_string()])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ignores_paths_relative_to_the_journal() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("work/2021-07-03-standup.md").write_str("kept")?;
+        dir.child("work/2021-07-04-retro.md").write_str("ignored")?;
+        dir.child("drafts/2021-07-05-idea.md")
+            .write_str("ignored")?;
+        dir.child("drafts/old/2021-07-06-idea.md")
+            .write_str("ignored")?;
+
+        let journal = Journal::new_at(dir.path())
+            .ignoring(&["work/*-retro.md".to_string(), "drafts/*".to_string()])?;
+
+        assert_eq!(
+            journal.entries()?,
+            vec![dir.path().join("work/2021-07-03-standup.md")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reads_entries_with_any_date_stamp_in_order() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("12-03-2022-planning.md").write_str("third")?;
+        dir.child("20220311-retro.md").write_str("second")?;
+        dir.child("2022-03-10-standup.md").write_str("first")?;
+
+        let journal = Journal::new_at(dir.path());
+        let entries = journal.entries()?;
+
+        assert_eq!(
+            entries,
+            vec![
+                dir.path().join("2022-03-10-standup.md"),
+                dir.path().join("20220311-retro.md"),
+                dir.path().join("12-03-2022-planning.md"),
+            ]
+        );
+        assert_eq!(
+            journal.entries_on(time::macros::date!(2022 - 03 - 12))?,
+            vec![dir.path().join("12-03-2022-planning.md")]
+        );
+        assert_eq!(title_from_filename(Path::new("20220311-retro.md")), "retro");
+        Ok(())
+    }
+
+    #[test]
+    fn finds_the_latest_entry_by_its_date() -> Result<()> {
+        let dir = TempDir::new()?;
+        dir.child("01-04-2022-standup.md").write_str("newest")?;
+        dir.child("2022-03-31-planning.md").write_str("older")?;
+        dir.child("zzz-ideas.md").write_str("not an entry")?;
+        dir.child("2022-03-31-retro.md")
+            .write_str("later that day")?;
+
+        let journal = Journal::new_at(dir.path());
+        assert_eq!(journal.latest_entry()?.unwrap().markdown, "newest");
+        assert_eq!(
+            journal
+                .entry_for(time::macros::date!(2022 - 03 - 31))?
+                .unwrap()
+                .markdown,
+            "later that day"
+        );
+        assert!(journal
+            .entry_for(time::macros::date!(2022 - 03 - 30))?
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn restamps_filenames() {
+        let date = time::macros::date!(2022 - 03 - 11);
+        assert_eq!(
+            DateStamp::DayMonthYear.filename(date, "retro"),
+            "11-03-2022-retro.md"
+        );
+        assert_eq!(
+            restamped(Path::new("/j/2022-03-11-retro.md"), DateStamp::Compact),
+            Some(PathBuf::from("/j/20220311-retro.md"))
+        );
+        assert_eq!(
+            restamped(Path::new("/j/20220311-retro.md"), DateStamp::Compact),
+            None
+        );
+    }
+
+    #[test]
+    fn keeps_entries_in_folders_following_the_filename() -> Result<()> {
+        let date = time::macros::date!(2022 - 03 - 11);
+        let stamp = DateStamp::YearMonthDay;
+        assert_eq!(
+            stamp.path(Some("{year}/{month}/{date}-{slug}.md"), date, "retro")?,
+            "2022/03/2022-03-11-retro.md"
+        );
+        assert!(stamp.path(Some("{slug}.md"), date, "retro").is_err());
+        assert!(stamp
+            .path(Some("{date}/{title}.md"), date, "retro")
+            .is_err());
+        assert!(stamp.path(Some("../{date}.md"), date, "retro").is_err());
+        assert!(stamp.path(Some("{slug}-{date}.md"), date, "retro").is_err());
+        assert!(stamp
+            .path(Some("{month}/{year}/{day}.md"), date, "retro")
+            .is_err());
+        assert_eq!(
+            stamp.path(Some("{year}-{month}-{day}-{slug}.md"), date, "retro")?,
+            "2022-03-11-retro.md"
+        );
+
+        let dir = TempDir::new()?;
+        let journal = Journal::new_at(dir.path());
+        for pattern in ["{date}/{slug}.md", "{year}/{month}/{day}.md"] {
+            journal.add_entry(&stamp.path(Some(pattern), date, "retro")?, "# Retro")?;
+        }
+        dir.child(".git/2022-03-12-not-an-entry.md").write_str("")?;
+        dir.child("2022-03-10-standup.md").write_str("# Standup")?;
+
+        assert_eq!(
+            journal.entries()?,
+            vec![
+                dir.path().join("2022-03-10-standup.md"),
+                dir.path().join("2022/03/11.md"),
+                dir.path().join("2022-03-11/retro.md"),
+            ]
+        );
+        assert_eq!(journal.entries_on(date)?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn links_relative_to_the_linking_file() {
+        let journal = Path::new("/journal");
+        assert_eq!(
+            link(&journal.join("projects"), &journal.join("2022/03/11.md")),
+            "../2022/03/11.md"
+        );
+        assert_eq!(
+            link(&journal.join("2022/03"), &journal.join("2022/02/28.md")),
+            "../02/28.md"
+        );
+        assert_eq!(
+            link(journal, &journal.join("2022-03-11-retro.md")),
+            "2022-03-11-retro.md"
+        );
+    }
+
+    #[test]
+    fn reads_the_title_from_the_heading() {
+        let entry = Entry {
+            path: PathBuf::from("2022-03-11-through-the-looking-glass.md"),
+            markdown: "# Through the Looking-Glass on 2022-03-11\n\n## Notes\n".to_string(),
+        };
+        assert_eq!(entry.title(), "Through the Looking-Glass");
+
+        let renamed = Entry {
+            path: PathBuf::from("2022-03-11-retro.md"),
+            markdown: "Some notes\n# Retro on Mars\n".to_string(),
+        };
+        assert_eq!(renamed.title(), "Retro on Mars");
+
+        let spelled_out = Entry {
+            path: PathBuf::from("2022-03-11-retro.md"),
+            markdown: "# Retro on Friday, 11 March 2022
+"
+            .to_string(),
+        };
+        assert_eq!(spelled_out.title(), "Retro");
+    }
+
+    #[test]
+    fn reads_the_front_matter() -> Result<()> {
+        let front_matter = FrontMatter {
+            title: Some("Retro".to_string()),
+            date: Some(time::macros::date!(2022 - 03 - 11)),
+            tags: vec!["team".to_string()],
+            template: None,
+        };
+        let entry = Entry {
+            path: PathBuf::from("notes/retro.md"),
+            markdown: front_matter.render()? + "# Retro on 2022-03-11\n\n## Notes\n",
+        };
+
+        assert_eq!(entry.front_matter(), Some(front_matter));
+        assert_eq!(entry.date(), Some(time::macros::date!(2022 - 03 - 11)));
+        assert_eq!(entry.title(), "Retro");
+        assert_eq!(entry.tags(), vec!["team"]);
+        assert_eq!(entry.body(), "# Retro on 2022-03-11\n\n## Notes\n");
+
+        let plain = Entry {
+            path: PathBuf::from("2022-03-11-retro.md"),
+            markdown: "---\n\nA thematic break, not front matter\n".to_string(),
+        };
+        assert_eq!(plain.front_matter(), None);
+        assert_eq!(plain.body(), plain.markdown);
+        Ok(())
+    }
+
+    #[test]
+    fn falls_back_to_the_filename_for_legacy_entries() {
+        let entry = Entry {
+            path: PathBuf::from("2022-03-11-through-the-looking-glass.md"),
+            markdown: "## TODOs\n".to_string(),
+        };
+
+        assert_eq!(entry.title(), "through the looking glass");
+    }
+
+    #[test]
+    fn finds_entries_for_a_specific_date() {
+        let dir = TempDir::new().unwrap();
+        dir.child("2021-07-03-older_entry.md")
+            .write_str("older content")
+            .unwrap();
+        dir.child("2021-08-23-first_entry.md")
+            .write_str("first content")
+            .unwrap();
+
+        let journal = Journal::new_at(dir.path());
+
+        let entries = journal
+            .entries_on(time::macros::date!(2021 - 07 - 03))
+            .unwrap();
+
+        assert_eq!(entries, vec![dir.path().join("2021-07-03-older_entry.md")]);
+    }
 }
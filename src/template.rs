@@ -1,9 +1,210 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 use anyhow::Result;
+use handlebars::Handlebars;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
 use time::{format_description, Date};
 
 use crate::config::{default_order, SectionName};
+use crate::helpers;
+use crate::locale::Locale;
+use crate::status::Statuses;
+
+/// Puts the title, date, and tags of each new entry into YAML front matter at its top,
+/// for other tools that read markdown files like static site generators
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FrontMatterConfig {
+    /// Tags every new entry starts out with
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A kind of entry besides the daily one, like meeting notes or a retro, created with `journal new --template <name>`
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntryTemplate {
+    /// The only sections this kind of entry has, in this order. All enabled ones if left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sections: Option<Vec<SectionName>>,
+    /// Text that always goes at the end, e.g. the questions to go through in a retro
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    /// Lays out this kind of entry instead of the `entry_template` for all of them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry_template: Option<String>,
+}
+
+impl EntryTemplate {
+    pub fn sections(&self) -> Option<&[SectionName]> {
+        self.sections.as_deref()
+    }
+
+    pub fn has_section(&self, section: &SectionName) -> bool {
+        match self.sections {
+            Some(ref sections) => sections.contains(section),
+            None => true,
+        }
+    }
+
+    pub fn content(&self) -> Option<&str> {
+        self.content.as_deref()
+    }
+
+    pub fn entry_template(&self) -> Option<&str> {
+        self.entry_template.as_deref()
+    }
+}
+
+/// A rendered entry along with where each of its sections starts
+pub struct Rendered {
+    pub content: String,
+    lines: HashMap<SectionName, usize>,
+}
+
+impl Rendered {
+    /// The line, counting from 1, on which `section` starts
+    pub fn line_of(&self, section: &SectionName) -> Option<usize> {
+        self.lines.get(section).copied()
+    }
+}
+
+/// How the `{{date}}` in the heading of new entries is written, as a `time` format description like
+/// `[weekday], [day padding:none] [month repr:long] [year]`. It is checked when the configuration is read.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct DateFormat(String);
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        DateFormat("[year]-[month]-[day]".to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for DateFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let format = String::deserialize(deserializer)?;
+        format_description::parse(&format).map_err(|e| {
+            serde::de::Error::custom(format!("'{}' is not a date format: {}", format, e))
+        })?;
+        Ok(DateFormat(format))
+    }
+}
+
+impl DateFormat {
+    /// Writes `date` with the month and weekday names of `locale`
+    pub fn format(&self, date: Date, locale: Locale) -> Result<String> {
+        let format = format_description::parse(&self.0)?;
+        Ok(locale.translate_date(&date.format(&format)?))
+    }
+}
+
+/// The markdown heading a section starts with, like `### Aufgaben`. Written as a heading of level 2 or below,
+/// since the entry's own title is the only one of level 1. It is checked when the configuration is read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heading {
+    level: usize,
+    text: String,
+}
+
+impl Heading {
+    /// The `## TODOs` the TODO section starts with unless configured otherwise
+    pub fn todos() -> Self {
+        Heading {
+            level: 2,
+            text: "TODOs".to_string(),
+        }
+    }
+
+    /// The `## Notes` notes are added to unless configured otherwise
+    pub fn notes() -> Self {
+        Heading {
+            level: 2,
+            text: "Notes".to_string(),
+        }
+    }
+
+    /// The `## Scratchpad` that is carried over unless configured otherwise
+    pub fn scratchpad() -> Self {
+        Heading {
+            level: 2,
+            text: "Scratchpad".to_string(),
+        }
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the first heading in a `rendered` section with this one
+    pub fn retitle(&self, rendered: &str) -> String {
+        let mut retitled = false;
+        rendered
+            .split_inclusive('\n')
+            .map(|line| match line.starts_with('#') && !retitled {
+                true => {
+                    retitled = true;
+                    let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                    format!("{}{}", self, ending)
+                }
+                false => line.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Heading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", "#".repeat(self.level), self.text)
+    }
+}
+
+impl FromStr for Heading {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim_start_matches('#');
+        let level = s.len() - text.len();
+        match text.strip_prefix(' ').map(str::trim) {
+            Some(text) if (2..=6).contains(&level) && !text.is_empty() => Ok(Heading {
+                level,
+                text: text.to_string(),
+            }),
+            _ => Err(format!(
+                "'{}' is not a markdown heading like '## TODOs' or '### Aufgaben'",
+                s
+            )),
+        }
+    }
+}
+
+impl Serialize for Heading {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Heading {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// How a whole entry is laid out around its sections
+pub struct Layout<'a> {
+    pub entry_template: &'a str,
+    pub date_format: &'a DateFormat,
+    pub locale: Locale,
+}
 
 pub struct Template {
     pub title: String,
@@ -11,37 +212,111 @@ pub struct Template {
     pub sections: HashMap<SectionName, String>,
 }
 
+/// How an entry is laid out unless `entry_template` is configured: the title with the date, followed by every section
+pub const DEFAULT_ENTRY_TEMPLATE: &str = "# {{title}} on {{date}}\n\n{{sections}}";
+
 impl Template {
     pub fn render(self, order: Vec<SectionName>) -> Result<String> {
-        let year_month_day = format_description::parse("[year]-[month]-[day]").unwrap();
+        let handlebars = helpers::handlebars(&Statuses::default(), false, self.today);
+        let layout = Layout {
+            entry_template: DEFAULT_ENTRY_TEMPLATE,
+            date_format: &DateFormat::default(),
+            locale: Locale::default(),
+        };
+        self.render_with_lines(order, &handlebars, &layout)
+            .map(|rendered| rendered.content)
+    }
 
+    /// Renders the `entry_template` of the `layout` with the `title`, the `date`, all `sections` in `order`,
+    /// and each `section` on its own, like `{{section.todos}}`
+    pub fn render_with_lines(
+        self,
+        order: Vec<SectionName>,
+        handlebars: &Handlebars,
+        layout: &Layout,
+    ) -> Result<Rendered> {
         let Template {
             title,
             today,
             sections,
         } = self;
 
-        let today = today.format(&year_month_day)?;
-
-        let order = expand_with_defaults(order);
-
-        let mut to_be_printed = vec![format!("# {title} on {today}")];
+        let today = layout.date_format.format(today, layout.locale)?;
+
+        let all: Vec<&str> = expand_with_defaults(order)
+            .iter()
+            .filter_map(|section| sections.get(section))
+            .map(String::as_str)
+            .collect();
+
+        let content = handlebars.render_template(
+            layout.entry_template,
+            &json!({
+                "title": title,
+                "date": today,
+                "sections": all.join("\n\n"),
+                "section": sections,
+            }),
+        )?;
+
+        // Some templates start with blank lines of their own, so look for where their text starts
+        let lines = sections
+            .iter()
+            .filter_map(|(section, section_content)| {
+                let text = section_content.trim_start_matches('\n');
+                let start = content.find(text).filter(|_| !text.is_empty())?;
+                Some((section.clone(), content[..start].matches('\n').count() + 1))
+            })
+            .collect();
+
+        Ok(Rendered { content, lines })
+    }
+}
 
-        for section in &order {
-            if let Some(content) = sections.get(section) {
-                to_be_printed.push(content.to_string());
-            };
+/// Adds the `## Sections` of `rendered` that `existing` doesn't have yet to its end
+pub fn add_missing_sections(existing: &str, rendered: &str) -> String {
+    let headings: Vec<&str> = existing
+        .lines()
+        .filter(|line| is_section_heading(line))
+        .map(str::trim_end)
+        .collect();
+
+    let mut sections: Vec<Vec<&str>> = Vec::new();
+    for line in rendered.lines() {
+        if line.starts_with("# ") || is_section_heading(line) {
+            sections.push(Vec::new());
         }
+        if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
 
-        Ok(to_be_printed.join("\n\n"))
+    let mut updated = existing.trim_end().to_string();
+    for section in sections {
+        match section.first() {
+            Some(heading)
+                if is_section_heading(heading) && !headings.contains(&heading.trim_end()) =>
+            {
+                updated.push_str("\n\n");
+                updated.push_str(section.join("\n").trim_end());
+            }
+            _ => {}
+        }
     }
+    updated.push('\n');
+    updated
+}
+
+/// Whether `line` starts a section, which can have a `Heading` of any level but the title's
+fn is_section_heading(line: &str) -> bool {
+    line.parse::<Heading>().is_ok()
 }
 
 fn expand_with_defaults(mut order: Vec<SectionName>) -> Vec<SectionName> {
     let mut df = default_order();
 
     for section in &order {
-        df = df.into_iter().filter(|s| s != section).collect();
+        df.retain(|s| s != section);
     }
 
     order.extend(df);
@@ -55,6 +330,46 @@ mod tests {
     use pretty_assertions::assert_eq;
     use time::macros::date;
 
+    #[test]
+    fn adds_only_the_missing_sections() {
+        let existing = indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## Notes
+
+            Busy day.
+            "#};
+        let rendered = indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## Notes
+
+            > This is where your notes will go!
+
+            ## TODOs
+
+            * [ ] Call the bank
+
+            "#};
+
+        let updated = add_missing_sections(existing, rendered);
+        assert_eq!(
+            updated,
+            indoc! {r#"
+            # Planning on 2022-03-11
+
+            ## Notes
+
+            Busy day.
+
+            ## TODOs
+
+            * [ ] Call the bank
+            "#}
+        );
+        assert_eq!(add_missing_sections(&updated, rendered), updated);
+    }
+
     #[test]
     fn title_and_todos_for_today() -> Result<()> {
         let template = Template {
@@ -101,6 +416,86 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn knows_on_which_line_each_section_starts() -> Result<()> {
+        let template = Template {
+            title: "Some title".to_string(),
+            today: date!(2021 - 12 - 24),
+            sections: maplit::hashmap! {
+                SectionName::Notes => "## Notes\n\n> This is where your notes will go!\n".to_string(),
+                SectionName::Reminders => "\n## Your reminders for today:\n* [ ] Call mum\n".to_string(),
+            },
+        };
+
+        let handlebars = helpers::handlebars(&Statuses::default(), false, date!(2021 - 12 - 24));
+        let rendered = template.render_with_lines(
+            vec![SectionName::Notes],
+            &handlebars,
+            &Layout {
+                entry_template: DEFAULT_ENTRY_TEMPLATE,
+                date_format: &DateFormat::default(),
+                locale: Locale::En,
+            },
+        )?;
+        let lines = rendered.content.lines().collect::<Vec<_>>();
+
+        let notes = rendered.line_of(&SectionName::Notes).unwrap();
+        assert_eq!(lines[notes - 1], "## Notes");
+
+        let reminders = rendered.line_of(&SectionName::Reminders).unwrap();
+        assert_eq!(lines[reminders - 1], "## Your reminders for today:");
+
+        assert_eq!(rendered.line_of(&SectionName::Todos), None);
+        Ok(())
+    }
+
+    #[test]
+    fn lays_out_the_entry_with_its_own_template() -> Result<()> {
+        let template = Template {
+            title: "Retro".to_string(),
+            today: date!(2021 - 12 - 24),
+            sections: maplit::hashmap! {
+                SectionName::Notes => "## Notes\n\n> This is where your notes will go!\n".to_string(),
+                SectionName::Todos => "## TODOs\n\n* [ ] a todo\n".to_string(),
+            },
+        };
+        let entry_template = indoc! {r#"
+            {{section.todos}}
+            ---
+            *{{title}}*, written on {{date}}
+
+            {{section.notes}}"#};
+
+        let rendered = template.render_with_lines(
+            vec![],
+            &helpers::handlebars(&Statuses::default(), false, date!(2021 - 12 - 24)),
+            &Layout {
+                entry_template,
+                date_format: &serde_yaml::from_str(
+                    "'[weekday], [day padding:none]. [month repr:long] [year]'",
+                )?,
+                locale: Locale::De,
+            },
+        )?;
+        assert_eq!(
+            rendered.content,
+            indoc! {r#"
+            ## TODOs
+
+            * [ ] a todo
+
+            ---
+            *Retro*, written on Freitag, 24. Dezember 2021
+
+            ## Notes
+
+            > This is where your notes will go!
+            "#}
+        );
+        assert_eq!(rendered.line_of(&SectionName::Notes), Some(8));
+        Ok(())
+    }
+
     #[test]
     fn title_todos_and_prs_for_today() -> Result<()> {
         let template = Template {
@@ -214,4 +609,19 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn retitles_sections_with_their_configured_heading() {
+        let heading: Heading = "### Aufgaben".parse().unwrap();
+        assert_eq!(heading.to_string(), "### Aufgaben");
+        assert_eq!(
+            heading.retitle("\n## TODOs\n* [ ] a todo\n### not this one\n"),
+            "\n### Aufgaben\n* [ ] a todo\n### not this one\n"
+        );
+
+        assert!("# Title".parse::<Heading>().is_err());
+        assert!("####### Too deep".parse::<Heading>().is_err());
+        assert!("##Notes".parse::<Heading>().is_err());
+        assert!("## ".parse::<Heading>().is_err());
+    }
 }
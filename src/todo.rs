@@ -1,14 +1,23 @@
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::StructOpt;
 use handlebars::Handlebars;
 use indoc::indoc;
-use pulldown_cmark::{Event, HeadingLevel::H2, Options, Parser, Tag};
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use time::{Date, Duration, Weekday};
 use tracing::Level;
 
 use crate::config::Section;
-use crate::storage::Journal;
+use crate::edit::DryRun;
+use crate::reminders::{ReminderConfig, Reminders, SpecificDate};
+use crate::storage::{link, write_atomically, DateStamp, Entry, Journal, YEAR_MONTH_DAY};
+use crate::template::Heading;
+use crate::{Clock, Config};
 
 const TODO: &str = indoc! {r#"
 ## TODOs
@@ -17,46 +26,702 @@ const TODO: &str = indoc! {r#"
 {{/each}}
 "#};
 
+const TODO_BY_TAG: &str = indoc! {r#"
+## TODOs
+{{#each groups as |group| }}
+{{#if group.tag}}
+
+**#{{group.tag}}**
+
+{{/if}}
+{{#each group.todos as |todo| }}
+{{~todo~}}
+{{/each}}
+{{/each}}
+"#};
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct TodoConfig {
     template: Option<String>,
+    /// How many of the most recent entries open TODOs are collected from, so skipping a day
+    /// or writing an entry without TODOs doesn't lose them
+    #[serde(default = "default_look_back_entries")]
+    look_back_entries: usize,
+    /// Ignores entries older than this many days when looking back
+    look_back_days: Option<i64>,
+    /// Markers like `!high` or `(A)` in the order carried over TODOs are sorted in.
+    /// `unmarked` stands for the TODOs without any of them, which otherwise come last.
+    #[serde(default = "default_priorities")]
+    priorities: Vec<String>,
+    /// Carry over open TODOs beneath a checked off one on their own instead of dropping them
+    #[serde(default)]
+    promote_orphans: bool,
+    /// Only carry over TODOs with one of these `#tags`, if there are any
+    #[serde(default)]
+    include_tags: Vec<String>,
+    /// Never carry over TODOs with any of these `#tags`
+    #[serde(default)]
+    exclude_tags: Vec<String>,
+    /// Put the TODOs under their first `#tag`, the ones without any first
+    #[serde(default)]
+    group_by_tag: bool,
+    /// Add a link to the entry a TODO was first written down in when carrying it over
+    #[serde(default)]
+    link_origin: bool,
+    /// TODOs that are added on certain days of the week, like `Friday: [Submit the timesheet]`
+    #[serde(default)]
+    routine: HashMap<Weekday, Vec<String>>,
+    /// A file in the journal that checked off TODOs are added to before they are dropped,
+    /// as JSON if it ends in `.json` and as markdown otherwise
+    archive: Option<PathBuf>,
+    /// The reminders to take one-off reminders from, including shared ones, set from the reminders configuration.
+    /// See `one_offs_as_todos`.
+    #[serde(skip)]
+    pub(crate) reminders: Option<ReminderConfig>,
+    /// The heading of the TODO section, set from the `headings` configuration
+    #[serde(skip, default = "Heading::todos")]
+    pub(crate) heading: Heading,
+    /// How new entries are named, set from the `date_stamp` configuration to link back from them
+    #[serde(skip)]
+    pub(crate) date_stamp: DateStamp,
+    /// Where new entries go, set from the `filename` configuration to link back from them
+    #[serde(skip)]
+    pub(crate) filename: Option<String>,
 }
 
 impl Default for TodoConfig {
     fn default() -> Self {
         Self {
             template: Some(TODO.to_string()),
+            look_back_entries: default_look_back_entries(),
+            look_back_days: None,
+            priorities: default_priorities(),
+            promote_orphans: false,
+            include_tags: Vec::new(),
+            exclude_tags: Vec::new(),
+            group_by_tag: false,
+            link_origin: false,
+            routine: HashMap::new(),
+            archive: None,
+            reminders: None,
+            heading: Heading::todos(),
+            date_stamp: DateStamp::default(),
+            filename: None,
         }
     }
 }
 
+/// Stands for the TODOs without a marker in `priorities`
+const UNMARKED: &str = "unmarked";
+
+fn default_priorities() -> Vec<String> {
+    ["!high", "(A)", "(B)", "(C)", UNMARKED, "!low"]
+        .iter()
+        .map(|marker| marker.to_string())
+        .collect()
+}
+
+impl TodoConfig {
+    /// Whether the tags of `text` allow it to be carried over
+    fn carries(&self, text: &str) -> bool {
+        let tags = tags(text);
+        let has_any = |wanted: &[String]| {
+            wanted
+                .iter()
+                .any(|tag| tags.contains(&tag.trim_start_matches('#').to_lowercase()))
+        };
+
+        (self.include_tags.is_empty() || has_any(&self.include_tags))
+            && !has_any(&self.exclude_tags)
+    }
+
+    /// What `text` is compared by to find the same TODO in several entries,
+    /// ignoring case, spacing, trailing punctuation, priority markers, and the link to where it came from
+    fn key(&self, text: &str) -> String {
+        let text = match text.rfind(" ([from ") {
+            Some(link) if text.ends_with("))") => &text[..link],
+            _ => text,
+        };
+        text.split_whitespace()
+            .filter(|word| !self.priorities.iter().any(|marker| marker == word))
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_end_matches(['.', '!', '?'])
+            .to_lowercase()
+    }
+
+    /// Where `todo` goes among the others, the lower the more important
+    fn rank(&self, todo: &str) -> usize {
+        let text = todo_text(todo);
+        let marker = self
+            .priorities
+            .iter()
+            .position(|marker| text.split_whitespace().any(|word| word == marker));
+
+        marker
+            .or_else(|| self.priorities.iter().position(|marker| marker == UNMARKED))
+            .unwrap_or(self.priorities.len())
+    }
+}
+
+fn default_look_back_entries() -> usize {
+    1
+}
+
 #[async_trait::async_trait]
 impl Section for TodoConfig {
-    async fn render(&self, journal: &Journal, _: &dyn crate::Clock) -> Result<String> {
-        let todos = match journal.latest_entry() {
-            Ok(None) => Vec::new(),
-            Ok(Some(last_entry)) => {
-                let mut finder = FindTodos::new();
-                finder.process(&last_entry.markdown)
+    async fn render(
+        &self,
+        journal: &Journal,
+        clock: &dyn crate::Clock,
+        handlebars: &Handlebars<'static>,
+    ) -> Result<String> {
+        let cutoff = self
+            .look_back_days
+            .map(|days| clock.today() - Duration::days(days));
+
+        let mut todos: Vec<String> = Vec::new();
+        let mut loose = false;
+        // Anything that shows up in a newer entry, even checked off, shadows the same TODO in older ones
+        let mut seen = HashSet::new();
+        // What today's entries already had, so a routine TODO checked off today doesn't come back
+        let mut seen_today = HashSet::new();
+        let mut carried = HashSet::new();
+        // Entries after the day this one is for, when backfilling, are not carried back
+        let entries = journal.entries_until(clock.today())?;
+        // Links to where a TODO came from are relative to the new entry, which may be in a folder of its own
+        let here = match self.link_origin {
+            true => journal
+                .child_file(
+                    self.date_stamp
+                        .path(self.filename.as_deref(), clock.today(), "new")?,
+                )
+                .parent()
+                .map(Path::to_path_buf),
+            false => None,
+        };
+        for (index, path) in entries
+            .into_iter()
+            .rev()
+            .take(self.look_back_entries)
+            .enumerate()
+        {
+            let entry = journal.read_entry(path)?;
+            if index > 0 && cutoff.is_some_and(|cutoff| entry.date() < Some(cutoff)) {
+                break;
+            }
+
+            let mut finder = FindTodos::new()
+                .under(&self.heading)
+                .promoting_orphans(self.promote_orphans);
+            let mut open = finder.process(&entry.markdown);
+            if !finder.found_todo_section() && self.heading != Heading::todos() {
+                // Entries written before the heading was renamed still have the default one
+                finder = FindTodos::new().promoting_orphans(self.promote_orphans);
+                open = finder.process(&entry.markdown);
+            }
+            loose |= finder.is_loose();
+            if index == 0 && !finder.found_todo_section() {
+                tracing::warn!("No TODO section in {:?}", entry.path);
+                eprintln!(
+                    "Warning: couldn't find '{}' in {} entry",
+                    self.heading,
+                    entry.describe()
+                );
+            }
+
+            let completed = finder.completed_todo_ranges(&entry.markdown);
+            let mut shadowing = completed
+                .into_iter()
+                .map(|range| self.key(&todo_text(&entry.markdown[range])))
+                .collect::<Vec<_>>();
+            for todo in open {
+                let text = self.key(&todo_text(&todo));
+                let wanted = self.carries(&todo_text(&todo));
+                if wanted && !seen.contains(&text) && carried.insert(text.clone()) {
+                    todos.push(match here {
+                        Some(ref here) => with_origin(&todo, &entry, here),
+                        None => todo,
+                    });
+                }
+                shadowing.push(text);
+            }
+            if entry.date() == Some(clock.today()) {
+                seen_today.extend(shadowing.iter().cloned());
             }
-            Err(e) => return Err(anyhow::anyhow!(e)),
+            seen.extend(shadowing);
+        }
+
+        let routine = self.routine.get(&clock.today().weekday());
+        for item in routine.into_iter().flatten() {
+            let key = self.key(item);
+            if !seen_today.contains(&key) && carried.insert(key) {
+                todos.push(format!("* [ ] {}\n", item));
+            }
+        }
+        todos.sort_by_key(|todo| self.rank(todo));
+
+        if let Some(ref reminders) = self.reminders {
+            let reminders = reminders.load_with_shared(journal).await?;
+            for reminder in reminders.one_offs_for_today(clock) {
+                // Creating a second entry on the same day would otherwise add them twice
+                if carried.insert(self.key(&reminder)) {
+                    todos.push(format!("* [ ] {}\n", reminder));
+                }
+            }
+        }
+
+        let mut groups = match self.group_by_tag {
+            true => by_tag(todos),
+            false => vec![Group { tag: None, todos }],
         };
 
+        // Keep the spacing of the entry they came from, no matter which one ended up last
+        if loose {
+            for group in &mut groups {
+                if let Some((_, rest)) = group.todos.split_last_mut() {
+                    rest.iter_mut().for_each(|todo| todo.push('\n'));
+                }
+            }
+        }
+
         #[derive(Serialize)]
         struct C {
             todos: Vec<String>,
+            groups: Vec<Group>,
         }
 
-        let template = self.template.clone().unwrap_or_else(|| TODO.to_string());
+        let template = match self.template {
+            Some(ref template) if !self.group_by_tag || template != TODO => template.clone(),
+            _ if self.group_by_tag => TODO_BY_TAG.to_string(),
+            _ => TODO.to_string(),
+        };
+        let todos = groups
+            .iter()
+            .flat_map(|group| group.todos.iter().cloned())
+            .collect();
 
-        let mut tt = Handlebars::new();
+        let mut tt = handlebars.clone();
         tt.register_template_string("todos", template)?;
-        tt.register_escape_fn(handlebars::no_escape);
-        tt.render("todos", &C { todos })
+        tt.render("todos", &C { todos, groups })
             .map_err(|e| anyhow::anyhow!(e))
     }
 }
 
+/// Links `todo` to `entry` from an entry in `here`, unless it already links to the entry it came from originally
+fn with_origin(todo: &str, entry: &Entry, here: &Path) -> String {
+    let (first_line, rest) = todo.split_once('\n').unwrap_or((todo, ""));
+    let origin = Regex::new(r" \(\[from ([^\]]+)\]\(([^)]+)\)\)").unwrap();
+    let there = entry.path.parent().unwrap_or(here);
+
+    // An origin from further back is relative to the entry it was carried over from
+    let mut linked = match origin.is_match(first_line) {
+        true => origin
+            .replace(first_line, |captures: &regex::Captures| {
+                format!(
+                    " ([from {}]({}))",
+                    &captures[1],
+                    link(here, &there.join(&captures[2]))
+                )
+            })
+            .to_string(),
+        false => format!(
+            "{} ([from {}]({}))",
+            first_line,
+            entry.describe(),
+            link(here, &entry.path)
+        ),
+    };
+    if todo.contains('\n') {
+        linked.push('\n');
+        linked.push_str(rest);
+    }
+    linked
+}
+
+/// TODOs that share their first tag
+#[derive(Debug, Serialize)]
+struct Group {
+    tag: Option<String>,
+    todos: Vec<String>,
+}
+
+/// The `#tags` in `text`, in lowercase
+fn tags(text: &str) -> Vec<String> {
+    let tag = Regex::new(r"(?:^|\s)#([\w-]+)").unwrap();
+    tag.captures_iter(text)
+        .map(|captures| captures[1].to_lowercase())
+        .collect()
+}
+
+/// Groups `todos` by their first tag, the untagged ones first and the rest by the name of their tag
+fn by_tag(todos: Vec<String>) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for todo in todos {
+        let tag = tags(&todo_text(&todo)).into_iter().next();
+        match groups.iter_mut().find(|group| group.tag == tag) {
+            Some(group) => group.todos.push(todo),
+            None => groups.push(Group {
+                tag,
+                todos: vec![todo],
+            }),
+        }
+    }
+    groups.sort_by(|a, b| a.tag.cmp(&b.tag));
+    groups
+}
+
+/// A TODO that was checked off, as it is kept in the archive
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Done {
+    date: Date,
+    todo: String,
+}
+
+/// Adds the TODOs checked off in the latest entry to the archive, since the next entry won't have them.
+/// Ones that are already in it, e.g. from creating a second entry on the same day, aren't added again.
+pub(crate) fn archive_completed(config: &TodoConfig, journal: &Journal) -> Result<()> {
+    let archive = match config.archive {
+        Some(ref archive) => journal.child_file(archive),
+        None => return Ok(()),
+    };
+    let entry = match journal.latest_entry()? {
+        Some(entry) => entry,
+        None => return Ok(()),
+    };
+    let date = match entry.date() {
+        Some(date) => date,
+        None => return Ok(()),
+    };
+
+    let done = FindTodos::new()
+        .under(&config.heading)
+        .completed_todo_ranges(&entry.markdown)
+        .into_iter()
+        .map(|range| Done {
+            date,
+            todo: todo_text(&entry.markdown[range]),
+        })
+        .collect::<Vec<_>>();
+    if done.is_empty() {
+        return Ok(());
+    }
+
+    let existing = match std::fs::read_to_string(&archive) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Could not read {:?}", archive)),
+    };
+    let updated = if archive.extension().is_some_and(|ext| ext == "json") {
+        add_to_json_archive(&existing, done)?
+    } else {
+        add_to_markdown_archive(&existing, done)
+    };
+
+    write_atomically(&archive, updated.as_bytes())
+        .with_context(|| format!("Could not write the archive {:?}", archive))
+}
+
+fn add_to_json_archive(existing: &str, done: Vec<Done>) -> Result<String> {
+    let mut archived: Vec<Done> = match existing.trim() {
+        "" => Vec::new(),
+        existing => serde_json::from_str(existing).context("The archive is not valid JSON")?,
+    };
+    for todo in done {
+        if !archived.contains(&todo) {
+            archived.push(todo);
+        }
+    }
+    Ok(serde_json::to_string_pretty(&archived)?)
+}
+
+fn add_to_markdown_archive(existing: &str, done: Vec<Done>) -> String {
+    let mut archived = match existing {
+        "" => "# Done\n\n".to_string(),
+        existing if existing.ends_with('\n') => existing.to_string(),
+        existing => format!("{}\n", existing),
+    };
+    for Done { date, todo } in done {
+        let line = format!("* {}: {}", date.format(YEAR_MONTH_DAY).unwrap(), todo);
+        if !archived.lines().any(|archived| archived == line) {
+            archived.push_str(&line);
+            archived.push('\n');
+        }
+    }
+    archived
+}
+
+#[derive(Debug, StructOpt)]
+pub enum TodoCmd {
+    /// Show the open TODOs of the latest entry with their numbers
+    List,
+    /// Check off open TODOs in the latest entry
+    Done {
+        /// The numbers of the open TODOs, counting from 1
+        #[clap(required = true)]
+        nrs: Vec<usize>,
+        #[clap(flatten)]
+        dry_run: DryRun,
+    },
+    /// Add an open TODO to the latest entry without opening the editor
+    Add {
+        /// What needs to be done
+        text: String,
+        #[clap(flatten)]
+        dry_run: DryRun,
+    },
+    /// Turn an open TODO from the latest entry into a reminder
+    Promote {
+        /// The number of the open TODO, counting from 1
+        nr: usize,
+        /// Promote the TODO to a reminder
+        #[clap(long)]
+        reminder: bool,
+        /// When to be reminded, like `Monday` or `20.Mar`
+        #[clap(long = "on")]
+        on_date: String,
+        /// Keep the TODO in the entry, marked as delegated, instead of removing it
+        #[clap(long)]
+        mark_delegated: bool,
+        #[clap(flatten)]
+        dry_run: DryRun,
+    },
+}
+
+impl TodoCmd {
+    pub(crate) fn execute(
+        self,
+        config: &Config,
+        journal: &Journal,
+        clock: &dyn Clock,
+    ) -> Result<()> {
+        let heading = config.todo_heading();
+        match self {
+            TodoCmd::List => {
+                let entry = match journal.latest_entry()? {
+                    Some(entry) => entry,
+                    None => bail!("There are no entries with TODOs yet"),
+                };
+
+                let ranges = FindTodos::new()
+                    .under(&heading)
+                    .open_todo_ranges(&entry.markdown);
+                if ranges.is_empty() {
+                    println!("Nothing left to do in the {} entry", entry.describe());
+                }
+                for (nr, range) in (1..).zip(ranges) {
+                    println!("{:>3}. {}", nr, todo_text(&entry.markdown[range]));
+                }
+            }
+            TodoCmd::Done { nrs, dry_run } => {
+                let entry = match journal.latest_entry()? {
+                    Some(entry) => entry,
+                    None => bail!("There are no entries with TODOs yet"),
+                };
+
+                let ranges = FindTodos::new()
+                    .under(&heading)
+                    .open_todo_ranges(&entry.markdown);
+                let updated = check_off(&entry.markdown, &nrs, &heading)?;
+                let changed = dry_run.apply(&entry.path, &entry.markdown, &updated)?;
+                if !dry_run.is_dry_run() {
+                    for nr in nrs {
+                        let range = ranges[nr - 1].clone();
+                        println!("Checked off '{}'", todo_text(&entry.markdown[range]));
+                    }
+                }
+
+                dry_run.finish(changed as usize)?;
+            }
+            TodoCmd::Add { text, dry_run } => {
+                let entry = match journal.latest_entry()? {
+                    Some(entry) => entry,
+                    None => bail!("There is no entry to add the TODO to yet"),
+                };
+
+                let updated = append_todos(&entry.markdown, std::slice::from_ref(&text), &heading);
+                let changed = dry_run.apply(&entry.path, &entry.markdown, &updated)?;
+                if !dry_run.is_dry_run() {
+                    println!("Added '{}' to the {} entry", text, entry.describe());
+                }
+
+                dry_run.finish(changed as usize)?;
+            }
+            TodoCmd::Promote {
+                nr,
+                reminder,
+                on_date,
+                mark_delegated,
+                dry_run,
+            } => {
+                if !reminder {
+                    bail!("TODOs can only be promoted to a --reminder for now");
+                }
+
+                let entry = match journal.latest_entry()? {
+                    Some(entry) => entry,
+                    None => bail!("There are no entries with TODOs yet"),
+                };
+
+                let ranges = FindTodos::new()
+                    .under(&heading)
+                    .open_todo_ranges(&entry.markdown);
+                let range = match nr.checked_sub(1).and_then(|index| ranges.get(index)) {
+                    Some(range) => range.clone(),
+                    None => bail!(
+                        "There is no open TODO number {} in the {} entry",
+                        nr,
+                        entry.describe()
+                    ),
+                };
+
+                let text = todo_text(&entry.markdown[range.clone()]);
+                let date = SpecificDate::parse(&on_date, config.locale)
+                    .map_err(|e| anyhow!(e))?
+                    .next_date(clock.today());
+
+                let mut updated = entry.markdown.clone();
+                if mark_delegated {
+                    let marked = delegated(&entry.markdown[range.clone()], date)?;
+                    updated.replace_range(range, &marked);
+                } else {
+                    updated.replace_range(range, "");
+                }
+
+                // Save the reminder first so a broken reminders file doesn't lose the TODO
+                if !dry_run.is_dry_run() {
+                    let location = config.reminders_file();
+                    let mut reminders = Reminders::load(&location)?;
+                    reminders.on_date(date, &text);
+                    reminders
+                        .save(&location)
+                        .context("Failed to save reminders")?;
+                }
+
+                let changed = dry_run.apply(&entry.path, &entry.markdown, &updated)?;
+                if !dry_run.is_dry_run() {
+                    println!(
+                        "Promoted '{}' to a reminder on {}",
+                        text,
+                        date.format(YEAR_MONTH_DAY)?
+                    );
+                }
+
+                dry_run.finish(changed as usize)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks off the open TODOs beneath `heading` with the given numbers, counting from 1
+pub(crate) fn check_off(markdown: &str, nrs: &[usize], heading: &Heading) -> Result<String> {
+    let ranges = FindTodos::new().under(heading).open_todo_ranges(markdown);
+    let mut done = Vec::new();
+    for nr in nrs {
+        match nr.checked_sub(1).and_then(|index| ranges.get(index)) {
+            Some(range) => done.push(range.clone()),
+            None => bail!("There is no open TODO number {}", nr),
+        }
+    }
+
+    // Going from the back keeps the earlier ranges valid
+    let mut updated = markdown.to_string();
+    done.sort_by_key(|range| std::cmp::Reverse(range.start));
+    done.dedup();
+    for range in done {
+        let checked = updated[range.clone()].replacen("[ ]", "[x]", 1);
+        updated.replace_range(range, &checked);
+    }
+    Ok(updated)
+}
+
+/// Adds `items` as open TODOs at the end of the section under `heading`, adding the section if there is none
+pub(crate) fn append_todos(markdown: &str, items: &[String], heading: &Heading) -> String {
+    if items.is_empty() {
+        return markdown.to_string();
+    }
+    let new_todos = items
+        .iter()
+        .map(|item| format!("* [ ] {}", item))
+        .collect::<Vec<_>>();
+
+    let mut lines = markdown.lines().collect::<Vec<_>>();
+    let section = match lines
+        .iter()
+        .position(|line| line.parse::<Heading>().as_ref() == Ok(heading))
+    {
+        Some(section) => section,
+        None => {
+            let mut updated = markdown.trim_end().to_string();
+            updated.push_str(&format!("\n\n{}\n\n", heading));
+            updated.push_str(&new_todos.join("\n"));
+            updated.push('\n');
+            return updated;
+        }
+    };
+
+    let next_section = lines[section + 1..]
+        .iter()
+        .position(|line| line.starts_with('#'))
+        .map_or(lines.len(), |offset| section + 1 + offset);
+    let last_line = (section..next_section)
+        .rev()
+        .find(|index| !lines[*index].trim().is_empty())
+        .unwrap_or(section);
+
+    let mut insert_at = last_line + 1;
+    if last_line == section {
+        lines.insert(insert_at, "");
+        insert_at += 1;
+    }
+    for (offset, todo) in new_todos.iter().enumerate() {
+        lines.insert(insert_at + offset, todo);
+    }
+
+    let mut updated = lines.join("\n");
+    if markdown.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated
+}
+
+/// The text of a TODO item without its bullet or number, checkbox, or sub-items
+pub(crate) fn todo_text(item: &str) -> String {
+    let first_line = item.lines().next().unwrap_or_default().trim_start();
+    let without_bullet = first_line
+        .trim_start_matches(['*', '-', '+'])
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim_start_matches(['.', ')'])
+        .trim_start();
+
+    ["[ ]", "[x]", "[X]"]
+        .iter()
+        .find_map(|checkbox| without_bullet.strip_prefix(checkbox))
+        .unwrap_or(without_bullet)
+        .trim()
+        .to_string()
+}
+
+/// Marks the item with `[>]` so it's no longer an open TODO, but it's clear where it went
+fn delegated(item: &str, date: Date) -> Result<String> {
+    let (first_line, rest) = item.split_once('\n').unwrap_or((item, ""));
+    let marked = first_line.replacen("[ ]", "[>]", 1);
+
+    let mut delegated = format!(
+        "{} (reminder on {})",
+        marked.trim_end(),
+        date.format(YEAR_MONTH_DAY)?
+    );
+    if item.contains('\n') {
+        delegated.push('\n');
+        delegated.push_str(rest);
+    }
+    Ok(delegated)
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum State {
     Initial,
@@ -66,32 +731,58 @@ enum State {
 
 pub(crate) struct FindTodos {
     state: State,
+    heading: Heading,
+    found_section: bool,
+    loose: bool,
+    promote_orphans: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 enum TodoHeader {
     NotFound,
     Found,
-    ProcessedTitle,
 }
 
 impl FindTodos {
     pub(crate) fn new() -> Self {
         FindTodos {
             state: State::Initial,
+            heading: Heading::todos(),
+            found_section: false,
+            loose: false,
+            promote_orphans: false,
         }
     }
 
-    fn gather_open_todos<'a>(
+    /// Looks for the TODOs beneath `heading` instead of `## TODOs`
+    pub(crate) fn under(mut self, heading: &Heading) -> Self {
+        self.heading = heading.clone();
+        self
+    }
+
+    /// Also finds open TODOs beneath checked off ones, as if they were top-level TODOs
+    pub(crate) fn promoting_orphans(mut self, promote: bool) -> Self {
+        self.promote_orphans = promote;
+        self
+    }
+
+    /// Whether the last processed markdown had a TODO section at all
+    pub(crate) fn found_todo_section(&self) -> bool {
+        self.found_section
+    }
+
+    fn gather_todos<'a>(
         &mut self,
         parser: &mut impl Iterator<Item = (Event<'a>, Range<usize>)>,
+        completed: bool,
     ) -> Vec<Range<usize>> {
-        let mut found_top_level_item = false;
-        let mut range_of_todo_item = None;
-        let mut depth = 0;
+        // The items we're in, from the top-level one down, and whether each is checked off.
+        // Items without a checkbox of their own are not TODOs, even if some of their children are.
+        let mut items: Vec<(Range<usize>, Option<bool>)> = Vec::new();
         let mut todos = Vec::new();
 
         for (event, range) in parser {
+            let depth = items.len();
             let span = tracing::span!(Level::INFO, "processing_todos", ?event, ?depth);
             let _entered = span.enter();
             match event {
@@ -100,28 +791,29 @@ impl FindTodos {
                     self.state = State::Done;
                     break;
                 }
-                Event::Start(Tag::Item) if depth == 0 => {
-                    tracing::info!("Found the beginning of a top-level item");
-                    depth += 1;
-                    found_top_level_item = true;
-                    range_of_todo_item = Some(range);
-                }
                 Event::Start(Tag::Item) => {
-                    depth += 1;
                     tracing::info!("Beginning of an item");
+                    items.push((range, None));
                 }
                 Event::End(Tag::Item) => {
-                    depth -= 1;
                     tracing::info!("End of an item");
+                    items.pop();
                 }
-                Event::TaskListMarker(done) if found_top_level_item => {
+                Event::TaskListMarker(done) if depth > 0 => {
                     tracing::info!("Found a TODO item.");
-                    found_top_level_item = false;
-                    if done {
-                        tracing::info!("Skipping completed TODO");
+                    let parents_done = items[..depth - 1]
+                        .iter()
+                        .all(|(_, checked)| *checked == Some(true));
+                    let (range, checked) = items.last_mut().unwrap();
+                    *checked = Some(done);
+
+                    let top_level = depth == 1;
+                    let orphan = self.promote_orphans && !top_level && parents_done && !done;
+                    if done == completed && (top_level || orphan) {
+                        tracing::info!("Storing TODO item");
+                        todos.push(range.clone());
                     } else {
-                        tracing::info!("Storing incomplete TODO item");
-                        todos.push(range_of_todo_item.take().unwrap());
+                        tracing::info!("Skipping TODO");
                     }
                 }
                 _ => {
@@ -133,33 +825,130 @@ impl FindTodos {
         todos
     }
 
+    /// Whether the open TODOs in the last processed markdown were separated by empty lines
+    pub(crate) fn is_loose(&self) -> bool {
+        self.loose
+    }
+
+    /// The open top-level TODOs along with their open sub-items, ready to be carried over.
+    /// Each one ends in a single newline, regardless of what followed it in `markdown`.
     pub fn process(&mut self, markdown: &str) -> Vec<String> {
+        let ranges = self.open_todo_ranges(markdown);
+        let list_item = Regex::new(r"^\s*([*+-]|\d+[.)])\s").unwrap();
+        // The last item of a list has an empty line after it too, so only ones followed by another item tell
+        self.loose = ranges.iter().any(|todo| {
+            let next_line = markdown[todo.end..]
+                .lines()
+                .find(|line| !line.trim().is_empty());
+            let followed_by_item = next_line.is_some_and(|line| list_item.is_match(line));
+            markdown[todo.clone()].ends_with("\n\n") && followed_by_item
+        });
+
+        ranges
+            .into_iter()
+            .map(|todo| {
+                // Promoted TODOs were indented along with everything beneath them
+                let line_start = markdown[..todo.start]
+                    .rfind('\n')
+                    .map_or(0, |n| n + 1)
+                    .max(markdown[..todo.start].trim_end_matches(' ').len());
+                let item = markdown[line_start..todo.end].trim_start_matches(' ');
+                let item = dedent(item, todo.end - line_start - item.len());
+                let mut todo = without_completed_sub_items(&item).trim_end().to_string();
+                todo.push('\n');
+                todo
+            })
+            .collect::<Vec<_>>()
+    }
+
+    /// Where in `markdown` the open top-level TODOs are
+    pub(crate) fn open_todo_ranges(&mut self, markdown: &str) -> Vec<Range<usize>> {
+        self.todo_ranges(markdown, false)
+    }
+
+    /// Where in `markdown` the completed top-level TODOs are
+    pub(crate) fn completed_todo_ranges(&mut self, markdown: &str) -> Vec<Range<usize>> {
+        self.todo_ranges(markdown, true)
+    }
+
+    fn todo_ranges(&mut self, markdown: &str, completed: bool) -> Vec<Range<usize>> {
         let mut options = Options::empty();
         options.insert(Options::ENABLE_TASKLISTS);
         let mut parser = Parser::new_ext(markdown, options);
 
-        let found = find_todo_section(&mut parser);
+        let found = find_todo_section(&mut parser, &self.heading);
+        self.found_section = found;
 
-        let todo_text = Vec::new();
         if !found {
             self.state = State::Done;
-            return todo_text;
+            return Vec::new();
         }
 
         let mut parser = parser.into_offset_iter();
         self.state = State::GettingTodos;
 
-        let ranges = self.gather_open_todos(&mut parser);
+        self.gather_todos(&mut parser, completed)
+    }
+}
 
-        ranges
-            .into_iter()
-            .map(|todo| markdown[todo].to_string())
-            .collect::<Vec<_>>()
+/// Removes up to `width` spaces from the start of every line but the first
+fn dedent(item: &str, width: usize) -> String {
+    item.split_inclusive('\n')
+        .enumerate()
+        .map(|(index, line)| match index {
+            0 => line,
+            _ => {
+                let spaces = line.len() - line.trim_start_matches(' ').len();
+                &line[spaces.min(width)..]
+            }
+        })
+        .collect()
+}
+
+/// Removes the checked sub-items of `item`, along with anything nested beneath them.
+/// The remaining lines keep their indentation so the structure survives being carried over.
+fn without_completed_sub_items(item: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut items: Vec<Range<usize>> = Vec::new();
+    let mut completed: Vec<Range<usize>> = Vec::new();
+    for (event, range) in Parser::new_ext(item, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Item) => items.push(range),
+            Event::End(Tag::Item) => {
+                items.pop();
+            }
+            Event::TaskListMarker(true) if items.len() > 1 => {
+                let sub_item = items.last().unwrap().clone();
+                if !completed.iter().any(|outer| outer.end >= sub_item.end) {
+                    completed.push(sub_item);
+                }
+            }
+            _ => {}
+        }
     }
+
+    let mut remaining = item.to_string();
+    for range in completed.into_iter().rev() {
+        // Take whole lines so neither the indentation nor an empty line is left behind
+        let start = item[..range.start]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1);
+        let end = match item[..range.end].ends_with('\n') {
+            true => range.end,
+            false => item[range.end..]
+                .find('\n')
+                .map_or(item.len(), |newline| range.end + newline + 1),
+        };
+        remaining.replace_range(start..end, "");
+    }
+    remaining
 }
 
-fn find_todo_section<'a>(parser: &mut impl Iterator<Item = Event<'a>>) -> bool {
+fn find_todo_section<'a>(parser: &mut impl Iterator<Item = Event<'a>>, heading: &Heading) -> bool {
     let mut todo_header = TodoHeader::NotFound;
+    let mut title = String::new();
 
     for event in parser {
         let span = tracing::span!(
@@ -171,16 +960,18 @@ fn find_todo_section<'a>(parser: &mut impl Iterator<Item = Event<'a>>) -> bool {
         let _entered = span.enter();
 
         match (&event, &todo_header) {
-            (Event::Start(Tag::Heading(H2, _, _)), _) => {
+            (Event::Start(Tag::Heading(level, _, _)), _) if *level as usize == heading.level() => {
                 todo_header = TodoHeader::Found;
+                title.clear();
             }
-            (Event::Text(ref text), TodoHeader::Found) => {
-                if text.to_string() == "TODOs" {
-                    todo_header = TodoHeader::ProcessedTitle;
+            (Event::Text(text) | Event::Code(text), TodoHeader::Found) => title.push_str(text),
+            (Event::End(Tag::Heading(_, _, _)), TodoHeader::Found) => {
+                if title.trim() == heading.text() {
                     tracing::info!("Found a TODO header");
+                    return true;
                 }
+                todo_header = TodoHeader::NotFound;
             }
-            (Event::End(Tag::Heading(H2, _, _)), TodoHeader::ProcessedTitle) => return true,
             _ => {
                 tracing::trace!("Ignoring event");
             }
@@ -192,10 +983,564 @@ fn find_todo_section<'a>(parser: &mut impl Iterator<Item = Event<'a>>) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{FindTodos, State};
+    use super::{append_todos, check_off, delegated, todo_text, FindTodos, State};
+    use crate::template::Heading;
+    use crate::Clock;
     use indoc::indoc;
+    use time::macros::date;
     use tracing_test::traced_test;
 
+    #[tokio::test]
+    async fn one_off_reminders_become_todos() -> anyhow::Result<()> {
+        use crate::config::Section;
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022-03-11-something.md").write_str(indoc! {r#"
+            # Something on 2022-03-11
+
+            ## TODOs
+
+            * [ ] Send the figures
+            "#})?;
+        dir.child("reminders.json").write_str(
+            r#"{"stored": [
+                {"concrete": ["2022-03-11", "Send the figures"]},
+                {"concrete": ["2022-03-11", "Call the bank"]},
+                {"recurring": {"start": "2022-03-01", "interval": {"weekday": "Friday"}, "reminder": "Timesheet"}}
+            ]}"#,
+        )?;
+
+        let journal = Journal::new_at(dir.path());
+        let clock = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 11)?;
+        let config = super::TodoConfig {
+            reminders: Some(crate::reminders::ReminderConfig {
+                file: "reminders.json".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let rendered = config
+            .render(
+                &journal,
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await?;
+
+        assert_eq!(
+            rendered,
+            indoc! {r#"
+            ## TODOs
+            * [ ] Send the figures
+            * [ ] Call the bank
+            "#}
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn collects_open_todos_from_recent_entries() -> anyhow::Result<()> {
+        use crate::config::Section;
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022-03-01-long-ago.md")
+            .write_str("## TODOs\n\n* [ ] Renew the passport\n")?;
+        dir.child("2022-03-09-monday.md").write_str(indoc! {r#"
+            ## TODOs
+
+            * [ ] Send the figures
+            * [ ] Call the bank
+            "#})?;
+        dir.child("2022-03-10-tuesday.md").write_str(indoc! {r#"
+            ## TODOs
+
+            * [x] Call the bank
+            * [ ] Send the figures
+            "#})?;
+        dir.child("2022-03-11-written-by-hand.md")
+            .write_str("# Offsite\n\nNo TODOs today.\n")?;
+
+        let journal = Journal::new_at(dir.path());
+        let clock = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 12)?;
+        let config: super::TodoConfig = serde_yaml::from_str(indoc! {r#"
+            template: "{{#each todos}}{{this}}{{/each}}"
+            look_back_entries: 4
+            look_back_days: 7
+            "#})?;
+
+        let rendered = config
+            .render(
+                &journal,
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await?;
+
+        assert_eq!(rendered, "* [ ] Send the figures\n");
+        Ok(())
+    }
+
+    async fn carried_over(entry: &str) -> anyhow::Result<String> {
+        use crate::config::Section;
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022-03-11-friday.md").write_str(entry)?;
+        let clock = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 12)?;
+
+        super::TodoConfig::default()
+            .render(
+                &Journal::new_at(dir.path()),
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn keeps_the_spacing_of_the_carried_todos() -> anyhow::Result<()> {
+        let tight = indoc! {r#"
+            ## TODOs
+
+            * [ ] Send the figures
+            * [x] Book the venue
+            * [ ] Call the bank !high
+              about the mortgage"#};
+        assert_eq!(
+            carried_over(tight).await?,
+            indoc! {r#"
+            ## TODOs
+            * [ ] Call the bank !high
+              about the mortgage
+            * [ ] Send the figures
+            "#}
+        );
+
+        let loose = indoc! {r#"
+            ## TODOs
+
+            * [ ] Send the figures
+                * [ ] to Jessica
+
+            * [x] Book the venue
+
+            * [ ] Call the bank !high
+
+
+            ## Notes
+            "#};
+        assert_eq!(
+            carried_over(loose).await?,
+            indoc! {r#"
+            ## TODOs
+            * [ ] Call the bank !high
+
+            * [ ] Send the figures
+                * [ ] to Jessica
+            "#}
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn adds_routine_todos_on_their_weekday() -> anyhow::Result<()> {
+        use crate::config::Section;
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022-03-10-thursday.md").write_str(indoc! {r#"
+            ## TODOs
+
+            * [ ] Water the plants
+            "#})?;
+        let journal = Journal::new_at(dir.path());
+        let config: super::TodoConfig = serde_yaml::from_str(indoc! {r#"
+            template: "{{#each todos}}{{this}}{{/each}}"
+            routine:
+              Friday: [Submit the timesheet, Water the plants]
+              Monday: [Plan the week]
+            "#})?;
+        let friday = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 11)?;
+        let handlebars = crate::helpers::handlebars(&Default::default(), false, friday.today());
+        assert_eq!(
+            config.render(&journal, &friday, &handlebars).await?,
+            "* [ ] Water the plants\n* [ ] Submit the timesheet\n"
+        );
+
+        dir.child("2022-03-11-friday.md").write_str(indoc! {r#"
+            ## TODOs
+
+            * [ ] Water the plants
+            * [x] Submit the timesheet
+            "#})?;
+        assert_eq!(
+            config.render(&journal, &friday, &handlebars).await?,
+            "* [ ] Water the plants\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn the_same_todo_is_only_carried_over_once() -> anyhow::Result<()> {
+        use crate::config::Section;
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022-03-09-wednesday.md").write_str(indoc! {r#"
+            ## TODOs
+
+            * [ ] Send the figures to Jessica.
+            * [ ] Call the bank
+            "#})?;
+        dir.child("2022-03-10-by-hand.md").write_str(indoc! {r#"
+            ## TODOs
+
+            * [ ] send the  figures to Jessica
+            * [ ] Call the bank !high
+            * [ ] Call the bank
+            "#})?;
+        let journal = Journal::new_at(dir.path());
+        let clock = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 11)?;
+        let config: super::TodoConfig = serde_yaml::from_str(indoc! {r#"
+            template: "{{#each todos}}{{this}}{{/each}}"
+            look_back_entries: 2
+            "#})?;
+
+        let rendered = config
+            .render(
+                &journal,
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await?;
+
+        assert_eq!(
+            rendered,
+            "* [ ] Call the bank !high\n* [ ] send the  figures to Jessica\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn groups_the_carried_todos_by_tag() -> anyhow::Result<()> {
+        use crate::config::Section;
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022-03-11-friday.md").write_str(indoc! {r#"
+            ## TODOs
+
+            * [ ] Review the #Apollo design
+            * [ ] Water the plants
+            * [ ] Renew the passport #private
+            * [ ] Book the #zeus offsite
+            * [ ] Ship #apollo
+            "#})?;
+        let journal = Journal::new_at(dir.path());
+        let clock = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 12)?;
+        let config: super::TodoConfig = serde_yaml::from_str(indoc! {r#"
+            exclude_tags: [private]
+            group_by_tag: true
+            "#})?;
+
+        let rendered = config
+            .render(
+                &journal,
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await?;
+
+        assert_eq!(
+            rendered,
+            indoc! {r#"
+            ## TODOs
+            * [ ] Water the plants
+
+            **#apollo**
+
+            * [ ] Review the #Apollo design
+            * [ ] Ship #apollo
+
+            **#zeus**
+
+            * [ ] Book the #zeus offsite
+            "#}
+        );
+
+        // The groups don't make the list look loose the next day
+        let mut finder = FindTodos::new();
+        finder.process(&rendered);
+        assert!(!finder.is_loose());
+
+        let config: super::TodoConfig = serde_yaml::from_str("include_tags: [zeus]")?;
+        assert!(config.carries("Book the #zeus offsite"));
+        assert!(!config.carries("Water the plants"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn links_carried_todos_to_where_they_came_from() -> anyhow::Result<()> {
+        use crate::config::Section;
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022-03-10-thursday.md").write_str(indoc! {r#"
+            ## TODOs
+
+            * [ ] Send the figures ([from 2022-03-01](2022-03-01-standup.md))
+            * [ ] Call the bank
+                * about the mortgage
+            "#})?;
+        let journal = Journal::new_at(dir.path());
+        let clock = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 11)?;
+        let config: super::TodoConfig = serde_yaml::from_str(indoc! {r#"
+            template: "{{#each todos}}{{this}}{{/each}}"
+            link_origin: true
+            "#})?;
+
+        let rendered = config
+            .render(
+                &journal,
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await?;
+
+        assert_eq!(
+            rendered,
+            indoc! {r#"
+            * [ ] Send the figures ([from 2022-03-01](2022-03-01-standup.md))
+            * [ ] Call the bank ([from 2022-03-10](2022-03-10-thursday.md))
+                * about the mortgage
+            "#}
+        );
+        assert_eq!(
+            config.key("Send the figures ([from 2022-03-01](2022-03-01-standup.md))"),
+            "send the figures"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn links_origins_relative_to_the_new_entry() -> anyhow::Result<()> {
+        use crate::config::Section;
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022/02/2022-02-28-monday.md")
+            .write_str(indoc! {r#"
+            ## TODOs
+
+            * [ ] Send the figures ([from 2022-02-01](../01/2022-02-01-standup.md))
+            * [ ] Call the bank
+            "#})?;
+        let journal = Journal::new_at(dir.path());
+        let clock = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 1)?;
+        let config = super::TodoConfig {
+            template: Some("{{#each todos}}{{this}}{{/each}}".to_string()),
+            link_origin: true,
+            filename: Some("{year}/{month}/{date}-{slug}.md".to_string()),
+            ..Default::default()
+        };
+
+        let rendered = config
+            .render(
+                &journal,
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await?;
+
+        assert_eq!(
+            rendered,
+            indoc! {r#"
+            * [ ] Send the figures ([from 2022-02-01](../01/2022-02-01-standup.md))
+            * [ ] Call the bank ([from 2022-02-28](../02/2022-02-28-monday.md))
+            "#}
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sorts_todos_by_their_priority_marker() -> anyhow::Result<()> {
+        let config = super::TodoConfig::default();
+        let mut todos = vec![
+            "* [ ] Water the plants !low\n",
+            "* [ ] Send the figures\n",
+            "* [ ] (B) Book the venue\n",
+            "* [ ] Renew the certificate !high\n",
+            "* [ ] (A) Call the bank\n",
+        ];
+        todos.sort_by_key(|todo| config.rank(todo));
+
+        assert_eq!(
+            todos,
+            vec![
+                "* [ ] Renew the certificate !high\n",
+                "* [ ] (A) Call the bank\n",
+                "* [ ] (B) Book the venue\n",
+                "* [ ] Send the figures\n",
+                "* [ ] Water the plants !low\n",
+            ]
+        );
+
+        let config: super::TodoConfig = serde_yaml::from_str("priorities: [\"!low\"]")?;
+        assert_eq!(config.rank("* [ ] Water the plants !low"), 0);
+        assert_eq!(config.rank("* [ ] (A) Call the bank"), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn adds_a_todo_section_when_there_is_none() {
+        let entry = "# Planning on 2022-03-11\n\n## Notes\n";
+
+        assert_eq!(
+            append_todos(entry, &["Follow up".to_string()], &Heading::todos()),
+            "# Planning on 2022-03-11\n\n## Notes\n\n## TODOs\n\n* [ ] Follow up\n"
+        );
+    }
+
+    #[test]
+    fn checks_off_todos_by_their_number() {
+        let markdown = "## TODOs\n\n* [ ] first\n* [x] second\n* [ ] third\n";
+
+        assert_eq!(
+            check_off(markdown, &[2], &Heading::todos()).unwrap(),
+            "## TODOs\n\n* [ ] first\n* [x] second\n* [x] third\n"
+        );
+        assert!(check_off(markdown, &[3], &Heading::todos()).is_err());
+        assert!(check_off(markdown, &[0], &Heading::todos()).is_err());
+    }
+
+    #[test]
+    fn finds_todos_beneath_a_renamed_heading() -> anyhow::Result<()> {
+        let heading: Heading = "### Aufgaben".parse().map_err(anyhow::Error::msg)?;
+        let markdown = "## TODOs\n\n* [ ] old\n\n### Aufgaben\n\n* [ ] first\n\n## Notes\n";
+
+        let mut parser = FindTodos::new().under(&heading);
+        assert_eq!(parser.process(markdown), vec!["* [ ] first\n"]);
+        assert!(parser.found_todo_section());
+        assert_eq!(
+            append_todos(markdown, &["second".to_string()], &heading),
+            "## TODOs\n\n* [ ] old\n\n### Aufgaben\n\n* [ ] first\n* [ ] second\n\n## Notes\n"
+        );
+        assert!(!FindTodos::new()
+            .under(&heading)
+            .process("## Aufgaben\n\n* [ ] first\n")
+            .iter()
+            .any(|todo| todo.contains("first")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn carries_todos_over_a_renamed_heading() -> anyhow::Result<()> {
+        use crate::config::Section;
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022-03-11-friday.md")
+            .write_str("# Friday\n\n## TODOs\n\n* [ ] Call the bank\n* [x] Pay rent\n")?;
+        let clock = crate::controlled_clock::ControlledClock::new(2022, time::Month::March, 12)?;
+        let config = super::TodoConfig {
+            heading: "### Aufgaben".parse().map_err(anyhow::Error::msg)?,
+            ..Default::default()
+        };
+
+        let rendered = config
+            .render(
+                &Journal::new_at(dir.path()),
+                &clock,
+                &crate::helpers::handlebars(&Default::default(), false, clock.today()),
+            )
+            .await?;
+
+        assert_eq!(rendered, "## TODOs\n* [ ] Call the bank\n");
+        Ok(())
+    }
+
+    #[test]
+    fn archives_completed_todos_once() -> anyhow::Result<()> {
+        use crate::storage::Journal;
+        use assert_fs::{prelude::*, TempDir};
+
+        let dir = TempDir::new()?;
+        dir.child("2022-03-11-friday.md").write_str(indoc! {r#"
+            ## TODOs
+
+            * [x] Send the figures
+            * [ ] Call the bank
+            * [x] Book the venue
+            "#})?;
+        let journal = Journal::new_at(dir.path());
+
+        let config: super::TodoConfig = serde_yaml::from_str("archive: done.md")?;
+        super::archive_completed(&config, &journal)?;
+        super::archive_completed(&config, &journal)?;
+        dir.child("done.md").assert(indoc! {r#"
+            # Done
+
+            * 2022-03-11: Send the figures
+            * 2022-03-11: Book the venue
+            "#});
+
+        let config: super::TodoConfig = serde_yaml::from_str("archive: done.json")?;
+        super::archive_completed(&config, &journal)?;
+        super::archive_completed(&config, &journal)?;
+        let archived: Vec<super::Done> =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join("done.json"))?)?;
+        assert_eq!(
+            archived,
+            vec![
+                super::Done {
+                    date: date!(2022 - 03 - 11),
+                    todo: "Send the figures".to_string()
+                },
+                super::Done {
+                    date: date!(2022 - 03 - 11),
+                    todo: "Book the venue".to_string()
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn promoted_todos_keep_only_their_text() {
+        assert_eq!(
+            todo_text("* [ ] Send the figures\n    * to Jessica\n"),
+            "Send the figures"
+        );
+        assert_eq!(todo_text("- [ ] Book the venue"), "Book the venue");
+    }
+
+    #[test]
+    fn delegated_todos_are_no_longer_open() {
+        let item = "* [ ] Send the figures\n    * [ ] to Jessica\n";
+        let marked = delegated(item, date!(2022 - 03 - 20)).unwrap();
+
+        assert_eq!(
+            marked,
+            "* [>] Send the figures (reminder on 2022-03-20)\n    * [ ] to Jessica\n"
+        );
+
+        let markdown = format!("## TODOs\n\n{}", marked);
+        assert!(FindTodos::new().process(&markdown).is_empty());
+    }
+
     #[test]
     #[traced_test]
     fn there_were_no_todos() {
@@ -209,6 +1554,7 @@ mod tests {
 
         assert_eq!(parser.state, State::Done);
         assert_eq!(found_todos.len(), 0);
+        assert!(!parser.found_todo_section());
     }
 
     #[test]
@@ -226,6 +1572,7 @@ mod tests {
         parser.process(markdown);
 
         assert_eq!(parser.state, State::GettingTodos,);
+        assert!(parser.found_todo_section());
     }
 
     #[test]
@@ -353,6 +1700,79 @@ mod tests {
         assert_eq!(found_todos.len(), 2);
     }
 
+    #[test]
+    fn carries_open_sub_items_with_their_parent() {
+        let markdown = indoc! {r#"
+                ## TODOs
+
+                * [ ] Prepare the offsite
+                    * [x] Book the venue
+                        * [ ] Ask about parking
+                    * [ ] Send the invites
+                        * [ ] to the team
+                    * Bring snacks
+                * [ ] Call the bank
+
+                ## Notes
+                "#};
+
+        assert_eq!(
+            FindTodos::new().process(markdown),
+            vec![
+                indoc! {r#"
+                * [ ] Prepare the offsite
+                    * [ ] Send the invites
+                        * [ ] to the team
+                    * Bring snacks
+                "#},
+                "* [ ] Call the bank\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_todos_in_numbered_lists() {
+        let markdown = indoc! {r#"
+                ## TODOs
+
+                1. [ ] first
+                2. [x] second
+                3) [ ] third
+                "#};
+
+        let found_todos = FindTodos::new().process(markdown);
+        let texts = found_todos
+            .iter()
+            .map(|todo| todo_text(todo))
+            .collect::<Vec<_>>();
+
+        assert_eq!(texts, vec!["first", "third"]);
+    }
+
+    #[test]
+    fn promotes_open_todos_beneath_a_completed_one() {
+        let markdown = indoc! {r#"
+                ## TODOs
+
+                * [x] Prepare the offsite
+                    * [ ] Send the invites
+                        * [ ] to the team
+                        * [x] to Jessica
+                    * [x] Book the venue
+                    * Bring snacks
+                * [ ] Call the bank
+                "#};
+
+        assert_eq!(
+            FindTodos::new().promoting_orphans(true).process(markdown),
+            vec![
+                "* [ ] Send the invites\n    * [ ] to the team\n",
+                "* [ ] Call the bank\n",
+            ]
+        );
+        assert_eq!(FindTodos::new().process(markdown).len(), 1);
+    }
+
     #[test]
     #[traced_test]
     fn ignores_normal_bullet_lists_within_completed_ones() {